@@ -0,0 +1,69 @@
+//! The 14 allergens that must be declared on menus in the EU (regulation 1169/2011).
+
+use poc_fnb::{Plate, PlateComponent};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub enum Allergen {
+    Gluten,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Peanuts,
+    Soybeans,
+    Milk,
+    Nuts,
+    Celery,
+    Mustard,
+    Sesame,
+    Sulphites,
+    Lupin,
+    Molluscs,
+}
+
+impl Allergen {
+    pub const ALL: [Self; 14] = [
+        Self::Gluten,
+        Self::Crustaceans,
+        Self::Eggs,
+        Self::Fish,
+        Self::Peanuts,
+        Self::Soybeans,
+        Self::Milk,
+        Self::Nuts,
+        Self::Celery,
+        Self::Mustard,
+        Self::Sesame,
+        Self::Sulphites,
+        Self::Lupin,
+        Self::Molluscs,
+    ];
+}
+
+/// Allergens of every ingredient of `plate`, sub-recipes included.
+///
+/// `ingredient_allergens` maps ingredient names to their allergens, since `Ingredient`
+/// has no such field.
+pub fn plate_allergens(
+    plate: &Plate,
+    ingredient_allergens: &BTreeMap<String, BTreeSet<Allergen>>,
+) -> BTreeSet<Allergen> {
+    let mut allergens = BTreeSet::new();
+    for component in &plate.components {
+        match component {
+            PlateComponent::Ingredient { ingredient, .. } => {
+                if let Some(found) = ingredient_allergens.get(&ingredient.borrow().name) {
+                    allergens.extend(found);
+                }
+            }
+            PlateComponent::Plate {
+                plate: sub_plate, ..
+            } => {
+                allergens.extend(plate_allergens(&sub_plate.borrow(), ingredient_allergens));
+            }
+        }
+    }
+    allergens
+}
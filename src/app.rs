@@ -1,7 +1,113 @@
 use poc_fnb::{Ingredient, IngredientType, Plate, PlateComponent, QuantityUnit, Supplier};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 
+use crate::allergen::Allergen;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::app_storage::AppStorage;
+use crate::app_storage::CatalogStore as _;
+use crate::catalog::{Catalog, CatalogSettings, copy_ingredient};
+use crate::currency::Currency;
+use crate::history::{Edit, History};
+use crate::i18n::{self, Language, tr, trf};
+use crate::inventory::ReceivingReport;
+use crate::locale;
+use crate::persistence::CatalogFile;
+
+mod alerts;
+mod cost_charts;
+mod cost_tree;
+mod deletion;
+mod document;
+mod exchange_rates;
+mod file_dialog;
+mod ingredient_editor;
+mod ingredient_table;
+mod list_filter;
+mod plate_card;
+mod production;
+mod receiving;
+mod recipe_card;
+mod recipe_editor;
+mod recipe_method;
+mod substitution;
+mod valuation;
+mod virtual_list;
+mod waste;
+
+use deletion::PendingDeletion;
+use document::FileAction;
+use exchange_rates::ExchangeRateForm;
+use file_dialog::PickedFile;
+use ingredient_editor::IngredientEditForm;
+use ingredient_table::IngredientTable;
+use list_filter::{IngredientFilter, PlateFilter};
+
+use receiving::ReceivingForm;
+use recipe_editor::RecipeEditForm;
+use substitution::SubstitutionForm;
+use waste::WasteForm;
+
+/// The saved catalog: from the app database on desktop, from eframe storage on the web.
+#[cfg(not(target_arch = "wasm32"))]
+fn load_catalog(_storage: &dyn eframe::Storage) -> Result<Option<CatalogFile>, String> {
+    AppStorage::locate()?.load_catalog()
+}
+
+/// The saved catalog: from the app database on desktop, from eframe storage on the web.
+#[cfg(target_arch = "wasm32")]
+fn load_catalog(storage: &dyn eframe::Storage) -> Result<Option<CatalogFile>, String> {
+    storage.load_catalog()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_catalog(_storage: &mut dyn eframe::Storage, catalog: &CatalogFile) -> Result<(), String> {
+    AppStorage::locate()?.save_catalog(catalog)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_catalog(storage: &mut dyn eframe::Storage, catalog: &CatalogFile) -> Result<(), String> {
+    storage.save_catalog(catalog)
+}
+
+const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+
+const REDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::Z,
+);
+
+/// Format of the dates typed in the forms.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn parse_date(text: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(text.trim(), DATE_FORMAT).ok()
+}
+
+fn today_text() -> String {
+    chrono::Local::now()
+        .date_naive()
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+/// `egui::DragValue` shown and typed in the number format of the current language.
+fn number_input(value: &mut f64) -> egui::DragValue<'_> {
+    egui::DragValue::new(value)
+        .custom_formatter(|value, decimals| {
+            locale::localize_decimal(&egui::emath::format_with_decimals_in_range(value, decimals))
+        })
+        .custom_parser(locale::parse_number)
+}
+
+/// `number_input` of an amount, with the currency symbol where the language puts it.
+fn money_input(value: &mut f64, currency: Currency) -> egui::DragValue<'_> {
+    let (prefix, suffix) = locale::money_affixes(currency);
+    number_input(value).prefix(prefix).suffix(suffix)
+}
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -12,19 +118,50 @@ pub struct TemplateApp {
     #[serde(skip)] // This how you opt-out of serialization of a field
     value: f32,
 
-    // F&B Management data (skipped from serialization for now due to complexity)
+    /// Language of the interface, applied with `i18n::set_language` on load.
+    language: Language,
+
+    // F&B Management data (saved apart from the UI state, see `app_storage`)
     #[serde(skip)]
-    ingredients: Vec<Rc<RefCell<Ingredient>>>,
+    catalog: Catalog,
 
     #[serde(skip)]
-    plates: Vec<Rc<RefCell<Plate>>>,
+    history: History,
+
+    /// Why the saved catalog could not be loaded; while set, it is not saved over.
+    #[serde(skip)]
+    storage_error: Option<String>,
 
     // UI state
     #[serde(skip)]
     selected_ingredient_idx: Option<usize>,
 
     #[serde(skip)]
-    selected_plate_idx: Option<usize>,
+    pending_deletion: Option<PendingDeletion>,
+
+    #[serde(skip)]
+    ingredient_filter: IngredientFilter,
+
+    #[serde(skip)]
+    edit_ingredient_idx: Option<usize>,
+
+    #[serde(skip)]
+    ingredient_editor: Option<IngredientEditForm>,
+
+    #[serde(skip)]
+    show_ingredient_table: bool,
+
+    #[serde(skip)]
+    ingredient_table: IngredientTable,
+
+    #[serde(skip)]
+    plate_filter: PlateFilter,
+
+    #[serde(skip)]
+    show_substitution: bool,
+
+    #[serde(skip)]
+    substitution_form: SubstitutionForm,
 
     #[serde(skip)]
     new_ingredient_name: String,
@@ -60,20 +197,130 @@ pub struct TemplateApp {
     #[serde(skip)]
     editing_recipe_idx: Option<usize>,
 
+    #[serde(skip)]
+    recipe_form: Option<RecipeEditForm>,
+
     #[serde(skip)]
     show_add_recipe_form: bool,
+
+    // Goods receiving state
+    #[serde(skip)]
+    show_receiving: bool,
+
+    #[serde(skip)]
+    receiving_form: ReceivingForm,
+
+    #[serde(skip)]
+    receiving_report: Option<ReceivingReport>,
+
+    #[serde(skip)]
+    show_valuation: bool,
+
+    #[serde(skip)]
+    show_cost_charts: bool,
+
+    #[serde(skip)]
+    show_exchange_rates: bool,
+
+    #[serde(skip)]
+    exchange_rate_form: ExchangeRateForm,
+
+    #[serde(skip)]
+    chart_plate_idx: Option<usize>,
+
+    // Waste log state
+    #[serde(skip)]
+    show_waste: bool,
+
+    #[serde(skip)]
+    waste_form: WasteForm,
+
+    // Production planning state
+    #[serde(skip)]
+    show_production: bool,
+
+    #[serde(skip)]
+    production_date: String,
+
+    /// Expected covers per plate name.
+    production_forecast: BTreeMap<String, f64>,
+
+    /// Plate waiting for the photo the user is picking.
+    #[serde(skip)]
+    photo_pick: Option<(
+        String,
+        std::sync::mpsc::Receiver<Result<PickedFile, String>>,
+    )>,
+
+    #[serde(skip)]
+    photo_error: Option<String>,
+
+    #[serde(skip)]
+    export_error: Option<String>,
+
+    // Catalog file state
+    /// The catalog file being edited, `None` until it is saved; only its name on the web.
+    catalog_path: Option<std::path::PathBuf>,
+
+    /// Catalog files opened or saved lately, most recent first.
+    recent_files: Vec<std::path::PathBuf>,
+
+    /// Whether the catalog had changes not saved to its file when the app state was saved.
+    unsaved_changes: bool,
+
+    /// `History::changes` when the catalog was last opened or saved as a file.
+    #[serde(skip)]
+    saved_changes: u64,
+
+    #[serde(skip)]
+    catalog_pick: Option<std::sync::mpsc::Receiver<Result<PickedFile, String>>>,
+
+    #[serde(skip)]
+    pending_file_action: Option<FileAction>,
+
+    #[serde(skip)]
+    file_error: Option<String>,
+
+    /// Whether the window may close although the catalog has unsaved changes.
+    #[serde(skip)]
+    allow_close: bool,
+
+    /// Title last given to the window.
+    #[serde(skip)]
+    window_title: String,
 }
 
 impl Default for TemplateApp {
     fn default() -> Self {
-        let mut app = Self {
+        let mut app = Self::without_data();
+
+        // Initialize with sample data
+        app.initialize_sample_data();
+        app
+    }
+}
+
+impl TemplateApp {
+    /// A blank app: empty catalog, no history, default preferences.
+    fn without_data() -> Self {
+        Self {
             // Example stuff:
             label: "Gestion des Coûts F&B".to_owned(),
             value: 2.7,
-            ingredients: Vec::new(),
-            plates: Vec::new(),
+            language: Language::default(),
+            catalog: Catalog::default(),
+            history: History::default(),
+            storage_error: None,
             selected_ingredient_idx: None,
-            selected_plate_idx: None,
+            pending_deletion: None,
+            ingredient_filter: IngredientFilter::default(),
+            edit_ingredient_idx: None,
+            ingredient_editor: None,
+            show_ingredient_table: false,
+            ingredient_table: IngredientTable::default(),
+            plate_filter: PlateFilter::default(),
+            show_substitution: false,
+            substitution_form: SubstitutionForm::default(),
             new_ingredient_name: String::new(),
             new_ingredient_price: 0.0,
             new_ingredient_quantity: 100.0,
@@ -85,12 +332,36 @@ impl Default for TemplateApp {
             new_recipe_batch_quantity: 1.0,
             new_recipe_batch_unit: QuantityUnit::Unit,
             editing_recipe_idx: None,
+            recipe_form: None,
             show_add_recipe_form: false,
-        };
-
-        // Initialize with sample data
-        app.initialize_sample_data();
-        app
+            show_receiving: false,
+            receiving_form: ReceivingForm::default(),
+            receiving_report: None,
+            show_valuation: false,
+            show_cost_charts: false,
+            show_exchange_rates: false,
+            exchange_rate_form: ExchangeRateForm::default(),
+            chart_plate_idx: None,
+            show_waste: false,
+            waste_form: WasteForm::default(),
+            show_production: false,
+            production_date: (chrono::Local::now().date_naive() + chrono::Days::new(1))
+                .format(DATE_FORMAT)
+                .to_string(),
+            production_forecast: BTreeMap::new(),
+            photo_pick: None,
+            photo_error: None,
+            export_error: None,
+            catalog_path: None,
+            recent_files: Vec::new(),
+            unsaved_changes: false,
+            saved_changes: 0,
+            catalog_pick: None,
+            pending_file_action: None,
+            file_error: None,
+            allow_close: false,
+            window_title: String::new(),
+        }
     }
 }
 
@@ -105,8 +376,18 @@ impl TemplateApp {
         if let Some(storage) = cc.storage {
             let mut app: TemplateApp =
                 eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-            // Reinitialize sample data since it's not serialized
-            app.initialize_sample_data();
+            let catalog = load_catalog(storage)
+                .and_then(|file| file.map(|file| Catalog::try_from(&file)).transpose());
+            match catalog {
+                Ok(Some(catalog)) => app.catalog = catalog,
+                Ok(None) => app.initialize_sample_data(),
+                Err(err) => {
+                    log::error!("Failed to load the catalog: {err}");
+                    app.initialize_sample_data();
+                    app.storage_error = Some(err);
+                }
+            }
+            i18n::set_language(app.language);
             app
         } else {
             Default::default()
@@ -153,7 +434,7 @@ impl TemplateApp {
             supplier: supplier1,
         }));
 
-        self.ingredients = vec![tomato.clone(), cheese.clone(), flour.clone()];
+        self.catalog.ingredients = vec![tomato.clone(), cheese.clone(), flour.clone()];
 
         // Create tomato sauce plate
         let tomato_sauce = Rc::new(RefCell::new(Plate {
@@ -194,7 +475,7 @@ impl TemplateApp {
             batch_unit: QuantityUnit::Unit,
         }));
 
-        self.plates = vec![tomato_sauce, pizza];
+        self.catalog.plates = vec![tomato_sauce, pizza];
     }
 
     fn get_unit_variants() -> Vec<QuantityUnit> {
@@ -215,18 +496,173 @@ impl TemplateApp {
         ]
     }
 
+    fn unit_label(unit: &QuantityUnit) -> &'static str {
+        tr(match unit {
+            QuantityUnit::Gram => "Gramme",
+            QuantityUnit::Kilogram => "Kilogramme",
+            QuantityUnit::Milligram => "Milligramme",
+            QuantityUnit::Liter => "Litre",
+            QuantityUnit::Milliliter => "Millilitre",
+            QuantityUnit::Centiliter => "Centilitre",
+            QuantityUnit::Deciliter => "Décilitre",
+            QuantityUnit::Unit => "Unité",
+            QuantityUnit::Piece => "Pièce",
+            QuantityUnit::Slice => "Tranche",
+            QuantityUnit::Teaspoon => "Cuillère à café",
+            QuantityUnit::Tablespoon => "Cuillère à soupe",
+            QuantityUnit::Cup => "Tasse",
+            _ => "Autre",
+        })
+    }
+
+    fn ingredient_type_label(ingredient_type: &IngredientType) -> &'static str {
+        tr(match ingredient_type {
+            IngredientType::Grocery => "Épicerie",
+            IngredientType::DairyEggsCheeseSauces => "Lait, Œufs, Fromages, Sauces",
+            IngredientType::VegetablesFruits => "Légumes & Fruits",
+            IngredientType::Packaging => "Packaging",
+            IngredientType::MeatProteins => "Viandes / Protéines",
+        })
+    }
+
+    fn get_ingredient_type_variants() -> Vec<IngredientType> {
+        vec![
+            IngredientType::Grocery,
+            IngredientType::DairyEggsCheeseSauces,
+            IngredientType::VegetablesFruits,
+            IngredientType::Packaging,
+            IngredientType::MeatProteins,
+        ]
+    }
+
+    fn allergen_label(allergen: Allergen) -> &'static str {
+        tr(match allergen {
+            Allergen::Gluten => "Gluten",
+            Allergen::Crustaceans => "Crustacés",
+            Allergen::Eggs => "Œufs",
+            Allergen::Fish => "Poissons",
+            Allergen::Peanuts => "Arachides",
+            Allergen::Soybeans => "Soja",
+            Allergen::Milk => "Lait",
+            Allergen::Nuts => "Fruits à coque",
+            Allergen::Celery => "Céleri",
+            Allergen::Mustard => "Moutarde",
+            Allergen::Sesame => "Sésame",
+            Allergen::Sulphites => "Sulfites",
+            Allergen::Lupin => "Lupin",
+            Allergen::Molluscs => "Mollusques",
+        })
+    }
+
+    /// Allergen checkboxes of an ingredient card; returns the new allergens when changed.
+    fn allergens_ui(
+        ui: &mut egui::Ui,
+        ingredient_name: &str,
+        allergens: &BTreeSet<Allergen>,
+    ) -> Option<BTreeSet<Allergen>> {
+        let mut edited = allergens.clone();
+        egui::CollapsingHeader::new(trf("Allergènes ({})", &[&allergens.len()]))
+            .id_salt(("allergens", ingredient_name))
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for allergen in Allergen::ALL {
+                        let mut present = edited.contains(&allergen);
+                        if ui
+                            .checkbox(&mut present, Self::allergen_label(allergen))
+                            .changed()
+                        {
+                            if present {
+                                edited.insert(allergen);
+                            } else {
+                                edited.remove(&allergen);
+                            }
+                        }
+                    }
+                });
+            });
+        (edited != *allergens).then_some(edited)
+    }
+
+    fn new_recipe_form_ui(&mut self, ui: &mut egui::Ui) {
+        let currency = self.catalog.settings.currency;
+        ui.group(|ui| {
+            ui.heading(tr("Créer une Nouvelle Recette"));
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Nom :"));
+                ui.text_edit_singleline(&mut self.new_recipe_name);
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Prix de Vente :"));
+                ui.add(money_input(&mut self.new_recipe_selling_price, currency).speed(0.1));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Temps de Préparation (h) :"));
+                ui.add(
+                    number_input(&mut self.new_recipe_prep_time)
+                        .speed(0.1)
+                        .range(0.0..=24.0),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Quantité par Lot :"));
+                ui.add(
+                    number_input(&mut self.new_recipe_batch_quantity)
+                        .speed(0.1)
+                        .range(0.1..=1000.0),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Unité du Lot :"));
+                egui::ComboBox::from_id_salt("new_recipe_unit")
+                    .selected_text(Self::unit_label(&self.new_recipe_batch_unit))
+                    .show_ui(ui, |ui| {
+                        for unit in Self::get_unit_variants() {
+                            let label = Self::unit_label(&unit);
+                            ui.selectable_value(&mut self.new_recipe_batch_unit, unit, label);
+                        }
+                    });
+            });
+
+            let name = self.new_recipe_name.trim();
+            let taken = self.catalog.has_plate_named(name);
+            if taken {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    tr("Une recette porte déjà ce nom"),
+                );
+            }
+            let enabled = !name.is_empty() && !taken;
+            ui.horizontal(|ui| {
+                let create = egui::Button::new(tr("Créer la Recette"));
+                if ui.add_enabled(enabled, create).clicked() {
+                    self.create_new_recipe();
+                }
+                if ui.button(tr("Annuler")).clicked() {
+                    self.show_add_recipe_form = false;
+                }
+            });
+        });
+    }
+
     fn create_new_recipe(&mut self) {
-        if !self.new_recipe_name.is_empty() {
-            let new_recipe = Rc::new(RefCell::new(Plate {
-                name: self.new_recipe_name.clone(),
+        let name = self.new_recipe_name.trim();
+        if !name.is_empty() && !self.catalog.has_plate_named(name) {
+            let new_recipe = Plate {
+                name: name.to_owned(),
                 components: Vec::new(),
                 selling_price: self.new_recipe_selling_price,
                 batch_preparation_time_hours: self.new_recipe_prep_time,
                 batch_quantity: self.new_recipe_batch_quantity,
                 batch_unit: self.new_recipe_batch_unit.clone(),
-            }));
+            };
 
-            self.plates.push(new_recipe);
+            let edit = Edit::add_plate(&self.catalog, new_recipe);
+            self.history.apply(&mut self.catalog, edit);
 
             // Reset form
             self.new_recipe_name.clear();
@@ -238,171 +674,163 @@ impl TemplateApp {
         }
     }
 
+    /// Records a settings change made by a widget, as its own undo step.
+    fn record_settings_change(&mut self, before: CatalogSettings) {
+        if before != self.catalog.settings {
+            self.history.record(Edit::UpdateSettings {
+                before,
+                after: self.catalog.settings,
+            });
+            self.history.seal();
+        }
+    }
+
+    fn edit_menu(&mut self, ui: &mut egui::Ui) {
+        let undo = self.history.undo_description();
+        let undo_button = egui::Button::new(match &undo {
+            Some(description) => trf("Annuler : {}", &[description]),
+            None => tr("Rien à annuler").to_owned(),
+        })
+        .shortcut_text(ui.ctx().format_shortcut(&UNDO_SHORTCUT));
+        if ui.add_enabled(undo.is_some(), undo_button).clicked() {
+            self.history.undo(&mut self.catalog);
+            ui.close();
+        }
+
+        let redo = self.history.redo_description();
+        let redo_button = egui::Button::new(match &redo {
+            Some(description) => trf("Rétablir : {}", &[description]),
+            None => tr("Rien à rétablir").to_owned(),
+        })
+        .shortcut_text(ui.ctx().format_shortcut(&REDO_SHORTCUT));
+        if ui.add_enabled(redo.is_some(), redo_button).clicked() {
+            self.history.redo(&mut self.catalog);
+            ui.close();
+        }
+
+        ui.separator();
+        if ui.button(tr("Substituer un Ingrédient…")).clicked() {
+            self.show_substitution = true;
+            ui.close();
+        }
+        if ui.button(tr("Tableau des Ingrédients")).clicked() {
+            self.show_ingredient_table = true;
+            ui.close();
+        }
+    }
+
+    fn language_menu(&mut self, ui: &mut egui::Ui) {
+        for language in Language::ALL {
+            if ui
+                .selectable_value(&mut self.language, language, language.native_name())
+                .clicked()
+            {
+                i18n::set_language(language);
+                ui.close();
+            }
+        }
+    }
+
+    fn currency_menu(&mut self, ui: &mut egui::Ui) {
+        let settings_before = self.catalog.settings;
+        for currency in Currency::ALL {
+            let text = format!("{} ({})", currency.code(), currency.symbol());
+            if ui
+                .selectable_value(&mut self.catalog.settings.currency, currency, text)
+                .clicked()
+            {
+                ui.close();
+            }
+        }
+        self.record_settings_change(settings_before);
+    }
+
+    /// Ctrl+Z / Ctrl+Shift+Z, unless a text field is focused and handles them itself.
+    fn undo_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        // Redo first: the undo shortcut would also match with Shift held
+        if ctx.input_mut(|input| input.consume_shortcut(&REDO_SHORTCUT)) {
+            self.history.redo(&mut self.catalog);
+        } else if ctx.input_mut(|input| input.consume_shortcut(&UNDO_SHORTCUT)) {
+            self.history.undo(&mut self.catalog);
+        }
+    }
+
     fn ingredients_panel(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Ingrédients");
+        let currency = self.catalog.settings.currency;
+        ui.heading(tr("Ingrédients"));
 
         // Add new ingredient form
-        ui.collapsing("Ajouter un Nouvel Ingrédient", |ui| {
+        ui.collapsing(tr("Ajouter un Nouvel Ingrédient"), |ui| {
             ui.horizontal(|ui| {
-                ui.label("Nom :");
+                ui.label(tr("Nom :"));
                 ui.text_edit_singleline(&mut self.new_ingredient_name);
             });
 
             ui.horizontal(|ui| {
-                ui.label("Prix :");
-                ui.add(
-                    egui::DragValue::new(&mut self.new_ingredient_price)
-                        .speed(0.01)
-                        .prefix("€"),
-                );
+                ui.label(tr("Prix :"));
+                ui.add(money_input(&mut self.new_ingredient_price, currency).speed(0.01));
             });
 
             ui.horizontal(|ui| {
-                ui.label("Quantité Réf. :");
-                ui.add(egui::DragValue::new(&mut self.new_ingredient_quantity).speed(1.0));
+                ui.label(tr("Quantité Réf. :"));
+                ui.add(number_input(&mut self.new_ingredient_quantity).speed(1.0));
             });
 
             ui.horizontal(|ui| {
-                ui.label("Unité :");
+                ui.label(tr("Unité :"));
                 egui::ComboBox::from_id_salt("ingredient_unit_combo")
-                    .selected_text(match self.new_ingredient_unit {
-                        QuantityUnit::Gram => "Gramme",
-                        QuantityUnit::Kilogram => "Kilogramme",
-                        QuantityUnit::Milligram => "Milligramme",
-                        QuantityUnit::Liter => "Litre",
-                        QuantityUnit::Milliliter => "Millilitre",
-                        QuantityUnit::Centiliter => "Centilitre",
-                        QuantityUnit::Deciliter => "Décilitre",
-                        QuantityUnit::Unit => "Unité",
-                        QuantityUnit::Piece => "Pièce",
-                        QuantityUnit::Slice => "Tranche",
-                        QuantityUnit::Teaspoon => "Cuillère à café",
-                        QuantityUnit::Tablespoon => "Cuillère à soupe",
-                        QuantityUnit::Cup => "Tasse",
-                        _ => "Autre",
-                    })
+                    .selected_text(Self::unit_label(&self.new_ingredient_unit))
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Gram,
-                            "Gramme",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Kilogram,
-                            "Kilogramme",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Milligram,
-                            "Milligramme",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Liter,
-                            "Litre",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Milliliter,
-                            "Millilitre",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Centiliter,
-                            "Centilitre",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Deciliter,
-                            "Décilitre",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Unit,
-                            "Unité",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Piece,
-                            "Pièce",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Slice,
-                            "Tranche",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Teaspoon,
-                            "Cuillère à café",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Tablespoon,
-                            "Cuillère à soupe",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_unit,
-                            QuantityUnit::Cup,
-                            "Tasse",
-                        );
+                        for unit in Self::get_unit_variants() {
+                            let label = Self::unit_label(&unit);
+                            ui.selectable_value(&mut self.new_ingredient_unit, unit, label);
+                        }
                     });
             });
 
             ui.horizontal(|ui| {
-                ui.label("Type :");
+                ui.label(tr("Type :"));
                 egui::ComboBox::from_id_salt("ingredient_type_combo")
-                    .selected_text(match self.new_ingredient_type {
-                        IngredientType::Grocery => "Épicerie",
-                        IngredientType::DairyEggsCheeseSauces => "Lait, Œufs, Fromages, Sauces",
-                        IngredientType::VegetablesFruits => "Légumes & Fruits",
-                        IngredientType::Packaging => "Packaging",
-                        IngredientType::MeatProteins => "Viandes / Protéines",
-                    })
+                    .selected_text(Self::ingredient_type_label(&self.new_ingredient_type))
                     .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut self.new_ingredient_type,
-                            IngredientType::Grocery,
-                            "Épicerie",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_type,
-                            IngredientType::DairyEggsCheeseSauces,
-                            "Lait, Œufs, Fromages, Sauces",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_type,
-                            IngredientType::VegetablesFruits,
-                            "Légumes & Fruits",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_type,
-                            IngredientType::Packaging,
-                            "Packaging",
-                        );
-                        ui.selectable_value(
-                            &mut self.new_ingredient_type,
-                            IngredientType::MeatProteins,
-                            "Viandes / Protéines",
-                        );
+                        for ingredient_type in Self::get_ingredient_type_variants() {
+                            let label = Self::ingredient_type_label(&ingredient_type);
+                            ui.selectable_value(
+                                &mut self.new_ingredient_type,
+                                ingredient_type,
+                                label,
+                            );
+                        }
                     });
             });
 
-            if ui.button("Ajouter Ingrédient").clicked() && !self.new_ingredient_name.is_empty() {
-                let new_ingredient = Rc::new(RefCell::new(Ingredient {
-                    name: self.new_ingredient_name.clone(),
+            let name = self.new_ingredient_name.trim();
+            let taken = self.catalog.has_ingredient_named(name);
+            if taken {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    tr("Un ingrédient porte déjà ce nom"),
+                );
+            }
+            let add = egui::Button::new(tr("Ajouter Ingrédient"));
+            if ui.add_enabled(!name.is_empty() && !taken, add).clicked() {
+                let new_ingredient = Ingredient {
+                    name: name.to_owned(),
                     cost_price: self.new_ingredient_price,
                     reference_quantity: self.new_ingredient_quantity,
                     unit: self.new_ingredient_unit.clone(),
                     ingredient_type: self.new_ingredient_type.clone(),
                     supplier: Supplier {
-                        name: "Par Défaut".to_string(),
+                        name: tr("Par Défaut").to_string(),
                         contact: None,
                     },
-                }));
+                };
 
-                self.ingredients.push(new_ingredient);
+                let edit = Edit::add_ingredient(&self.catalog, new_ingredient);
+                self.history.apply(&mut self.catalog, edit);
                 self.new_ingredient_name.clear();
                 self.new_ingredient_price = 0.0;
                 self.new_ingredient_quantity = 100.0;
@@ -411,292 +839,201 @@ impl TemplateApp {
             }
         });
 
+        ui.separator();
+        self.ingredient_filter_ui(ui);
         ui.separator();
 
         // Ingredients list
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for (idx, ingredient_rc) in self.ingredients.iter().enumerate() {
-                let mut ingredient = ingredient_rc.borrow_mut();
-
-                ui.group(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.heading(&ingredient.name);
-                        if ui.small_button("🗑").clicked() {
-                            // Mark for deletion (we'll handle this outside the borrow)
-                            self.selected_ingredient_idx = Some(idx);
-                        }
-                    });
+        let order = self.filtered_ingredients();
+        let item_ids: Vec<egui::Id> = order
+            .iter()
+            .map(|&idx| {
+                egui::Id::new((
+                    "ingredient_card",
+                    Rc::as_ptr(&self.catalog.ingredients[idx]),
+                ))
+            })
+            .collect();
+        virtual_list::show(ui, "ingredients_list", &item_ids, |ui, row| {
+            let idx = order[row];
+            let ingredient_rc = &self.catalog.ingredients[idx];
+            let mut ingredient = ingredient_rc.borrow_mut();
+            let before = copy_ingredient(&ingredient);
+            let mut changed = false;
+            // Applied after the group, which borrows the whole catalog
+            let mut allergens_edit = None;
 
-                    ui.horizontal(|ui| {
-                        ui.label("Prix de Revient :");
-                        let response = ui.add(
-                            egui::DragValue::new(&mut ingredient.cost_price)
-                                .speed(0.01)
-                                .prefix("€"),
-                        );
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(&ingredient.name);
+                    if ui.small_button("🗑").clicked() {
+                        // Mark for deletion (we'll handle this outside the borrow)
+                        self.selected_ingredient_idx = Some(idx);
+                    }
+                    if ui.small_button("✏").on_hover_text(tr("Modifier")).clicked() {
+                        self.edit_ingredient_idx = Some(idx);
+                    }
+                    if ui
+                        .small_button("⇄")
+                        .on_hover_text(tr("Substituer dans toutes les recettes"))
+                        .clicked()
+                    {
+                        self.substitution_form.ingredient_idx = Some(idx);
+                        self.show_substitution = true;
+                    }
+                });
 
-                        if response.changed() {
-                            // Price changed, UI will automatically reflect in plates
+                let conversion = self.catalog.price_conversion();
+                let price_currency = conversion.currency_of(&ingredient.name);
+                ui.horizontal(|ui| {
+                    ui.label(tr("Prix de Revient :"));
+                    let response =
+                        ui.add(money_input(&mut ingredient.cost_price, price_currency).speed(0.01));
+                    changed |= response.changed();
+                    if price_currency != currency {
+                        if conversion.lacks_rate(&ingredient.name) {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 200, 100),
+                                trf(
+                                    "⚠ aucun taux {} → {}",
+                                    &[&price_currency.code(), &currency.code()],
+                                ),
+                            );
+                        } else {
+                            let converted =
+                                conversion.convert(&ingredient.name, ingredient.cost_price);
+                            ui.label(format!("≈ {}", locale::money(converted, currency)));
                         }
-                    });
+                    }
+                });
 
-                    ui.horizontal(|ui| {
-                        ui.label("Quantité de Référence :");
-                        ui.add(egui::DragValue::new(&mut ingredient.reference_quantity).speed(1.0));
-                        ui.label(format!("{:?}", ingredient.unit));
-                    });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Quantité de Référence :"));
+                    changed |= ui
+                        .add(number_input(&mut ingredient.reference_quantity).speed(1.0))
+                        .changed();
+                    ui.label(Self::unit_label(&ingredient.unit));
+                });
 
-                    ui.label(format!("Fournisseur : {}", ingredient.supplier.name));
-                    ui.label(format!("Type : {:?}", ingredient.ingredient_type));
+                ui.label(trf(
+                    "Stock : {} {}",
+                    &[
+                        &locale::number(self.catalog.inventory.stock_of(ingredient_rc), 2),
+                        &Self::unit_label(&ingredient.unit),
+                    ],
+                ));
+
+                ui.label(trf("Fournisseur : {}", &[&ingredient.supplier.name]));
+                ui.label(trf(
+                    "Type : {}",
+                    &[&Self::ingredient_type_label(&ingredient.ingredient_type)],
+                ));
+
+                let allergens = self
+                    .catalog
+                    .allergens
+                    .get(&ingredient.name)
+                    .cloned()
+                    .unwrap_or_default();
+                if let Some(edited) = Self::allergens_ui(ui, &ingredient.name, &allergens) {
+                    allergens_edit = Some((allergens, edited));
+                }
+            });
+
+            if let Some((allergens, edited)) = allergens_edit {
+                self.catalog
+                    .allergens
+                    .insert(ingredient.name.clone(), edited.clone());
+                self.history.record(Edit::UpdateAllergens {
+                    ingredient_name: ingredient.name.clone(),
+                    before: allergens,
+                    after: edited,
+                });
+            }
+
+            if changed {
+                self.history.record(Edit::UpdateIngredient {
+                    ingredient: ingredient_rc.clone(),
+                    before,
+                    after: copy_ingredient(&ingredient),
                 });
             }
         });
 
         // Handle deletion outside of borrow
         if let Some(idx) = self.selected_ingredient_idx.take() {
-            if idx < self.ingredients.len() {
-                self.ingredients.remove(idx);
+            self.request_ingredient_deletion(idx);
+        }
+
+        if let Some(idx) = self.edit_ingredient_idx.take() {
+            if let Some(ingredient) = self.catalog.ingredients.get(idx).cloned() {
+                self.open_ingredient_editor(&ingredient);
             }
         }
     }
 
     fn plates_panel(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Plats (Recettes)");
+        ui.heading(tr("Plats (Recettes)"));
+
+        if let Some(error) = &self.export_error {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 100, 100),
+                trf("Échec de l'export : {}", &[error]),
+            );
+        }
+        if let Some(error) = &self.file_error {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 100, 100),
+                trf("Erreur de fichier : {}", &[error]),
+            );
+        }
+        if let Some(error) = &self.photo_error {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 100, 100),
+                trf("Photo illisible : {}", &[error]),
+            );
+        }
+
+        self.alerts_ui(ui);
 
         // Add new recipe button
         ui.horizontal(|ui| {
-            if ui.button("➕ Ajouter une Nouvelle Recette").clicked() {
+            if ui.button(tr("➕ Ajouter une Nouvelle Recette")).clicked() {
                 self.show_add_recipe_form = true;
             }
         });
 
         // Add new recipe form
         if self.show_add_recipe_form {
-            ui.group(|ui| {
-                ui.heading("Créer une Nouvelle Recette");
-
-                ui.horizontal(|ui| {
-                    ui.label("Nom :");
-                    ui.text_edit_singleline(&mut self.new_recipe_name);
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Prix de Vente :");
-                    ui.add(
-                        egui::DragValue::new(&mut self.new_recipe_selling_price)
-                            .speed(0.1)
-                            .prefix("€"),
-                    );
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Temps de Préparation (h) :");
-                    ui.add(
-                        egui::DragValue::new(&mut self.new_recipe_prep_time)
-                            .speed(0.1)
-                            .range(0.0..=24.0),
-                    );
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Quantité par Lot :");
-                    ui.add(
-                        egui::DragValue::new(&mut self.new_recipe_batch_quantity)
-                            .speed(0.1)
-                            .range(0.1..=1000.0),
-                    );
-                });
-
-                ui.horizontal(|ui| {
-                    ui.label("Unité du Lot :");
-                    egui::ComboBox::from_id_salt("new_recipe_unit")
-                        .selected_text(format!("{:?}", self.new_recipe_batch_unit))
-                        .show_ui(ui, |ui| {
-                            for unit in Self::get_unit_variants() {
-                                ui.selectable_value(
-                                    &mut self.new_recipe_batch_unit,
-                                    unit.clone(),
-                                    format!("{:?}", unit),
-                                );
-                            }
-                        });
-                });
-
-                ui.horizontal(|ui| {
-                    if ui.button("Créer la Recette").clicked() {
-                        self.create_new_recipe();
-                    }
-                    if ui.button("Annuler").clicked() {
-                        self.show_add_recipe_form = false;
-                    }
-                });
-            });
+            self.new_recipe_form_ui(ui);
         }
 
+        ui.separator();
+        self.plate_filter_ui(ui);
         ui.separator();
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for (idx, plate_rc) in self.plates.iter().enumerate() {
-                let mut plate = plate_rc.borrow_mut();
-
-                ui.group(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.heading(&plate.name);
-                        if ui.small_button("🗑").clicked() {
-                            self.selected_plate_idx = Some(idx);
-                        }
-                    });
-
-                    // Show cost breakdown
-                    ui.horizontal(|ui| {
-                        ui.label("Coût Ingrédients :");
-                        ui.label(format!("€{:.2}", plate.ingredient_cost()));
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label("Coût Main-d'œuvre :");
-                        ui.label(format!("€{:.2}", plate.labor_cost()));
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label("Coût Total :");
-                        ui.colored_label(
-                            egui::Color32::from_rgb(255, 100, 100),
-                            format!("€{:.2}", plate.total_cost_price()),
-                        );
-                    });
-
-                    // Editable selling price
-                    ui.horizontal(|ui| {
-                        ui.label("Prix de Vente :");
-                        ui.add(
-                            egui::DragValue::new(&mut plate.selling_price)
-                                .speed(0.1)
-                                .prefix("€"),
-                        );
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label("Marge Brute :");
-                        ui.colored_label(
-                            egui::Color32::from_rgb(100, 255, 100),
-                            format!("€{:.2}", plate.gross_margin()),
-                        );
-                    });
-
-                    ui.horizontal(|ui| {
-                        ui.label("Taux de Marge :");
-                        ui.colored_label(
-                            egui::Color32::from_rgb(100, 255, 100),
-                            format!("{:.1}%", plate.margin_rate() * 100.0),
-                        );
-                    });
-
-                    // Show and edit components
-                    egui::CollapsingHeader::new("Composants")
-                        .id_salt(format!("components_plate_{}", plate.name))
-                        .show(ui, |ui| {
-                            // Add ingredient to recipe
-                            ui.horizontal(|ui| {
-                                ui.label("Ajouter un ingrédient :");
-                                egui::ComboBox::from_id_salt(format!("add_ingredient_{}", idx))
-                                    .selected_text("Sélectionner un ingrédient...")
-                                    .show_ui(ui, |ui| {
-                                        for ingredient_rc in &self.ingredients {
-                                            let ingredient = ingredient_rc.borrow();
-                                            if ui
-                                                .selectable_label(false, &ingredient.name)
-                                                .clicked()
-                                            {
-                                                // Add ingredient to recipe with default quantity
-                                                plate.components.push(PlateComponent::Ingredient {
-                                                    ingredient: ingredient_rc.clone(),
-                                                    quantity: 100.0,
-                                                    unit: QuantityUnit::Gram,
-                                                });
-                                            }
-                                        }
-                                    });
-                            });
-
-                            // Display and edit existing components
-                            let mut components_to_remove = Vec::new();
-                            for (comp_idx, component) in plate.components.iter_mut().enumerate() {
-                                ui.horizontal(|ui| {
-                                    match component {
-                                        PlateComponent::Ingredient {
-                                            ingredient,
-                                            quantity,
-                                            unit: _,
-                                        } => {
-                                            let ing = ingredient.borrow();
-                                            ui.label(&ing.name);
-                                            ui.add(
-                                                egui::DragValue::new(quantity)
-                                                    .speed(1.0)
-                                                    .suffix("g")
-                                                    .range(0.1..=10000.0),
-                                            );
-
-                                            // Convert ingredient unit to grams for display
-                                            let cost_per_gram = if ing.unit == QuantityUnit::Gram {
-                                                ing.cost_price / ing.reference_quantity
-                                            } else if ing.unit == QuantityUnit::Kilogram {
-                                                // Convert kg to g: 1 kg = 1000 g
-                                                ing.cost_price / (ing.reference_quantity * 1000.0)
-                                            } else {
-                                                // For other units, use conversion if available
-                                                if let Some(conversion_factor) = ing
-                                                    .unit
-                                                    .conversion_factor_to(&QuantityUnit::Gram)
-                                                {
-                                                    (ing.cost_price / ing.reference_quantity)
-                                                        * conversion_factor
-                                                } else {
-                                                    ing.cost_price / ing.reference_quantity
-                                                }
-                                            };
-
-                                            ui.label(format!("@ €{:.4}/g", cost_per_gram));
-                                        }
-                                        PlateComponent::Plate {
-                                            plate: sub_plate,
-                                            quantity,
-                                        } => {
-                                            let sub = sub_plate.borrow();
-                                            ui.label(&sub.name);
-                                            ui.add(
-                                                egui::DragValue::new(quantity)
-                                                    .speed(1.0)
-                                                    .suffix("g")
-                                                    .range(0.1..=10000.0),
-                                            );
-                                            ui.label(format!(
-                                                "@ €{:.4}/g",
-                                                sub.batch_cost_per_unit()
-                                            ));
-                                        }
-                                    }
-                                    if ui.small_button("🗑").clicked() {
-                                        components_to_remove.push(comp_idx);
-                                    }
-                                });
-                            }
-
-                            // Remove components marked for deletion
-                            for &idx_to_remove in components_to_remove.iter().rev() {
-                                plate.components.remove(idx_to_remove);
-                            }
-                        });
-                });
+        let valuation = self.catalog.valuation();
+        let order = self.filtered_plates(&valuation);
+        let item_ids: Vec<egui::Id> = order
+            .iter()
+            .map(|&idx| egui::Id::new(("plate_card", Rc::as_ptr(&self.catalog.plates[idx]))))
+            .collect();
+        let mut plate_action = None;
+        virtual_list::show(ui, "plates_list", &item_ids, |ui, row| {
+            let idx = order[row];
+            let recipe_form = self
+                .recipe_form
+                .as_mut()
+                .filter(|_| self.editing_recipe_idx == Some(idx));
+            if let Some(action) =
+                Self::plate_card_ui(ui, &self.catalog, &valuation, idx, recipe_form)
+            {
+                plate_action = Some((idx, action));
             }
         });
 
-        // Handle plate deletion outside of borrow
-        if let Some(idx) = self.selected_plate_idx.take() {
-            if idx < self.plates.len() {
-                self.plates.remove(idx);
-            }
+        if let Some((idx, action)) = plate_action {
+            self.apply_plate_action(ui.ctx(), idx, action);
         }
     }
 }
@@ -704,24 +1041,84 @@ impl TemplateApp {
 impl eframe::App for TemplateApp {
     /// Called by the framework to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.unsaved_changes = self.has_unsaved_changes();
         eframe::set_value(storage, eframe::APP_KEY, self);
+        // The catalog shown is not the saved one, which may still be recovered
+        if self.storage_error.is_some() {
+            return;
+        }
+        if let Err(err) = save_catalog(storage, &CatalogFile::from(&self.catalog)) {
+            log::error!("Failed to save the catalog: {err}");
+        }
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.intercept_close(ctx);
+        self.undo_shortcuts(ctx);
+        self.file_shortcuts(ctx);
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
-                let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("Fichier", |ui| {
-                        if ui.button("Quitter").clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                        }
+                ui.menu_button(tr("Fichier"), |ui| {
+                    self.file_menu(ui);
+                });
+                ui.add_space(16.0);
+                ui.menu_button(tr("Édition"), |ui| {
+                    self.edit_menu(ui);
+                });
+                ui.menu_button(tr("Coûts"), |ui| {
+                    if ui.button(tr("Méthode de Valorisation")).clicked() {
+                        self.show_valuation = true;
+                        ui.close();
+                    }
+                    if ui.button(tr("Graphiques des Coûts")).clicked() {
+                        self.show_cost_charts = true;
+                        ui.close();
+                    }
+                    if ui.button(tr("Taux de Change")).clicked() {
+                        self.show_exchange_rates = true;
+                        ui.close();
+                    }
+                    ui.menu_button(tr("Devise"), |ui| {
+                        self.currency_menu(ui);
                     });
-                    ui.add_space(16.0);
-                }
+                });
+                ui.menu_button(tr("Production"), |ui| {
+                    if ui.button(tr("Plan de Production")).clicked() {
+                        self.show_production = true;
+                        ui.close();
+                    }
+                });
+                ui.menu_button(tr("Stock"), |ui| {
+                    if ui.button(tr("Réception de Marchandises")).clicked() {
+                        self.show_receiving = true;
+                        ui.close();
+                    }
+                    if ui.button(tr("Pertes et Gaspillage")).clicked() {
+                        self.show_waste = true;
+                        ui.close();
+                    }
+                });
+                ui.menu_button(format!("🌐 {}", self.language.native_name()), |ui| {
+                    self.language_menu(ui);
+                });
+                ui.add_space(16.0);
                 egui::widgets::global_theme_preference_buttons(ui);
             });
+            if let Some(error) = &self.storage_error {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    trf(
+                        "Catalogue enregistré illisible, il ne sera pas remplacé : {}",
+                        &[error],
+                    ),
+                )
+                .on_hover_text(tr(
+                    "Les modifications ne sont pas enregistrées automatiquement ; \
+                     utilisez Fichier › Enregistrer sous pour les garder",
+                ));
+            }
         });
 
         egui::SidePanel::left("ingredients_panel")
@@ -730,18 +1127,38 @@ impl eframe::App for TemplateApp {
                 self.ingredients_panel(ui);
             });
 
+        self.receiving_window(ctx);
+        self.valuation_window(ctx);
+        self.cost_charts_window(ctx);
+        self.exchange_rates_window(ctx);
+        self.waste_window(ctx);
+        self.production_window(ctx);
+        self.substitution_window(ctx);
+        self.ingredient_table_window(ctx);
+        self.poll_photo_pick();
+        self.poll_catalog_pick();
+        self.unsaved_changes_modal(ctx);
+        self.deletion_modal(ctx);
+        self.ingredient_editor_modal(ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.plates_panel(ui);
 
             ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
                 ui.horizontal(|ui| {
                     ui.spacing_mut().item_spacing.x = 0.0;
-                    ui.label("Gestion des Coûts F&B propulsé par ");
+                    ui.label(tr("Gestion des Coûts F&B propulsé par "));
                     ui.hyperlink_to("egui", "https://github.com/emilk/egui");
                     ui.label(".");
                 });
                 egui::warn_if_debug_build(ui);
             });
         });
+
+        // A drag or a text entry in progress keeps extending the same undo step
+        if ctx.dragged_id().is_none() && ctx.memory(|memory| memory.focused().is_none()) {
+            self.history.seal();
+        }
+        self.update_title(ctx);
     }
 }
@@ -0,0 +1,64 @@
+use super::{TemplateApp, number_input};
+use crate::health::{self, Status};
+use crate::i18n::{tr, trf};
+
+impl TemplateApp {
+    pub(super) fn status_color(status: Status) -> egui::Color32 {
+        match status {
+            Status::Green => egui::Color32::from_rgb(100, 255, 100),
+            Status::Amber => egui::Color32::from_rgb(255, 200, 100),
+            Status::Red => egui::Color32::from_rgb(255, 100, 100),
+        }
+    }
+
+    /// Editor of a threshold stored as a fraction and shown as a percentage.
+    fn rate_ui(ui: &mut egui::Ui, label: &str, rate: &mut f64) {
+        ui.label(label);
+        let mut percent = *rate * 100.0;
+        if ui
+            .add(
+                number_input(&mut percent)
+                    .speed(0.5)
+                    .range(0.0..=100.0)
+                    .suffix(" %"),
+            )
+            .changed()
+        {
+            *rate = percent / 100.0;
+        }
+    }
+
+    /// Margin thresholds and the issues of the catalog, above the plate list.
+    pub(super) fn alerts_ui(&mut self, ui: &mut egui::Ui) {
+        let issues = health::validate(&self.catalog, &self.catalog.valuation());
+        let header = if issues.is_empty() {
+            tr("✔ Aucune alerte").to_owned()
+        } else {
+            trf("⚠ Alertes ({})", &[&issues.len()])
+        };
+
+        egui::CollapsingHeader::new(header)
+            .id_salt("plate_alerts")
+            .default_open(true)
+            .show(ui, |ui| {
+                let settings_before = self.catalog.settings;
+                let thresholds = &mut self.catalog.settings.margin_thresholds;
+                ui.horizontal_wrapped(|ui| {
+                    Self::rate_ui(ui, tr("Marge minimum :"), &mut thresholds.min_margin_rate);
+                    Self::rate_ui(
+                        ui,
+                        tr("Food cost maximum :"),
+                        &mut thresholds.max_food_cost_rate,
+                    );
+                });
+                self.record_settings_change(settings_before);
+
+                for issue in &issues {
+                    ui.colored_label(
+                        Self::status_color(issue.status()),
+                        format!("• {}", issue.message()),
+                    );
+                }
+            });
+    }
+}
@@ -0,0 +1,148 @@
+use egui_plot::{Bar, BarChart, Plot};
+
+use super::TemplateApp;
+use crate::cost_tree::CostNode;
+use crate::costing;
+use crate::i18n::tr;
+use crate::locale;
+
+/// Height of one bar of the charts, in points.
+const BAR_HEIGHT: f32 = 24.0;
+
+/// Horizontal bar chart with one labelled bar per value.
+fn bar_chart_ui(
+    ui: &mut egui::Ui,
+    id: &str,
+    values: Vec<(String, f64)>,
+    color: egui::Color32,
+    format_value: impl Fn(f64) -> String + Copy + 'static,
+) {
+    if values.is_empty() {
+        ui.label(tr("Aucune donnée."));
+        return;
+    }
+    let labels: Vec<String> = values.iter().map(|(label, _)| label.clone()).collect();
+    let bars = values
+        .into_iter()
+        .enumerate()
+        .map(|(position, (label, value))| Bar::new(position as f64, value).name(label).width(0.6))
+        .collect();
+    let chart = BarChart::new(id, bars)
+        .horizontal()
+        .color(color)
+        .element_formatter(Box::new(move |bar, _| {
+            format!("{} : {}", bar.name, format_value(bar.value))
+        }));
+
+    Plot::new(id)
+        .height(BAR_HEIGHT * labels.len() as f32 + 40.0)
+        .allow_zoom(false)
+        .allow_drag(false)
+        .allow_scroll(false)
+        .show_grid([true, false])
+        .include_x(0.0)
+        .y_axis_min_width(120.0)
+        .x_axis_formatter(move |mark, _| format_value(mark.value))
+        .y_axis_formatter(move |mark, _| {
+            // Only the marks falling on a bar get its label
+            let position = mark.value.round();
+            if (mark.value - position).abs() < 1e-6 && position >= 0.0 {
+                labels.get(position as usize).cloned().unwrap_or_default()
+            } else {
+                String::new()
+            }
+        })
+        .show(ui, |plot_ui| plot_ui.bar_chart(chart));
+}
+
+impl TemplateApp {
+    pub(super) fn cost_charts_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_cost_charts;
+        egui::Window::new(tr("Graphiques des Coûts"))
+            .id(egui::Id::new("cost_charts_window"))
+            .open(&mut open)
+            .default_width(600.0)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                self.cost_charts_ui(ui);
+            });
+        self.show_cost_charts = open;
+    }
+
+    fn cost_charts_ui(&mut self, ui: &mut egui::Ui) {
+        let valuation = self.catalog.valuation();
+        let currency = self.catalog.settings.currency;
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Recette :"));
+            let selected_text = self
+                .chart_plate_idx
+                .and_then(|idx| self.catalog.plates.get(idx))
+                .map_or_else(
+                    || tr("Sélectionner...").to_owned(),
+                    |plate| plate.borrow().name.clone(),
+                );
+            egui::ComboBox::from_id_salt("chart_plate")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (idx, plate) in self.catalog.plates.iter().enumerate() {
+                        let name = plate.borrow().name.clone();
+                        ui.selectable_value(&mut self.chart_plate_idx, Some(idx), name);
+                    }
+                });
+        });
+
+        if let Some(plate) = self
+            .chart_plate_idx
+            .and_then(|idx| self.catalog.plates.get(idx))
+        {
+            let plate = plate.borrow();
+            ui.heading(tr("Coût du lot par composant"));
+            let tree = CostNode::of(&plate, &valuation);
+            let by_component = tree
+                .children
+                .into_iter()
+                .map(|node| (node.name, node.cost.to_f64()))
+                .collect();
+            bar_chart_ui(
+                ui,
+                "chart_by_component",
+                by_component,
+                egui::Color32::from_rgb(100, 150, 255),
+                move |value| locale::money(value, currency),
+            );
+
+            ui.heading(tr("Coût ingrédients par catégorie"));
+            let by_type = costing::ingredient_cost_by_type(&plate, &valuation)
+                .into_iter()
+                .map(|(ingredient_type, cost)| {
+                    (
+                        Self::ingredient_type_label(&ingredient_type).to_owned(),
+                        cost,
+                    )
+                })
+                .collect();
+            bar_chart_ui(
+                ui,
+                "chart_by_type",
+                by_type,
+                egui::Color32::from_rgb(255, 180, 80),
+                move |value| locale::money(value, currency),
+            );
+        }
+
+        ui.separator();
+        ui.heading(tr("Food cost par plat"));
+        let rates = costing::food_cost_rates(&self.catalog.plates, &valuation)
+            .into_iter()
+            .map(|(name, rate)| (name, rate * 100.0))
+            .collect();
+        bar_chart_ui(
+            ui,
+            "chart_food_cost",
+            rates,
+            egui::Color32::from_rgb(255, 100, 100),
+            |value| locale::percent(value, 1),
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use super::TemplateApp;
+use crate::cost_tree::{CostNode, CostNodeKind};
+use crate::currency::Currency;
+use crate::i18n::trf;
+use crate::locale;
+use crate::money::Money;
+
+impl TemplateApp {
+    /// "quantity unit × unit cost = cost (percent)" for a node of a tree costing `total`.
+    pub(super) fn cost_node_text(node: &CostNode, total: Money, currency: Currency) -> String {
+        let unit = node.unit.as_ref().map_or("h", Self::unit_label);
+        format!(
+            "{} {unit} × {} = {} ({})",
+            locale::number(node.quantity, 2),
+            locale::unit_money(node.unit_cost, currency),
+            locale::money(node.cost, currency),
+            locale::percent(node.percent_of(total), 1)
+        )
+    }
+
+    /// Expandable tree of the components of `tree`, with their share of its cost.
+    pub(super) fn cost_tree_ui(ui: &mut egui::Ui, tree: &CostNode, currency: Currency) {
+        ui.label(trf(
+            "Coût du lot : {} ({} {})",
+            &[
+                &locale::money(tree.cost, currency),
+                &locale::number(tree.quantity, 2),
+                &tree.unit.as_ref().map_or("", Self::unit_label),
+            ],
+        ));
+        Self::cost_children_ui(ui, tree, tree.cost, &tree.name, currency);
+    }
+
+    fn cost_children_ui(
+        ui: &mut egui::Ui,
+        node: &CostNode,
+        total: Money,
+        id_path: &str,
+        currency: Currency,
+    ) {
+        for (position, child) in node.children.iter().enumerate() {
+            let text = format!(
+                "{} — {}",
+                child.name,
+                Self::cost_node_text(child, total, currency)
+            );
+            match child.kind {
+                CostNodeKind::Plate => {
+                    let id_path = format!("{id_path}/{position}");
+                    egui::CollapsingHeader::new(format!("🍲 {text}"))
+                        .id_salt(("cost_tree", &id_path))
+                        .show(ui, |ui| {
+                            Self::cost_children_ui(ui, child, total, &id_path, currency);
+                        });
+                }
+                CostNodeKind::Ingredient => {
+                    ui.label(format!("• {text}"));
+                }
+                CostNodeKind::Labor => {
+                    ui.label(format!("⏱ {text}"));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use poc_fnb::{Ingredient, Plate};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::TemplateApp;
+use crate::dependencies::{self, Dependent};
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+
+/// What the user asked to delete.
+pub(super) enum DeletedItem {
+    Ingredient(Rc<RefCell<Ingredient>>),
+    Plate(Rc<RefCell<Plate>>),
+}
+
+impl DeletedItem {
+    fn name(&self) -> String {
+        match self {
+            Self::Ingredient(ingredient) => ingredient.borrow().name.clone(),
+            Self::Plate(plate) => plate.borrow().name.clone(),
+        }
+    }
+}
+
+/// A deletion waiting for the user to decide what happens to the recipes using the item.
+pub(super) struct PendingDeletion {
+    item: DeletedItem,
+    dependents: Vec<Dependent>,
+    replace: bool,
+    replacement_idx: Option<usize>,
+}
+
+enum Decision {
+    Cancel,
+    Delete { replacement_idx: Option<usize> },
+}
+
+impl TemplateApp {
+    /// Deletes the ingredient right away if no recipe uses it, otherwise asks what to do.
+    pub(super) fn request_ingredient_deletion(&mut self, idx: usize) {
+        if let Some(ingredient) = self.catalog.ingredients.get(idx) {
+            self.request_deletion(DeletedItem::Ingredient(ingredient.clone()));
+        }
+    }
+
+    /// Deletes the plate right away if no recipe uses it, otherwise asks what to do.
+    pub(super) fn request_plate_deletion(&mut self, idx: usize) {
+        if let Some(plate) = self.catalog.plates.get(idx) {
+            self.request_deletion(DeletedItem::Plate(plate.clone()));
+        }
+    }
+
+    fn request_deletion(&mut self, item: DeletedItem) {
+        let dependents = match &item {
+            DeletedItem::Ingredient(ingredient) => {
+                dependencies::ingredient_dependents(&self.catalog, ingredient)
+            }
+            DeletedItem::Plate(plate) => dependencies::plate_dependents(&self.catalog, plate),
+        };
+        if dependents.is_empty() {
+            self.delete(&item, None);
+        } else {
+            self.pending_deletion = Some(PendingDeletion {
+                item,
+                dependents,
+                replace: false,
+                replacement_idx: None,
+            });
+        }
+    }
+
+    fn delete(&mut self, item: &DeletedItem, replacement_idx: Option<usize>) {
+        let edit = match item {
+            DeletedItem::Ingredient(ingredient) => {
+                match replacement_idx.and_then(|idx| self.catalog.ingredients.get(idx)) {
+                    Some(replacement) => {
+                        Edit::replace_ingredient(&self.catalog, ingredient, replacement)
+                    }
+                    None => Edit::remove_ingredient(&self.catalog, ingredient),
+                }
+            }
+            DeletedItem::Plate(plate) => {
+                match replacement_idx.and_then(|idx| self.catalog.plates.get(idx)) {
+                    Some(replacement) => Edit::replace_plate(&self.catalog, plate, replacement),
+                    None => Edit::remove_plate(&self.catalog, plate),
+                }
+            }
+        };
+        self.history.apply(&mut self.catalog, edit);
+    }
+
+    /// Items that can take the place of the deleted one, by catalog index.
+    fn replacement_candidates(&self, pending: &PendingDeletion) -> Vec<(usize, String)> {
+        match &pending.item {
+            DeletedItem::Ingredient(ingredient) => self
+                .catalog
+                .ingredients
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| !Rc::ptr_eq(other, ingredient))
+                .map(|(idx, other)| (idx, other.borrow().name.clone()))
+                .collect(),
+            // A plate containing one of the recipes to update would make it contain itself
+            DeletedItem::Plate(plate) => self
+                .catalog
+                .plates
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| !Rc::ptr_eq(other, plate))
+                .filter(|(_, other)| {
+                    pending
+                        .dependents
+                        .iter()
+                        .filter(|dependent| dependent.is_direct())
+                        .all(|dependent| !dependencies::uses_plate(other, &dependent.plate))
+                })
+                .map(|(idx, other)| (idx, other.borrow().name.clone()))
+                .collect(),
+        }
+    }
+
+    pub(super) fn deletion_modal(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_deletion else {
+            return;
+        };
+        let candidates = self.replacement_candidates(pending);
+        let Some(pending) = &mut self.pending_deletion else {
+            return;
+        };
+
+        let mut decision = None;
+        let modal = egui::Modal::new(egui::Id::new("deletion_modal")).show(ctx, |ui| {
+            ui.heading(trf("Supprimer {} ?", &[&pending.item.name()]));
+            ui.label(trf(
+                "Utilisé par {} recette(s) :",
+                &[&pending.dependents.len()],
+            ));
+            for dependent in &pending.dependents {
+                let plate_name = dependent.plate.borrow().name.clone();
+                if dependent.is_direct() {
+                    ui.label(format!("• {plate_name}"));
+                } else {
+                    ui.label(format!(
+                        "• {plate_name} (via {})",
+                        dependent.path.join(" → ")
+                    ));
+                }
+            }
+
+            ui.separator();
+            ui.radio_value(&mut pending.replace, false, tr("Retirer des recettes"))
+                .on_hover_text(tr(
+                    "Les recettes passant par une sous-recette changent avec elle",
+                ));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut pending.replace, true, tr("Remplacer par :"));
+                ui.add_enabled_ui(pending.replace, |ui| {
+                    let selected = pending
+                        .replacement_idx
+                        .and_then(|idx| candidates.iter().find(|(candidate, _)| *candidate == idx))
+                        .map_or(tr("Sélectionner..."), |(_, name)| name.as_str());
+                    egui::ComboBox::from_id_salt("deletion_replacement")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            for (idx, name) in &candidates {
+                                ui.selectable_value(&mut pending.replacement_idx, Some(*idx), name);
+                            }
+                        });
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(tr("Annuler")).clicked() {
+                    decision = Some(Decision::Cancel);
+                }
+                let can_delete = !pending.replace || pending.replacement_idx.is_some();
+                if ui
+                    .add_enabled(can_delete, egui::Button::new(tr("🗑 Supprimer")))
+                    .clicked()
+                {
+                    decision = Some(Decision::Delete {
+                        replacement_idx: pending.replacement_idx.filter(|_| pending.replace),
+                    });
+                }
+            });
+        });
+        if modal.should_close() && decision.is_none() {
+            decision = Some(Decision::Cancel);
+        }
+
+        match decision {
+            Some(Decision::Cancel) => self.pending_deletion = None,
+            Some(Decision::Delete { replacement_idx }) => {
+                if let Some(pending) = self.pending_deletion.take() {
+                    self.delete(&pending.item, replacement_idx);
+                }
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::TryRecvError;
+
+use super::{TemplateApp, file_dialog};
+use crate::app_storage::APP_ID;
+use crate::catalog::Catalog;
+use crate::i18n::{tr, trf};
+use crate::persistence::CatalogFile;
+
+/// Number of files listed under "Fichiers récents".
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_RECENT_FILES: usize = 8;
+
+const NEW_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::N);
+
+const OPEN_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::O);
+
+const SAVE_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+
+const SAVE_AS_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::S,
+);
+
+#[cfg(not(target_arch = "wasm32"))]
+fn set_window_title(ctx: &egui::Context, title: &str) {
+    ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.to_owned()));
+}
+
+/// eframe leaves the title of the page alone, so it is set directly.
+#[cfg(target_arch = "wasm32")]
+fn set_window_title(_ctx: &egui::Context, title: &str) {
+    if let Some(document) = web_sys::window().and_then(|window| window.document()) {
+        document.set_title(title);
+    }
+}
+
+/// A file command that drops the current catalog, waiting for its unsaved changes to be
+/// saved or discarded.
+pub(super) enum FileAction {
+    New,
+    Open,
+    #[cfg(not(target_arch = "wasm32"))]
+    OpenRecent(PathBuf),
+    Quit,
+}
+
+enum Decision {
+    Save,
+    Discard,
+    Cancel,
+}
+
+impl TemplateApp {
+    /// Whether the catalog changed since it was last opened or saved as a file.
+    pub(super) fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes || self.history.changes() != self.saved_changes
+    }
+
+    /// Name of the catalog file, "Sans titre" until it is saved.
+    fn document_name(&self) -> String {
+        self.catalog_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or_else(
+                || tr("Sans titre").to_owned(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+    }
+
+    /// Name suggested when saving the catalog.
+    fn document_file_name(&self) -> String {
+        self.catalog_path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or_else(
+                || tr("catalogue.json").to_owned(),
+                |name| name.to_string_lossy().into_owned(),
+            )
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_changes = self.history.changes();
+        self.unsaved_changes = false;
+        self.file_error = None;
+    }
+
+    pub(super) fn file_menu(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let button = |text: &str, shortcut: &egui::KeyboardShortcut| {
+            egui::Button::new(text).shortcut_text(ctx.format_shortcut(shortcut))
+        };
+
+        if ui.add(button(tr("Nouveau"), &NEW_SHORTCUT)).clicked() {
+            self.request_file_action(&ctx, FileAction::New);
+            ui.close();
+        }
+        if ui.add(button(tr("Ouvrir..."), &OPEN_SHORTCUT)).clicked() {
+            self.request_file_action(&ctx, FileAction::Open);
+            ui.close();
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.add_enabled_ui(!self.recent_files.is_empty(), |ui| {
+            ui.menu_button(tr("Fichiers récents"), |ui| {
+                self.recent_files_menu(ui);
+            });
+        });
+
+        ui.separator();
+        if ui.add(button(tr("Enregistrer"), &SAVE_SHORTCUT)).clicked() {
+            self.save_document();
+            ui.close();
+        }
+        if ui
+            .add(button(tr("Enregistrer sous..."), &SAVE_AS_SHORTCUT))
+            .clicked()
+        {
+            self.save_document_as();
+            ui.close();
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            ui.separator();
+            if ui.button(tr("Quitter")).clicked() {
+                // Goes through `close_requested`, which asks about unsaved changes
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn recent_files_menu(&mut self, ui: &mut egui::Ui) {
+        let mut opened = None;
+        for path in &self.recent_files {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            if ui
+                .button(name)
+                .on_hover_text(path.display().to_string())
+                .clicked()
+            {
+                opened = Some(path.clone());
+            }
+        }
+        ui.separator();
+        if ui.button(tr("Effacer la liste")).clicked() {
+            self.recent_files.clear();
+            ui.close();
+        }
+
+        if let Some(path) = opened {
+            self.request_file_action(ui.ctx(), FileAction::OpenRecent(path));
+            ui.close();
+        }
+    }
+
+    pub(super) fn file_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.pending_file_action.is_some() {
+            return;
+        }
+        // Save as first: the save shortcut would also match with Shift held
+        if ctx.input_mut(|input| input.consume_shortcut(&SAVE_AS_SHORTCUT)) {
+            self.save_document_as();
+        } else if ctx.input_mut(|input| input.consume_shortcut(&SAVE_SHORTCUT)) {
+            self.save_document();
+        } else if ctx.input_mut(|input| input.consume_shortcut(&OPEN_SHORTCUT)) {
+            self.request_file_action(ctx, FileAction::Open);
+        } else if ctx.input_mut(|input| input.consume_shortcut(&NEW_SHORTCUT)) {
+            self.request_file_action(ctx, FileAction::New);
+        }
+    }
+
+    /// Carries out `action` right away if the catalog has no unsaved changes, otherwise asks
+    /// what to do with them first.
+    fn request_file_action(&mut self, ctx: &egui::Context, action: FileAction) {
+        if self.has_unsaved_changes() {
+            self.pending_file_action = Some(action);
+        } else {
+            self.perform_file_action(ctx, &action);
+        }
+    }
+
+    fn perform_file_action(&mut self, ctx: &egui::Context, action: &FileAction) {
+        match action {
+            FileAction::New => {
+                let catalog = Catalog {
+                    settings: self.catalog.settings,
+                    ..Catalog::default()
+                };
+                self.replace_catalog(catalog, None);
+            }
+            FileAction::Open => {
+                self.catalog_pick = Some(file_dialog::pick_file(ctx, "JSON", &["json"]));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            FileAction::OpenRecent(path) => match std::fs::read(path) {
+                Ok(bytes) => self.open_catalog(path.clone(), &bytes),
+                Err(err) => {
+                    self.file_error = Some(format!("{}: {err}", path.display()));
+                    self.recent_files.retain(|recent| recent != path);
+                }
+            },
+            FileAction::Quit => {
+                self.allow_close = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    /// Holds the window open while the catalog has unsaved changes, to ask about them.
+    pub(super) fn intercept_close(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.viewport().close_requested())
+            && !self.allow_close
+            && self.has_unsaved_changes()
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.pending_file_action = Some(FileAction::Quit);
+        }
+    }
+
+    pub(super) fn poll_catalog_pick(&mut self) {
+        let Some(receiver) = &self.catalog_pick else {
+            return;
+        };
+        let file = match receiver.try_recv() {
+            Ok(file) => file,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.catalog_pick = None;
+                return;
+            }
+        };
+        self.catalog_pick = None;
+
+        match file {
+            Ok(file) => {
+                let path = file.path.unwrap_or_else(|| file.name.into());
+                self.open_catalog(path, &file.bytes);
+            }
+            Err(err) => self.file_error = Some(err),
+        }
+    }
+
+    /// Replaces the catalog with the one in `bytes`, read from `path`.
+    fn open_catalog(&mut self, path: PathBuf, bytes: &[u8]) {
+        let catalog = std::str::from_utf8(bytes)
+            .map_err(|err| err.to_string())
+            .and_then(CatalogFile::from_json)
+            .and_then(|file| Catalog::try_from(&file));
+        match catalog {
+            Ok(catalog) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                self.add_recent_file(path.clone());
+                self.replace_catalog(catalog, Some(path));
+            }
+            Err(err) => self.file_error = Some(format!("{}: {err}", path.display())),
+        }
+    }
+
+    /// Starts over on `catalog`, saved as `path`.
+    ///
+    /// Selections, forms and the undo history all point into the previous catalog, so only
+    /// the preferences are kept, and the storage error so that the new catalog is not saved
+    /// over an unreadable one either.
+    fn replace_catalog(&mut self, catalog: Catalog, path: Option<PathBuf>) {
+        *self = Self {
+            catalog,
+            catalog_path: path,
+            language: self.language,
+            recent_files: std::mem::take(&mut self.recent_files),
+            storage_error: self.storage_error.take(),
+            ..Self::without_data()
+        };
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn add_recent_file(&mut self, path: PathBuf) {
+        self.recent_files.retain(|recent| *recent != path);
+        self.recent_files.insert(0, path);
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    /// Saves the catalog to its file, asking where first if it has none yet.
+    ///
+    /// Returns whether the catalog was saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_document(&mut self) -> bool {
+        match self.catalog_path.clone() {
+            Some(path) => self.write_document(path),
+            None => self.save_document_as(),
+        }
+    }
+
+    /// Asks where to save the catalog, then saves it there.
+    ///
+    /// Returns whether the catalog was saved.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_document_as(&mut self) -> bool {
+        match file_dialog::choose_save_path(&self.document_file_name()) {
+            Some(path) => self.write_document(path),
+            None => false,
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_document(&mut self, path: PathBuf) -> bool {
+        let json = CatalogFile::from(&self.catalog).to_json();
+        match std::fs::write(&path, json) {
+            Ok(()) => {
+                self.mark_saved();
+                self.add_recent_file(path.clone());
+                self.catalog_path = Some(path);
+                true
+            }
+            Err(err) => {
+                self.file_error = Some(format!("{}: {err}", path.display()));
+                false
+            }
+        }
+    }
+
+    /// Downloads the catalog under the name of its file.
+    ///
+    /// Returns whether the catalog was saved.
+    #[cfg(target_arch = "wasm32")]
+    fn save_document(&mut self) -> bool {
+        let file_name = self.document_file_name();
+        let json = CatalogFile::from(&self.catalog).to_json();
+        match file_dialog::save_bytes(&file_name, json.as_bytes()) {
+            Ok(saved) => {
+                if saved {
+                    self.mark_saved();
+                    self.catalog_path = Some(file_name.into());
+                }
+                saved
+            }
+            Err(err) => {
+                self.file_error = Some(err);
+                false
+            }
+        }
+    }
+
+    /// The browser chooses where downloads go, so this is the same as saving.
+    #[cfg(target_arch = "wasm32")]
+    fn save_document_as(&mut self) -> bool {
+        self.save_document()
+    }
+
+    /// Shows the file name and whether it has unsaved changes in the window title.
+    pub(super) fn update_title(&mut self, ctx: &egui::Context) {
+        let marker = if self.has_unsaved_changes() { "*" } else { "" };
+        let title = format!("{}{marker} — {APP_ID}", self.document_name());
+        if title != self.window_title {
+            set_window_title(ctx, &title);
+            self.window_title = title;
+        }
+    }
+
+    /// Asks whether to save the unsaved changes before a file command drops the catalog.
+    pub(super) fn unsaved_changes_modal(&mut self, ctx: &egui::Context) {
+        if self.pending_file_action.is_none() {
+            return;
+        }
+
+        let name = self.document_name();
+        let mut decision = None;
+        let modal = egui::Modal::new(egui::Id::new("unsaved_changes_modal")).show(ctx, |ui| {
+            ui.heading(tr("Modifications non enregistrées"));
+            ui.label(trf("Enregistrer les modifications de {} ?", &[&name]));
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button(tr("Enregistrer")).clicked() {
+                    decision = Some(Decision::Save);
+                }
+                if ui.button(tr("Ne pas enregistrer")).clicked() {
+                    decision = Some(Decision::Discard);
+                }
+                if ui.button(tr("Annuler")).clicked() {
+                    decision = Some(Decision::Cancel);
+                }
+            });
+        });
+        if modal.should_close() && decision.is_none() {
+            decision = Some(Decision::Cancel);
+        }
+
+        let Some(decision) = decision else {
+            return;
+        };
+        let Some(action) = self.pending_file_action.take() else {
+            return;
+        };
+        match decision {
+            Decision::Save => {
+                if self.save_document() {
+                    self.perform_file_action(ctx, &action);
+                }
+            }
+            Decision::Discard => self.perform_file_action(ctx, &action),
+            Decision::Cancel => {}
+        }
+    }
+}
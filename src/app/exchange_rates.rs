@@ -0,0 +1,154 @@
+use super::{DATE_FORMAT, TemplateApp, number_input, parse_date, today_text};
+use crate::currency::{Currency, ExchangeRate};
+use crate::history::Edit;
+use crate::i18n::tr;
+
+/// Rate being typed in the exchange-rate window.
+pub(super) struct ExchangeRateForm {
+    from: Currency,
+    to: Currency,
+    effective_from: String,
+    rate: f64,
+    error: Option<&'static str>,
+}
+
+impl Default for ExchangeRateForm {
+    fn default() -> Self {
+        Self {
+            from: Currency::Chf,
+            to: Currency::Eur,
+            effective_from: today_text(),
+            rate: 1.0,
+            error: None,
+        }
+    }
+}
+
+fn currency_combo(ui: &mut egui::Ui, id: &str, currency: &mut Currency) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(currency.code())
+        .show_ui(ui, |ui| {
+            for candidate in Currency::ALL {
+                ui.selectable_value(currency, candidate, candidate.code());
+            }
+        });
+}
+
+impl TemplateApp {
+    pub(super) fn exchange_rates_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_exchange_rates;
+        egui::Window::new(tr("Taux de Change"))
+            .id(egui::Id::new("exchange_rates_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                self.exchange_rates_ui(ui);
+            });
+        self.show_exchange_rates = open;
+    }
+
+    fn exchange_rates_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(tr(
+            "Valeur d'une unité de la première devise dans la seconde, à partir de la date indiquée.",
+        ));
+        ui.separator();
+
+        let before = self.catalog.exchange_rates.clone();
+        let mut rate_to_remove = None;
+        egui::Grid::new("exchange_rates")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr("À partir du"));
+                ui.strong(tr("De"));
+                ui.strong(tr("Vers"));
+                ui.strong(tr("Taux"));
+                ui.end_row();
+
+                for (idx, rate) in self.catalog.exchange_rates.rates.iter_mut().enumerate() {
+                    ui.label(rate.effective_from.format(DATE_FORMAT).to_string());
+                    ui.label(rate.from.code());
+                    ui.label(rate.to.code());
+                    ui.add(
+                        number_input(&mut rate.rate)
+                            .speed(0.001)
+                            .range(0.0..=f64::MAX)
+                            .max_decimals(6),
+                    );
+                    if ui.small_button("🗑").clicked() {
+                        rate_to_remove = Some(idx);
+                    }
+                    ui.end_row();
+                }
+            });
+        if self.catalog.exchange_rates != before {
+            self.history.record(Edit::UpdateExchangeRates {
+                before,
+                after: self.catalog.exchange_rates.clone(),
+            });
+        }
+
+        if let Some(idx) = rate_to_remove {
+            let before = self.catalog.exchange_rates.clone();
+            let mut after = before.clone();
+            after.rates.remove(idx);
+            self.history.apply(
+                &mut self.catalog,
+                Edit::UpdateExchangeRates { before, after },
+            );
+        }
+
+        ui.separator();
+        let form = &mut self.exchange_rate_form;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut form.effective_from)
+                    .desired_width(90.0)
+                    .hint_text(tr("AAAA-MM-JJ")),
+            );
+            currency_combo(ui, "exchange_rate_from", &mut form.from);
+            ui.label("→");
+            currency_combo(ui, "exchange_rate_to", &mut form.to);
+            ui.add(
+                number_input(&mut form.rate)
+                    .speed(0.001)
+                    .range(0.0..=f64::MAX)
+                    .max_decimals(6),
+            );
+        });
+        if ui.button(tr("➕ Ajouter le Taux")).clicked() {
+            self.add_exchange_rate();
+        }
+        if let Some(error) = self.exchange_rate_form.error {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+        }
+    }
+
+    fn add_exchange_rate(&mut self) {
+        let form = &mut self.exchange_rate_form;
+        let Some(effective_from) = parse_date(&form.effective_from) else {
+            form.error = Some(tr("Date invalide (AAAA-MM-JJ)"));
+            return;
+        };
+        if form.from == form.to {
+            form.error = Some(tr("Les deux devises doivent être différentes"));
+            return;
+        }
+        if form.rate <= 0.0 {
+            form.error = Some(tr("Le taux doit être positif"));
+            return;
+        }
+
+        let before = self.catalog.exchange_rates.clone();
+        let mut after = before.clone();
+        after.rates.push(ExchangeRate {
+            from: form.from,
+            to: form.to,
+            effective_from,
+            rate: form.rate,
+        });
+        form.error = None;
+        self.history.apply(
+            &mut self.catalog,
+            Edit::UpdateExchangeRates { before, after },
+        );
+    }
+}
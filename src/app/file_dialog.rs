@@ -0,0 +1,120 @@
+use std::sync::mpsc::{self, Receiver};
+
+/// Lets the user save `bytes` as `file_name`: a save dialog on desktop, a download on the web.
+///
+/// Returns `Ok(false)` if the user cancelled the dialog.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn save_bytes(file_name: &str, bytes: &[u8]) -> Result<bool, String> {
+    let Some(path) = choose_save_path(file_name) else {
+        return Ok(false);
+    };
+    std::fs::write(&path, bytes).map_err(|err| format!("{}: {err}", path.display()))?;
+    Ok(true)
+}
+
+/// Asks the user where to save a file suggested as `file_name`; `None` if they cancel.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn choose_save_path(file_name: &str) -> Option<std::path::PathBuf> {
+    let mut dialog = rfd::FileDialog::new().set_file_name(file_name);
+    if let Some((_, extension)) = file_name.rsplit_once('.') {
+        dialog = dialog.add_filter(extension.to_uppercase(), &[extension]);
+    }
+    dialog.save_file()
+}
+
+/// Lets the user save `bytes` as `file_name`: a save dialog on desktop, a download on the web.
+///
+/// Returns `Ok(false)` if the user cancelled the dialog.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn save_bytes(file_name: &str, bytes: &[u8]) -> Result<bool, String> {
+    use eframe::wasm_bindgen::JsCast as _;
+
+    let to_string = |err: eframe::wasm_bindgen::JsValue| format!("{err:?}");
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(to_string)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(to_string)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or("No document")?;
+    let anchor = document
+        .create_element("a")
+        .map_err(to_string)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_element| "Not an anchor element".to_owned())?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(to_string)?;
+    Ok(true)
+}
+
+/// A file chosen by the user.
+pub(super) struct PickedFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+
+    /// Where the file is; the browser does not tell.
+    pub path: Option<std::path::PathBuf>,
+}
+
+/// Lets the user choose a file among `extensions`.
+///
+/// The result arrives on the returned channel, which is dropped without a value if the
+/// user cancels. The browser dialog is asynchronous, so `ctx` is repainted when it is done.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn pick_file(
+    _ctx: &egui::Context,
+    filter_name: &str,
+    extensions: &[&str],
+) -> Receiver<Result<PickedFile, String>> {
+    let (sender, receiver) = mpsc::channel();
+    let dialog = rfd::FileDialog::new().add_filter(filter_name, extensions);
+    if let Some(path) = dialog.pick_file() {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let file = std::fs::read(&path)
+            .map(|bytes| PickedFile {
+                name,
+                bytes,
+                path: Some(path.clone()),
+            })
+            .map_err(|err| format!("{}: {err}", path.display()));
+        sender.send(file).ok();
+    }
+    receiver
+}
+
+/// Lets the user choose a file among `extensions`.
+///
+/// The result arrives on the returned channel, which is dropped without a value if the
+/// user cancels. The browser dialog is asynchronous, so `ctx` is repainted when it is done.
+#[cfg(target_arch = "wasm32")]
+pub(super) fn pick_file(
+    ctx: &egui::Context,
+    filter_name: &str,
+    extensions: &[&str],
+) -> Receiver<Result<PickedFile, String>> {
+    let (sender, receiver) = mpsc::channel();
+    let dialog = rfd::AsyncFileDialog::new().add_filter(filter_name, extensions);
+    let ctx = ctx.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Some(file) = dialog.pick_file().await {
+            let name = file.file_name();
+            let bytes = file.read().await;
+            sender
+                .send(Ok(PickedFile {
+                    name,
+                    bytes,
+                    path: None,
+                }))
+                .ok();
+        }
+        ctx.request_repaint();
+    });
+    receiver
+}
@@ -0,0 +1,286 @@
+use poc_fnb::{Ingredient, Plate};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{TemplateApp, money_input, number_input};
+use crate::catalog::{copy_ingredient, same_name};
+use crate::currency::Currency;
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::unit_change::UnitChange;
+
+/// Every field of an existing ingredient, edited in a dialog and applied on save.
+pub(super) struct IngredientEditForm {
+    ingredient: Rc<RefCell<Ingredient>>,
+    edited: Ingredient,
+    supplier_contact: String,
+
+    /// Currency of the supplier, which the cost price is in.
+    currency: Currency,
+
+    /// Quantity in the new unit of one former unit, when the unit changes.
+    factor: f64,
+    convert_recipes: bool,
+}
+
+impl IngredientEditForm {
+    fn new(ingredient: &Rc<RefCell<Ingredient>>, currency: Currency) -> Self {
+        let edited = copy_ingredient(&ingredient.borrow());
+        Self {
+            ingredient: ingredient.clone(),
+            supplier_contact: edited.supplier.contact.clone().unwrap_or_default(),
+            currency,
+            edited,
+            factor: 1.0,
+            convert_recipes: true,
+        }
+    }
+
+    fn unit_change(&self) -> Option<UnitChange> {
+        if self.edited.unit == self.ingredient.borrow().unit {
+            return None;
+        }
+        let mut change = UnitChange::new(&self.ingredient, self.edited.unit.clone());
+        if !change.is_exact() {
+            change.factor = self.factor;
+        }
+        Some(change)
+    }
+}
+
+impl TemplateApp {
+    pub(super) fn open_ingredient_editor(&mut self, ingredient: &Rc<RefCell<Ingredient>>) {
+        let currency = self.catalog.ingredient_currency(&ingredient.borrow().name);
+        self.ingredient_editor = Some(IngredientEditForm::new(ingredient, currency));
+    }
+
+    /// Why the form cannot be saved, if it cannot.
+    fn ingredient_form_error(&self, form: &IngredientEditForm) -> Option<&'static str> {
+        let name = form.edited.name.trim();
+        if name.is_empty() {
+            return Some(tr("Le nom est obligatoire"));
+        }
+        let taken = self.catalog.ingredients.iter().any(|ingredient| {
+            !Rc::ptr_eq(ingredient, &form.ingredient) && same_name(&ingredient.borrow().name, name)
+        });
+        if taken {
+            return Some(tr("Un autre ingrédient porte déjà ce nom"));
+        }
+        if form.edited.reference_quantity <= 0.0 {
+            return Some(tr("La quantité de référence doit être positive"));
+        }
+        if form.edited.supplier.name.trim().is_empty() {
+            return Some(tr("Le fournisseur est obligatoire"));
+        }
+        if form
+            .unit_change()
+            .is_some_and(|change| change.factor <= 0.0)
+        {
+            return Some(tr("Le facteur de conversion doit être positif"));
+        }
+        None
+    }
+
+    pub(super) fn ingredient_editor_modal(&mut self, ctx: &egui::Context) {
+        let Some(form) = &self.ingredient_editor else {
+            return;
+        };
+        let error = self.ingredient_form_error(form);
+        let Some(form) = &mut self.ingredient_editor else {
+            return;
+        };
+
+        let mut save = false;
+        let mut cancel = false;
+        let modal = egui::Modal::new(egui::Id::new("ingredient_editor")).show(ctx, |ui| {
+            ui.heading(trf("Modifier {}", &[&form.ingredient.borrow().name]));
+            Self::ingredient_fields_ui(ui, form);
+            Self::unit_change_ui(ui, form, &self.catalog.plates);
+
+            ui.separator();
+            if let Some(error) = error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+            }
+            ui.horizontal(|ui| {
+                cancel = ui.button(tr("Annuler")).clicked();
+                save = ui
+                    .add_enabled(error.is_none(), egui::Button::new(tr("Enregistrer")))
+                    .clicked();
+            });
+        });
+
+        if save {
+            if let Some(form) = self.ingredient_editor.take() {
+                self.save_ingredient_form(form);
+            }
+        } else if cancel || modal.should_close() {
+            self.ingredient_editor = None;
+        }
+    }
+
+    fn ingredient_fields_ui(ui: &mut egui::Ui, form: &mut IngredientEditForm) {
+        let edited = &mut form.edited;
+        egui::Grid::new("ingredient_editor_fields")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("Nom :"));
+                ui.text_edit_singleline(&mut edited.name);
+                ui.end_row();
+
+                ui.label(tr("Prix de Revient :"));
+                ui.add(money_input(&mut edited.cost_price, form.currency).speed(0.01));
+                ui.end_row();
+
+                ui.label(tr("Devise du fournisseur :"));
+                egui::ComboBox::from_id_salt("ingredient_editor_currency")
+                    .selected_text(form.currency.code())
+                    .show_ui(ui, |ui| {
+                        for currency in Currency::ALL {
+                            ui.selectable_value(&mut form.currency, currency, currency.code());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr("Quantité de Référence :"));
+                ui.add(number_input(&mut edited.reference_quantity).speed(1.0));
+                ui.end_row();
+
+                ui.label(tr("Unité :"));
+                egui::ComboBox::from_id_salt("ingredient_editor_unit")
+                    .selected_text(Self::unit_label(&edited.unit))
+                    .show_ui(ui, |ui| {
+                        for unit in Self::get_unit_variants() {
+                            let label = Self::unit_label(&unit);
+                            ui.selectable_value(&mut edited.unit, unit, label);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr("Type :"));
+                egui::ComboBox::from_id_salt("ingredient_editor_type")
+                    .selected_text(Self::ingredient_type_label(&edited.ingredient_type))
+                    .show_ui(ui, |ui| {
+                        for ingredient_type in Self::get_ingredient_type_variants() {
+                            let label = Self::ingredient_type_label(&ingredient_type);
+                            ui.selectable_value(
+                                &mut edited.ingredient_type,
+                                ingredient_type,
+                                label,
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr("Fournisseur :"));
+                ui.text_edit_singleline(&mut edited.supplier.name);
+                ui.end_row();
+
+                ui.label(tr("Contact Fournisseur :"));
+                ui.text_edit_singleline(&mut form.supplier_contact);
+                ui.end_row();
+            });
+    }
+
+    /// Conversion of the reference quantity and of the recipe quantities to a new unit.
+    fn unit_change_ui(
+        ui: &mut egui::Ui,
+        form: &mut IngredientEditForm,
+        plates: &[Rc<RefCell<Plate>>],
+    ) {
+        let Some(change) = form.unit_change() else {
+            return;
+        };
+        let from = Self::unit_label(&change.from);
+        let to = Self::unit_label(&change.to);
+
+        ui.separator();
+        ui.label(egui::RichText::new(tr("Changement d'unité")).strong());
+        ui.horizontal(|ui| {
+            if change.is_exact() {
+                ui.label(format!("1 {from} = {} {to}", change.factor));
+            } else {
+                ui.label(format!("1 {from} ="));
+                ui.add(number_input(&mut form.factor).speed(0.1));
+                ui.label(to);
+            }
+        });
+
+        let reference_quantity =
+            change.reference_quantity(form.ingredient.borrow().reference_quantity);
+        ui.horizontal(|ui| {
+            ui.label(trf(
+                "Quantité de référence équivalente : {} {}",
+                &[&format_args!("{reference_quantity:.2}"), &to],
+            ));
+            if ui
+                .small_button(tr("Utiliser"))
+                .on_hover_text(tr("Le prix couvre alors la même quantité qu'avant"))
+                .clicked()
+            {
+                form.edited.reference_quantity = reference_quantity;
+            }
+        });
+
+        let uses = change.incompatible_uses(plates);
+        if uses.is_empty() {
+            return;
+        }
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 200, 100),
+            trf(
+                "⚠ {} quantité(s) de recette ne peuvent pas être converties en {} :",
+                &[&uses.len(), &to],
+            ),
+        );
+        for incompatible in &uses {
+            ui.label(format!(
+                "• {} : {} {} → {} {to}",
+                incompatible.plate.borrow().name,
+                locale::number(incompatible.quantity, 2),
+                Self::unit_label(&incompatible.unit),
+                locale::number(incompatible.converted_quantity, 2)
+            ));
+        }
+        ui.checkbox(
+            &mut form.convert_recipes,
+            tr("Convertir ces quantités avec le facteur ci-dessus"),
+        )
+        .on_hover_text(tr("Sinon, elles restent signalées dans les recettes"));
+    }
+
+    fn save_ingredient_form(&mut self, mut form: IngredientEditForm) {
+        let unit_change = form.unit_change().filter(|_| form.convert_recipes);
+        let contact = form.supplier_contact.trim();
+        form.edited.supplier.contact = (!contact.is_empty()).then(|| contact.to_owned());
+        form.edited.name = form.edited.name.trim().to_owned();
+        form.edited.supplier.name = form.edited.supplier.name.trim().to_owned();
+
+        // Renaming moves the current currency to the new name, where it is then replaced
+        let name = form.edited.name.clone();
+        let currency_before = self
+            .catalog
+            .ingredient_currencies
+            .get(&form.ingredient.borrow().name)
+            .copied();
+        let currency_after =
+            (form.currency != self.catalog.settings.currency).then_some(form.currency);
+
+        let mut edit = Edit::update_ingredient(
+            &self.catalog,
+            &form.ingredient,
+            form.edited,
+            unit_change.as_ref(),
+        );
+        if currency_before != currency_after {
+            if let Edit::Group { edits, .. } = &mut edit {
+                edits.push(Edit::UpdateIngredientCurrency {
+                    ingredient_name: name,
+                    before: currency_before,
+                    after: currency_after,
+                });
+            }
+        }
+        self.history.apply(&mut self.catalog, edit);
+    }
+}
@@ -0,0 +1,402 @@
+use egui_extras::{Column, TableBuilder, TableRow};
+use poc_fnb::{Ingredient, IngredientType, QuantityUnit, Supplier};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::list_filter::compare_f64;
+use super::{TemplateApp, money_input, number_input};
+use crate::catalog::copy_ingredient;
+use crate::costing;
+use crate::currency::Currency;
+use crate::history::{Edit, History};
+use crate::i18n::{tr, trf};
+use crate::locale;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TableColumn {
+    Name,
+    Type,
+    Supplier,
+    Price,
+    ReferenceQuantity,
+    Unit,
+    CostPerBaseUnit,
+}
+
+impl TableColumn {
+    const ALL: [Self; 7] = [
+        Self::Name,
+        Self::Type,
+        Self::Supplier,
+        Self::Price,
+        Self::ReferenceQuantity,
+        Self::Unit,
+        Self::CostPerBaseUnit,
+    ];
+
+    fn title(self) -> &'static str {
+        tr(match self {
+            Self::Name => "Nom",
+            Self::Type => "Type",
+            Self::Supplier => "Fournisseur",
+            Self::Price => "Prix",
+            Self::ReferenceQuantity => "Qté Réf.",
+            Self::Unit => "Unité",
+            Self::CostPerBaseUnit => "Coût Unitaire",
+        })
+    }
+}
+
+pub(super) struct IngredientTable {
+    sort: Option<TableColumn>,
+    descending: bool,
+    selection: Vec<Rc<RefCell<Ingredient>>>,
+    bulk_type: IngredientType,
+    bulk_supplier: String,
+    price_increase_percent: f64,
+
+    /// Ingredient whose edit dialog was requested from its row.
+    edit_requested: Option<Rc<RefCell<Ingredient>>>,
+}
+
+impl Default for IngredientTable {
+    fn default() -> Self {
+        Self {
+            sort: None,
+            descending: false,
+            selection: Vec::new(),
+            bulk_type: IngredientType::Grocery,
+            bulk_supplier: String::new(),
+            price_increase_percent: 5.0,
+            edit_requested: None,
+        }
+    }
+}
+
+impl IngredientTable {
+    fn is_selected(&self, ingredient: &Rc<RefCell<Ingredient>>) -> bool {
+        self.selection
+            .iter()
+            .any(|selected| Rc::ptr_eq(selected, ingredient))
+    }
+
+    fn set_selected(&mut self, ingredient: &Rc<RefCell<Ingredient>>, selected: bool) {
+        self.selection
+            .retain(|existing| !Rc::ptr_eq(existing, ingredient));
+        if selected {
+            self.selection.push(ingredient.clone());
+        }
+    }
+
+    /// Sorts by `column`, or reverses the order when it already is the sort column.
+    fn sort_by(&mut self, column: TableColumn) {
+        if self.sort == Some(column) {
+            self.descending = !self.descending;
+        } else {
+            self.sort = Some(column);
+            self.descending = false;
+        }
+    }
+}
+
+/// An ingredient with its cost per base unit.
+struct TableRowData {
+    ingredient: Rc<RefCell<Ingredient>>,
+
+    /// Currency of the cost price; the unit cost is in the catalog currency.
+    price_currency: Currency,
+    unit_cost: f64,
+    base_unit: QuantityUnit,
+}
+
+impl TemplateApp {
+    pub(super) fn ingredient_table_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_ingredient_table;
+        egui::Window::new(tr("Tableau des Ingrédients"))
+            .id(egui::Id::new("ingredient_table_window"))
+            .open(&mut open)
+            .default_width(900.0)
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.ingredient_table_ui(ui);
+            });
+        self.show_ingredient_table = open;
+    }
+
+    /// Catalog ingredients in the order of the sort column.
+    fn ingredient_table_rows(&self) -> Vec<TableRowData> {
+        let valuation = self.catalog.valuation();
+        let mut rows: Vec<TableRowData> = self
+            .catalog
+            .ingredients
+            .iter()
+            .map(|ingredient| {
+                let (unit_cost, base_unit) = costing::cost_per_base_unit(ingredient, &valuation);
+                TableRowData {
+                    ingredient: ingredient.clone(),
+                    price_currency: self.catalog.ingredient_currency(&ingredient.borrow().name),
+                    unit_cost,
+                    base_unit,
+                }
+            })
+            .collect();
+
+        let Some(column) = self.ingredient_table.sort else {
+            return rows;
+        };
+        rows.sort_by(|a, b| {
+            let (first, second) = (a.ingredient.borrow(), b.ingredient.borrow());
+            match column {
+                TableColumn::Name => first.name.to_lowercase().cmp(&second.name.to_lowercase()),
+                TableColumn::Type => Self::ingredient_type_label(&first.ingredient_type)
+                    .cmp(Self::ingredient_type_label(&second.ingredient_type)),
+                TableColumn::Supplier => first
+                    .supplier
+                    .name
+                    .to_lowercase()
+                    .cmp(&second.supplier.name.to_lowercase()),
+                TableColumn::Price => compare_f64(first.cost_price, second.cost_price),
+                TableColumn::ReferenceQuantity => {
+                    compare_f64(first.reference_quantity, second.reference_quantity)
+                }
+                TableColumn::Unit => {
+                    Self::unit_label(&first.unit).cmp(Self::unit_label(&second.unit))
+                }
+                TableColumn::CostPerBaseUnit => compare_f64(a.unit_cost, b.unit_cost),
+            }
+        });
+        if self.ingredient_table.descending {
+            rows.reverse();
+        }
+        rows
+    }
+
+    fn ingredient_table_ui(&mut self, ui: &mut egui::Ui) {
+        let ingredients = &self.catalog.ingredients;
+        self.ingredient_table.selection.retain(|selected| {
+            ingredients
+                .iter()
+                .any(|ingredient| Rc::ptr_eq(ingredient, selected))
+        });
+
+        if let Some(edit) = self.bulk_actions_ui(ui) {
+            self.history.apply(&mut self.catalog, edit);
+        }
+        ui.separator();
+
+        let rows = self.ingredient_table_rows();
+        let currency = self.catalog.settings.currency;
+        let table = &mut self.ingredient_table;
+        let history = &mut self.history;
+        let row_height = ui.spacing().interact_size.y + 4.0;
+
+        TableBuilder::new(ui)
+            .id_salt("ingredient_table")
+            .striped(true)
+            .resizable(true)
+            .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+            .column(Column::exact(24.0))
+            .columns(Column::auto().at_least(80.0).clip(true), 6)
+            .column(Column::remainder().at_least(100.0))
+            .header(row_height, |mut header| {
+                header.col(|ui| {
+                    let all_selected = !rows.is_empty()
+                        && rows.iter().all(|row| table.is_selected(&row.ingredient));
+                    let mut checked = all_selected;
+                    if ui
+                        .checkbox(&mut checked, "")
+                        .on_hover_text(tr("Tout sélectionner"))
+                        .changed()
+                    {
+                        for row in &rows {
+                            table.set_selected(&row.ingredient, checked);
+                        }
+                    }
+                });
+                for column in TableColumn::ALL {
+                    header.col(|ui| {
+                        let arrow = match (table.sort == Some(column), table.descending) {
+                            (false, _) => "",
+                            (true, false) => " ⬆",
+                            (true, true) => " ⬇",
+                        };
+                        let title = format!("{}{arrow}", column.title());
+                        if ui
+                            .selectable_label(
+                                table.sort == Some(column),
+                                egui::RichText::new(title).strong(),
+                            )
+                            .clicked()
+                        {
+                            table.sort_by(column);
+                        }
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(row_height, rows.len(), |row| {
+                    let data = &rows[row.index()];
+                    Self::ingredient_table_row(row, data, table, history, currency);
+                });
+            });
+
+        if let Some(ingredient) = self.ingredient_table.edit_requested.take() {
+            self.open_ingredient_editor(&ingredient);
+        }
+    }
+
+    /// One editable table row; changes are recorded in `history`.
+    fn ingredient_table_row(
+        mut row: TableRow<'_, '_>,
+        data: &TableRowData,
+        table: &mut IngredientTable,
+        history: &mut History,
+        currency: Currency,
+    ) {
+        let ingredient_rc = &data.ingredient;
+        let mut selected = table.is_selected(ingredient_rc);
+        row.set_selected(selected);
+
+        let mut ingredient = ingredient_rc.borrow_mut();
+        let before = copy_ingredient(&ingredient);
+        let mut changed = false;
+        let id = Rc::as_ptr(ingredient_rc);
+
+        row.col(|ui| {
+            if ui.checkbox(&mut selected, "").changed() {
+                table.set_selected(ingredient_rc, selected);
+            }
+        });
+        row.col(|ui| {
+            if ui.small_button("✏").on_hover_text(tr("Modifier")).clicked() {
+                table.edit_requested = Some(ingredient_rc.clone());
+            }
+            ui.label(&ingredient.name);
+        });
+        row.col(|ui| {
+            egui::ComboBox::from_id_salt(("table_type", id))
+                .selected_text(Self::ingredient_type_label(&ingredient.ingredient_type))
+                .show_ui(ui, |ui| {
+                    for ingredient_type in Self::get_ingredient_type_variants() {
+                        let label = Self::ingredient_type_label(&ingredient_type);
+                        changed |= ui
+                            .selectable_value(
+                                &mut ingredient.ingredient_type,
+                                ingredient_type,
+                                label,
+                            )
+                            .changed();
+                    }
+                });
+        });
+        row.col(|ui| {
+            changed |= ui
+                .add(egui::TextEdit::singleline(&mut ingredient.supplier.name))
+                .changed();
+        });
+        row.col(|ui| {
+            changed |= ui
+                .add(money_input(&mut ingredient.cost_price, data.price_currency).speed(0.01))
+                .changed();
+        });
+        row.col(|ui| {
+            changed |= ui
+                .add(number_input(&mut ingredient.reference_quantity).speed(1.0))
+                .changed();
+        });
+        row.col(|ui| {
+            ui.label(Self::unit_label(&ingredient.unit));
+        });
+        row.col(|ui| {
+            ui.label(format!(
+                "{} / {}",
+                locale::unit_money(data.unit_cost, currency),
+                Self::unit_label(&data.base_unit)
+            ));
+        });
+
+        if changed {
+            history.record(Edit::UpdateIngredient {
+                ingredient: ingredient_rc.clone(),
+                before,
+                after: copy_ingredient(&ingredient),
+            });
+        }
+    }
+
+    /// Actions on the selected ingredients, as a single undo step each.
+    fn bulk_actions_ui(&mut self, ui: &mut egui::Ui) -> Option<Edit> {
+        let table = &mut self.ingredient_table;
+        let count = table.selection.len();
+        let mut edit = None;
+
+        ui.label(trf("{} ingrédient(s) sélectionné(s)", &[&count]));
+        ui.add_enabled_ui(count > 0, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("Type :"));
+                egui::ComboBox::from_id_salt("bulk_type")
+                    .selected_text(Self::ingredient_type_label(&table.bulk_type))
+                    .show_ui(ui, |ui| {
+                        for ingredient_type in Self::get_ingredient_type_variants() {
+                            let label = Self::ingredient_type_label(&ingredient_type);
+                            ui.selectable_value(&mut table.bulk_type, ingredient_type, label);
+                        }
+                    });
+                if ui.button(tr("Appliquer")).clicked() {
+                    let ingredient_type = table.bulk_type.clone();
+                    edit = Some(Edit::update_ingredients(
+                        &table.selection,
+                        trf("Changement de type de {} ingrédient(s)", &[&count]),
+                        |ingredient| ingredient.ingredient_type = ingredient_type.clone(),
+                    ));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Fournisseur :"));
+                ui.text_edit_singleline(&mut table.bulk_supplier);
+                let valid = !table.bulk_supplier.trim().is_empty();
+                if ui
+                    .add_enabled(valid, egui::Button::new(tr("Appliquer")))
+                    .clicked()
+                {
+                    let name = table.bulk_supplier.trim().to_owned();
+                    edit = Some(Edit::update_ingredients(
+                        &table.selection,
+                        trf("Changement de fournisseur de {} ingrédient(s)", &[&count]),
+                        |ingredient| {
+                            if ingredient.supplier.name != name {
+                                ingredient.supplier = Supplier {
+                                    name: name.clone(),
+                                    contact: None,
+                                };
+                            }
+                        },
+                    ));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Hausse de prix :"));
+                ui.add(
+                    number_input(&mut table.price_increase_percent)
+                        .speed(0.5)
+                        .range(-100.0..=1000.0)
+                        .suffix(" %"),
+                );
+                if ui.button(tr("Appliquer")).clicked() {
+                    let percent = table.price_increase_percent;
+                    edit = Some(Edit::update_ingredients(
+                        &table.selection,
+                        trf(
+                            "Hausse de prix de {} % sur {} ingrédient(s)",
+                            &[&percent, &count],
+                        ),
+                        |ingredient| ingredient.cost_price *= 1.0 + percent / 100.0,
+                    ));
+                }
+            });
+        });
+        edit
+    }
+}
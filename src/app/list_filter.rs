@@ -0,0 +1,276 @@
+use poc_fnb::IngredientType;
+use std::cmp::Ordering;
+
+use super::{TemplateApp, number_input};
+use crate::allergen::{self, Allergen};
+use crate::costing::{self, PlateCost};
+use crate::i18n::tr;
+use crate::valuation::Valuation;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum IngredientSort {
+    /// Catalog order.
+    #[default]
+    None,
+    Name,
+    Cost,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) enum PlateSort {
+    /// Catalog order.
+    #[default]
+    None,
+    Name,
+    Cost,
+    MarginRate,
+    FoodCostRate,
+}
+
+#[derive(Default)]
+pub(super) struct IngredientFilter {
+    search: String,
+    ingredient_type: Option<IngredientType>,
+    supplier: Option<String>,
+    allergen: Option<Allergen>,
+    sort: IngredientSort,
+    descending: bool,
+}
+
+#[derive(Default)]
+pub(super) struct PlateFilter {
+    search: String,
+
+    /// Only plates whose margin rate is below this percentage.
+    margin_below: Option<f64>,
+
+    /// Only plates without this allergen.
+    free_of: Option<Allergen>,
+    sort: PlateSort,
+    descending: bool,
+}
+
+/// Case-insensitive substring match.
+fn matches_search(name: &str, search: &str) -> bool {
+    let search = search.trim();
+    search.is_empty() || name.to_lowercase().contains(&search.to_lowercase())
+}
+
+pub(super) fn compare_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Combo offering "Tous" and the given choices.
+fn optional_combo<T: Clone + PartialEq>(
+    ui: &mut egui::Ui,
+    id: &str,
+    value: &mut Option<T>,
+    choices: impl IntoIterator<Item = (T, String)>,
+) {
+    let choices: Vec<(T, String)> = choices.into_iter().collect();
+    let selected_text = value
+        .as_ref()
+        .and_then(|value| choices.iter().find(|(choice, _)| choice == value))
+        .map_or(tr("Tous"), |(_, label)| label.as_str())
+        .to_owned();
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, None, tr("Tous"));
+            for (choice, label) in choices {
+                ui.selectable_value(value, Some(choice), label);
+            }
+        });
+}
+
+fn sort_direction_ui(ui: &mut egui::Ui, descending: &mut bool) {
+    let (text, hover) = if *descending {
+        ("⬇", tr("Ordre décroissant"))
+    } else {
+        ("⬆", tr("Ordre croissant"))
+    };
+    if ui.small_button(text).on_hover_text(hover).clicked() {
+        *descending = !*descending;
+    }
+}
+
+impl TemplateApp {
+    pub(super) fn ingredient_filter_ui(&mut self, ui: &mut egui::Ui) {
+        let mut suppliers: Vec<String> = self
+            .catalog
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.borrow().supplier.name.clone())
+            .collect();
+        suppliers.sort();
+        suppliers.dedup();
+
+        let filter = &mut self.ingredient_filter;
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut filter.search).hint_text(tr("Rechercher...")));
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Type :"));
+            optional_combo(
+                ui,
+                "ingredient_filter_type",
+                &mut filter.ingredient_type,
+                Self::get_ingredient_type_variants()
+                    .into_iter()
+                    .map(|ingredient_type| {
+                        let label = Self::ingredient_type_label(&ingredient_type).to_owned();
+                        (ingredient_type, label)
+                    }),
+            );
+            ui.label(tr("Fournisseur :"));
+            optional_combo(
+                ui,
+                "ingredient_filter_supplier",
+                &mut filter.supplier,
+                suppliers.into_iter().map(|name| (name.clone(), name)),
+            );
+            ui.label(tr("Allergène :"));
+            optional_combo(
+                ui,
+                "ingredient_filter_allergen",
+                &mut filter.allergen,
+                Allergen::ALL.map(|allergen| (allergen, Self::allergen_label(allergen).to_owned())),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("Trier par :"));
+            ui.selectable_value(&mut filter.sort, IngredientSort::None, tr("Catalogue"));
+            ui.selectable_value(&mut filter.sort, IngredientSort::Name, tr("Nom"));
+            ui.selectable_value(&mut filter.sort, IngredientSort::Cost, tr("Coût"));
+            sort_direction_ui(ui, &mut filter.descending);
+        });
+    }
+
+    /// Indices of the ingredients to show, in display order.
+    pub(super) fn filtered_ingredients(&self) -> Vec<usize> {
+        let filter = &self.ingredient_filter;
+        let valuation = self.catalog.valuation();
+        let mut rows: Vec<(usize, String, f64)> = self
+            .catalog
+            .ingredients
+            .iter()
+            .enumerate()
+            .filter(|(_, ingredient_rc)| {
+                let ingredient = ingredient_rc.borrow();
+                matches_search(&ingredient.name, &filter.search)
+                    && filter
+                        .ingredient_type
+                        .as_ref()
+                        .is_none_or(|ingredient_type| {
+                            ingredient.ingredient_type == *ingredient_type
+                        })
+                    && filter
+                        .supplier
+                        .as_ref()
+                        .is_none_or(|supplier| ingredient.supplier.name == *supplier)
+                    && filter.allergen.is_none_or(|allergen| {
+                        self.catalog
+                            .allergens
+                            .get(&ingredient.name)
+                            .is_some_and(|allergens| allergens.contains(&allergen))
+                    })
+            })
+            .map(|(idx, ingredient_rc)| {
+                let name = ingredient_rc.borrow().name.to_lowercase();
+                let (cost, _) = costing::cost_per_base_unit(ingredient_rc, &valuation);
+                (idx, name, cost)
+            })
+            .collect();
+
+        match filter.sort {
+            IngredientSort::None => {}
+            IngredientSort::Name => rows.sort_by(|a, b| a.1.cmp(&b.1)),
+            IngredientSort::Cost => rows.sort_by(|a, b| compare_f64(a.2, b.2)),
+        }
+        if filter.descending {
+            rows.reverse();
+        }
+        rows.into_iter().map(|(idx, _, _)| idx).collect()
+    }
+
+    pub(super) fn plate_filter_ui(&mut self, ui: &mut egui::Ui) {
+        let filter = &mut self.plate_filter;
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.add(egui::TextEdit::singleline(&mut filter.search).hint_text(tr("Rechercher...")));
+        });
+        ui.horizontal_wrapped(|ui| {
+            let mut below = filter.margin_below.is_some();
+            if ui.checkbox(&mut below, tr("Marge inférieure à")).changed() {
+                filter.margin_below = below.then_some(60.0);
+            }
+            if let Some(threshold) = &mut filter.margin_below {
+                ui.add(
+                    number_input(threshold)
+                        .speed(0.5)
+                        .range(-100.0..=100.0)
+                        .suffix(" %"),
+                );
+            }
+            ui.label(tr("Sans allergène :"));
+            optional_combo(
+                ui,
+                "plate_filter_allergen",
+                &mut filter.free_of,
+                Allergen::ALL.map(|allergen| (allergen, Self::allergen_label(allergen).to_owned())),
+            );
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("Trier par :"));
+            ui.selectable_value(&mut filter.sort, PlateSort::None, tr("Catalogue"));
+            ui.selectable_value(&mut filter.sort, PlateSort::Name, tr("Nom"));
+            ui.selectable_value(&mut filter.sort, PlateSort::Cost, tr("Coût"));
+            ui.selectable_value(&mut filter.sort, PlateSort::MarginRate, tr("Taux de marge"));
+            ui.selectable_value(&mut filter.sort, PlateSort::FoodCostRate, tr("Food cost %"));
+            sort_direction_ui(ui, &mut filter.descending);
+        });
+    }
+
+    /// Indices of the plates to show, in display order.
+    pub(super) fn filtered_plates(&self, valuation: &Valuation<'_>) -> Vec<usize> {
+        let filter = &self.plate_filter;
+        let mut rows: Vec<(usize, String, PlateCost)> = self
+            .catalog
+            .plates
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, plate_rc)| {
+                let plate = plate_rc.borrow();
+                let cost = PlateCost::of(&plate, valuation);
+                let shown = matches_search(&plate.name, &filter.search)
+                    && filter
+                        .margin_below
+                        .is_none_or(|threshold| cost.margin_rate() * 100.0 < threshold)
+                    && filter.free_of.is_none_or(|allergen| {
+                        !allergen::plate_allergens(&plate, &self.catalog.allergens)
+                            .contains(&allergen)
+                    });
+                shown.then(|| (idx, plate.name.to_lowercase(), cost))
+            })
+            .collect();
+
+        match filter.sort {
+            PlateSort::None => {}
+            PlateSort::Name => rows.sort_by(|a, b| a.1.cmp(&b.1)),
+            PlateSort::Cost => {
+                rows.sort_by(|a, b| a.2.total_cost_price().cmp(&b.2.total_cost_price()));
+            }
+            PlateSort::MarginRate => {
+                rows.sort_by(|a, b| compare_f64(a.2.margin_rate(), b.2.margin_rate()));
+            }
+            PlateSort::FoodCostRate => {
+                rows.sort_by(|a, b| compare_f64(a.2.food_cost_rate(), b.2.food_cost_rate()));
+            }
+        }
+        if filter.descending {
+            rows.reverse();
+        }
+        rows.into_iter().map(|(idx, _, _)| idx).collect()
+    }
+}
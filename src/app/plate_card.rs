@@ -0,0 +1,370 @@
+use poc_fnb::{Plate, PlateComponent, QuantityUnit};
+
+use super::recipe_editor::{RecipeEditForm, RecipeFormAction};
+use super::{TemplateApp, money_input, number_input};
+use crate::catalog::{Catalog, copy_plate};
+use crate::cost_tree::CostNode;
+use crate::costing::{self, PlateCost};
+use crate::currency::Currency;
+use crate::dependencies;
+use crate::health::Status;
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::money::Money;
+use crate::recipe_method::RecipeMethod;
+use crate::units;
+use crate::valuation::Valuation;
+
+/// What the user did on a plate card, carried out once the list no longer borrows the
+/// catalog.
+pub(super) enum PlateAction {
+    Delete,
+    ExportRecipeCard,
+    Edit,
+    Duplicate,
+
+    /// The edit form was saved or cancelled.
+    FinishEdit(RecipeFormAction),
+
+    PickPhoto,
+
+    /// Fields or components were edited in place; `before` is the plate as it was.
+    Changed {
+        before: Plate,
+    },
+
+    /// The method was edited into `after`.
+    UpdateMethod {
+        after: RecipeMethod,
+    },
+}
+
+impl TemplateApp {
+    /// Card of the plate at `idx`: costs, components, cost tree and method. `recipe_form` is
+    /// given while the plate is being edited.
+    pub(super) fn plate_card_ui(
+        ui: &mut egui::Ui,
+        catalog: &Catalog,
+        valuation: &Valuation<'_>,
+        idx: usize,
+        recipe_form: Option<&mut RecipeEditForm>,
+    ) -> Option<PlateAction> {
+        let currency = catalog.settings.currency;
+        let thresholds = &catalog.settings.margin_thresholds;
+        let plate_rc = &catalog.plates[idx];
+        let mut plate = plate_rc.borrow_mut();
+        let mut cost = PlateCost::of(&plate, valuation);
+        let before = copy_plate(&plate);
+        let mut changed = false;
+        let mut action = None;
+
+        ui.group(|ui| {
+            let status = thresholds.status(&cost);
+            action = ui
+                .horizontal(|ui| Self::plate_header_ui(ui, &plate.name, status))
+                .inner;
+
+            if let Some(form) = recipe_form {
+                if let Some(form_action) =
+                    Self::recipe_form_ui(ui, form, &catalog.plates, plate_rc, currency)
+                {
+                    action = Some(PlateAction::FinishEdit(form_action));
+                }
+            }
+
+            // Show cost breakdown
+            ui.horizontal(|ui| {
+                ui.label(tr("Coût Ingrédients :"));
+                ui.label(locale::money(cost.ingredient_cost, currency));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Coût Main-d'œuvre :"));
+                ui.label(locale::money(cost.labor_cost, currency));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Coût Total :"));
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    locale::money(cost.total_cost_price(), currency),
+                );
+            });
+
+            // Editable selling price
+            ui.horizontal(|ui| {
+                ui.label(tr("Prix de Vente :"));
+                changed |= ui
+                    .add(money_input(&mut plate.selling_price, currency).speed(0.1))
+                    .changed();
+            });
+            cost.selling_price = Money::from_f64(plate.selling_price).round();
+
+            // Sub-recipes have no selling price, hence no status
+            let margin_color = thresholds
+                .status(&cost)
+                .map_or_else(|| ui.visuals().text_color(), Self::status_color);
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Marge Brute :"));
+                ui.colored_label(margin_color, locale::money(cost.gross_margin(), currency));
+            });
+
+            ui.horizontal(|ui| {
+                ui.label(tr("Taux de Marge :"));
+                ui.colored_label(margin_color, locale::percent(cost.margin_rate() * 100.0, 1));
+            });
+
+            // Show and edit components
+            egui::CollapsingHeader::new(tr("Composants"))
+                .id_salt(format!("components_plate_{}", plate.name))
+                .show(ui, |ui| {
+                    changed |= Self::components_ui(ui, catalog, valuation, idx, &mut plate);
+                });
+
+            egui::CollapsingHeader::new(tr("Arbre des Coûts"))
+                .id_salt(format!("cost_tree_plate_{}", plate.name))
+                .show(ui, |ui| {
+                    Self::cost_tree_ui(ui, &CostNode::of(&plate, valuation), currency);
+                });
+
+            egui::CollapsingHeader::new(tr("Méthode"))
+                .id_salt(format!("method_plate_{}", plate.name))
+                .show(ui, |ui| {
+                    let method_before = catalog.recipe_methods.get(&plate.name);
+                    let mut method = method_before.cloned().unwrap_or_default();
+                    if Self::recipe_method_ui(ui, &plate.name, &mut method) {
+                        action = Some(PlateAction::PickPhoto);
+                    }
+                    if method_before.map_or(!method.is_empty(), |before| *before != method) {
+                        action = Some(PlateAction::UpdateMethod { after: method });
+                    }
+                });
+        });
+
+        // Only one widget changes per frame, and an edit must not go unrecorded
+        if changed {
+            action = Some(PlateAction::Changed { before });
+        }
+        action
+    }
+
+    /// Name and status of the plate, with the buttons acting on it.
+    fn plate_header_ui(
+        ui: &mut egui::Ui,
+        name: &str,
+        status: Option<Status>,
+    ) -> Option<PlateAction> {
+        let mut action = None;
+        ui.heading(name);
+        if let Some(status) = status {
+            ui.colored_label(Self::status_color(status), "●")
+                .on_hover_text(tr("Marge par rapport aux seuils d'alerte"));
+        }
+        if ui.small_button("🗑").clicked() {
+            action = Some(PlateAction::Delete);
+        }
+        if ui
+            .small_button("📄")
+            .on_hover_text(tr("Exporter la fiche technique (PDF)"))
+            .clicked()
+        {
+            action = Some(PlateAction::ExportRecipeCard);
+        }
+        if ui.small_button("✏").on_hover_text(tr("Modifier")).clicked() {
+            action = Some(PlateAction::Edit);
+        }
+        if ui
+            .small_button("⧉")
+            .on_hover_text(tr("Dupliquer la recette"))
+            .clicked()
+        {
+            action = Some(PlateAction::Duplicate);
+        }
+        action
+    }
+
+    /// Pickers adding ingredients and sub-recipes to `plate`, the plate at `idx`, and its
+    /// components with their quantity and cost. Returns whether the components changed.
+    fn components_ui(
+        ui: &mut egui::Ui,
+        catalog: &Catalog,
+        valuation: &Valuation<'_>,
+        idx: usize,
+        plate: &mut Plate,
+    ) -> bool {
+        let currency = catalog.settings.currency;
+        let plate_rc = &catalog.plates[idx];
+        let mut changed = false;
+
+        // Add ingredient to recipe
+        ui.horizontal(|ui| {
+            ui.label(tr("Ajouter un ingrédient :"));
+            egui::ComboBox::from_id_salt(format!("add_ingredient_{idx}"))
+                .selected_text(tr("Sélectionner un ingrédient..."))
+                .show_ui(ui, |ui| {
+                    for ingredient_rc in &catalog.ingredients {
+                        let ingredient = ingredient_rc.borrow();
+                        if ui.selectable_label(false, &ingredient.name).clicked() {
+                            // Add ingredient to recipe with default quantity
+                            plate.components.push(PlateComponent::Ingredient {
+                                ingredient: ingredient_rc.clone(),
+                                quantity: 100.0,
+                                unit: QuantityUnit::Gram,
+                            });
+                            changed = true;
+                        }
+                    }
+                });
+        });
+
+        // Add sub-recipe, leaving out those that would contain this plate
+        ui.horizontal(|ui| {
+            ui.label(tr("Ajouter une sous-recette :"));
+            egui::ComboBox::from_id_salt(("add_sub_recipe", idx))
+                .selected_text(tr("Sélectionner une recette..."))
+                .show_ui(ui, |ui| {
+                    for candidate_rc in &catalog.plates {
+                        if dependencies::uses_plate(candidate_rc, plate_rc) {
+                            continue;
+                        }
+                        let candidate = candidate_rc.borrow();
+                        if ui.selectable_label(false, &candidate.name).clicked() {
+                            plate.components.push(PlateComponent::Plate {
+                                plate: candidate_rc.clone(),
+                                quantity: 1.0,
+                            });
+                            changed = true;
+                        }
+                    }
+                });
+        });
+
+        // Display and edit existing components
+        let mut components_to_remove = Vec::new();
+        for (comp_idx, component) in plate.components.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                changed |= Self::component_ui(ui, component, valuation, currency);
+                if ui.small_button("🗑").clicked() {
+                    components_to_remove.push(comp_idx);
+                }
+            });
+        }
+
+        // Remove components marked for deletion
+        for &idx_to_remove in components_to_remove.iter().rev() {
+            plate.components.remove(idx_to_remove);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Quantity and cost of one component. Returns whether the quantity changed.
+    fn component_ui(
+        ui: &mut egui::Ui,
+        component: &mut PlateComponent,
+        valuation: &Valuation<'_>,
+        currency: Currency,
+    ) -> bool {
+        match component {
+            PlateComponent::Ingredient {
+                ingredient,
+                quantity,
+                unit,
+            } => {
+                let ing = ingredient.borrow();
+                ui.label(&ing.name);
+                let changed = ui
+                    .add(
+                        number_input(quantity)
+                            .speed(1.0)
+                            .suffix(format!(" {}", Self::unit_label(unit)))
+                            .range(0.1..=10000.0),
+                    )
+                    .changed();
+                if units::convert(1.0, unit, &ing.unit).is_none() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 200, 100), "⚠")
+                        .on_hover_text(trf(
+                            "Unité incompatible avec l'unité d'achat ({}) : \
+                             quantité comptée comme des {}",
+                            &[&Self::unit_label(&ing.unit), &Self::unit_label(&ing.unit)],
+                        ));
+                }
+
+                // Per gram or milliliter, as valued in the totals
+                let base_unit = units::base_unit(&ing.unit);
+                let base_unit_cost = valuation.unit_cost_of(ingredient)
+                    * units::convert(1.0, &base_unit, &ing.unit).unwrap_or(1.0);
+                ui.label(format!(
+                    "@ {}/{}",
+                    locale::unit_money(base_unit_cost, currency),
+                    Self::unit_label(&base_unit)
+                ));
+                changed
+            }
+            PlateComponent::Plate {
+                plate: sub_plate,
+                quantity,
+            } => {
+                let sub = sub_plate.borrow();
+                ui.label(&sub.name);
+                let changed = ui
+                    .add(
+                        number_input(quantity)
+                            .speed(0.1)
+                            .suffix(format!(" {}", Self::unit_label(&sub.batch_unit)))
+                            .range(0.0..=10000.0),
+                    )
+                    .changed();
+                let unit_cost = costing::batch_cost_per_unit(&sub, valuation);
+                ui.label(format!(
+                    "@ {} / {} = {}",
+                    locale::unit_money(unit_cost, currency),
+                    Self::unit_label(&sub.batch_unit),
+                    locale::money(unit_cost.times(*quantity), currency)
+                ));
+                changed
+            }
+        }
+    }
+
+    /// Carries out `action` on the plate at `idx`.
+    pub(super) fn apply_plate_action(
+        &mut self,
+        ctx: &egui::Context,
+        idx: usize,
+        action: PlateAction,
+    ) {
+        match action {
+            PlateAction::Delete => self.request_plate_deletion(idx),
+            PlateAction::ExportRecipeCard => self.export_recipe_card(idx),
+            PlateAction::Edit => self.start_recipe_edit(idx),
+            PlateAction::Duplicate => self.duplicate_recipe(idx),
+            PlateAction::FinishEdit(form_action) => self.finish_recipe_edit(&form_action),
+            PlateAction::PickPhoto => self.pick_photo(ctx, idx),
+            PlateAction::Changed { before } => {
+                let plate = self.catalog.plates[idx].clone();
+                let after = copy_plate(&plate.borrow());
+                self.history.record(Edit::UpdatePlate {
+                    plate,
+                    before,
+                    after,
+                });
+            }
+            PlateAction::UpdateMethod { after } => {
+                let plate_name = self.catalog.plates[idx].borrow().name.clone();
+                let before = self
+                    .catalog
+                    .recipe_methods
+                    .insert(plate_name.clone(), after.clone())
+                    .unwrap_or_default();
+                self.history.record(Edit::UpdateMethod {
+                    plate_name,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::fmt::Write as _;
+
+use super::{TemplateApp, number_input, today_text};
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::production::{Forecast, PrepList, plan_production};
+
+impl TemplateApp {
+    pub(super) fn production_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_production;
+        egui::Window::new(tr("Plan de Production"))
+            .id(egui::Id::new("production_plan_window"))
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.production_ui(ui);
+                });
+            });
+        self.show_production = open;
+    }
+
+    fn production_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Date de Production :"));
+            ui.text_edit_singleline(&mut self.production_date)
+                .on_hover_text(tr("AAAA-MM-JJ"));
+        });
+
+        egui::CollapsingHeader::new(tr("Prévisions (couverts par plat)"))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("production_forecast")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for plate_rc in &self.catalog.plates {
+                            let plate = plate_rc.borrow();
+                            let covers = self
+                                .production_forecast
+                                .entry(plate.name.clone())
+                                .or_default();
+                            ui.label(&plate.name);
+                            ui.add(
+                                number_input(covers)
+                                    .speed(1.0)
+                                    .range(0.0..=10000.0)
+                                    .suffix(format!(" {}", Self::unit_label(&plate.batch_unit))),
+                            );
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.separator();
+
+        let forecast: Vec<Forecast> = self
+            .catalog
+            .plates
+            .iter()
+            .filter_map(|plate_rc| {
+                let covers = *self.production_forecast.get(&plate_rc.borrow().name)?;
+                Some(Forecast {
+                    plate: plate_rc.clone(),
+                    covers,
+                })
+            })
+            .collect();
+
+        match plan_production(&forecast) {
+            Ok(prep_list) => {
+                let text = self.prep_list_text(&prep_list);
+                ui.horizontal(|ui| {
+                    ui.heading(tr("Liste de Préparation"));
+                    if ui.button(tr("📋 Copier pour Impression")).clicked() {
+                        ui.ctx().copy_text(text.clone());
+                    }
+                });
+                egui::Frame::group(ui.style()).show(ui, |ui| {
+                    ui.add(egui::Label::new(egui::RichText::new(text).monospace()).extend());
+                });
+            }
+            Err(error) => {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    trf("Impossible de planifier : {}", &[&error]),
+                );
+            }
+        }
+    }
+
+    /// Plain-text prep list, laid out to be printed or pasted as is.
+    fn prep_list_text(&self, prep_list: &PrepList) -> String {
+        let mut text = String::new();
+        let date = if self.production_date.trim().is_empty() {
+            today_text()
+        } else {
+            self.production_date.trim().to_owned()
+        };
+        writeln!(text, "{}", trf("LISTE DE PRÉPARATION — {}", &[&date])).ok();
+        writeln!(text).ok();
+        writeln!(
+            text,
+            "{:<28} {:>12} {:<12} {:>5} {:>8}",
+            tr("Recette"),
+            tr("Quantité"),
+            tr("Unité"),
+            tr("Lots"),
+            tr("Heures")
+        )
+        .ok();
+        writeln!(text, "{}", "-".repeat(69)).ok();
+        for item in &prep_list.items {
+            let plate = item.plate.borrow();
+            writeln!(
+                text,
+                "{:<28} {:>12} {:<12} {:>5} {:>8}   [ ]",
+                plate.name,
+                locale::number(item.required_quantity, 1),
+                Self::unit_label(&plate.batch_unit),
+                item.batches,
+                locale::number(item.labor_hours, 2),
+            )
+            .ok();
+        }
+        writeln!(text, "{}", "-".repeat(69)).ok();
+        writeln!(
+            text,
+            "{}",
+            trf(
+                "Main-d'œuvre totale : {} h",
+                &[&locale::number(prep_list.total_labor_hours, 2)],
+            )
+        )
+        .ok();
+        text
+    }
+}
@@ -0,0 +1,396 @@
+use poc_fnb::{Ingredient, QuantityUnit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{DATE_FORMAT, TemplateApp, money_input, number_input, parse_date, today_text};
+use crate::catalog::copy_ingredient;
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::inventory::{Delivery, DeliveryLine, PriceUpdatePolicy};
+use crate::locale;
+
+/// Delivery being typed in the receiving window.
+pub(super) struct ReceivingForm {
+    supplier: String,
+    order_reference: String,
+    received_on: String,
+    lines: Vec<ReceivingFormLine>,
+    error: Option<String>,
+}
+
+impl Default for ReceivingForm {
+    fn default() -> Self {
+        Self {
+            supplier: String::new(),
+            order_reference: String::new(),
+            received_on: today_text(),
+            lines: vec![ReceivingFormLine::default()],
+            error: None,
+        }
+    }
+}
+
+struct ReceivingFormLine {
+    ingredient_idx: Option<usize>,
+    quantity: f64,
+    unit: QuantityUnit,
+    unit_price: f64,
+    has_ordered_price: bool,
+    ordered_unit_price: f64,
+    lot_number: String,
+    use_by: String,
+}
+
+impl Default for ReceivingFormLine {
+    fn default() -> Self {
+        Self {
+            ingredient_idx: None,
+            quantity: 1.0,
+            unit: QuantityUnit::Kilogram,
+            unit_price: 0.0,
+            has_ordered_price: false,
+            ordered_unit_price: 0.0,
+            lot_number: String::new(),
+            use_by: String::new(),
+        }
+    }
+}
+
+impl TemplateApp {
+    pub(super) fn receiving_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_receiving;
+        egui::Window::new(tr("Réception de Marchandises"))
+            .id(egui::Id::new("receiving_window"))
+            .open(&mut open)
+            .default_width(800.0)
+            .show(ctx, |ui| {
+                self.receiving_ui(ui);
+            });
+        self.show_receiving = open;
+    }
+
+    fn receiving_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("Fournisseur :"));
+            ui.text_edit_singleline(&mut self.receiving_form.supplier);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("N° de Commande :"));
+            ui.text_edit_singleline(&mut self.receiving_form.order_reference)
+                .on_hover_text(tr("Laisser vide pour une réception sans commande"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Date de Réception :"));
+            ui.text_edit_singleline(&mut self.receiving_form.received_on)
+                .on_hover_text(tr("AAAA-MM-JJ"));
+        });
+
+        let settings_before = self.catalog.settings;
+        ui.horizontal(|ui| {
+            ui.label(tr("Mise à jour du prix :"));
+            ui.radio_value(
+                &mut self.catalog.settings.price_update_policy,
+                PriceUpdatePolicy::LastPrice,
+                tr("Dernier prix"),
+            );
+            ui.radio_value(
+                &mut self.catalog.settings.price_update_policy,
+                PriceUpdatePolicy::WeightedAverage,
+                tr("Prix moyen pondéré"),
+            );
+        });
+        self.record_settings_change(settings_before);
+
+        ui.separator();
+        self.receiving_lines_ui(ui);
+
+        ui.horizontal(|ui| {
+            if ui.button(tr("➕ Ajouter une Ligne")).clicked() {
+                self.receiving_form.lines.push(ReceivingFormLine::default());
+            }
+            if ui.button(tr("Valider la Réception")).clicked() {
+                self.validate_receiving();
+            }
+        });
+
+        if let Some(error) = &self.receiving_form.error {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+        }
+
+        self.receiving_report_ui(ui);
+    }
+
+    fn receiving_lines_ui(&mut self, ui: &mut egui::Ui) {
+        let currency = self.catalog.settings.currency;
+        let mut line_to_remove = None;
+        egui::Grid::new("receiving_lines")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr("Ingrédient"));
+                ui.strong(tr("Quantité"));
+                ui.strong(tr("Unité"));
+                ui.strong(tr("Prix Unitaire"));
+                ui.strong(tr("Prix Commandé"));
+                ui.strong(tr("N° de Lot"));
+                ui.strong(tr("DLC"));
+                ui.end_row();
+
+                for (line_idx, line) in self.receiving_form.lines.iter_mut().enumerate() {
+                    let selected_name = line
+                        .ingredient_idx
+                        .and_then(|idx| self.catalog.ingredients.get(idx))
+                        .map(|ingredient| ingredient.borrow().name.clone())
+                        .unwrap_or_else(|| tr("Sélectionner...").to_owned());
+                    egui::ComboBox::from_id_salt(("receiving_ingredient", line_idx))
+                        .selected_text(selected_name)
+                        .show_ui(ui, |ui| {
+                            for (idx, ingredient_rc) in self.catalog.ingredients.iter().enumerate()
+                            {
+                                let ingredient = ingredient_rc.borrow();
+                                if ui
+                                    .selectable_label(
+                                        line.ingredient_idx == Some(idx),
+                                        &ingredient.name,
+                                    )
+                                    .clicked()
+                                {
+                                    line.ingredient_idx = Some(idx);
+                                    line.unit = ingredient.unit.clone();
+                                }
+                            }
+                        });
+
+                    ui.add(
+                        number_input(&mut line.quantity)
+                            .speed(0.1)
+                            .range(0.0..=100000.0),
+                    );
+
+                    egui::ComboBox::from_id_salt(("receiving_unit", line_idx))
+                        .selected_text(Self::unit_label(&line.unit))
+                        .show_ui(ui, |ui| {
+                            for unit in Self::get_unit_variants() {
+                                let label = Self::unit_label(&unit);
+                                ui.selectable_value(&mut line.unit, unit, label);
+                            }
+                        });
+
+                    // Invoiced in the currency of the supplier
+                    let price_currency = line
+                        .ingredient_idx
+                        .and_then(|idx| self.catalog.ingredients.get(idx))
+                        .map_or(currency, |ingredient| {
+                            self.catalog.ingredient_currency(&ingredient.borrow().name)
+                        });
+                    ui.add(money_input(&mut line.unit_price, price_currency).speed(0.01));
+
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut line.has_ordered_price, "");
+                        ui.add_enabled(
+                            line.has_ordered_price,
+                            money_input(&mut line.ordered_unit_price, price_currency).speed(0.01),
+                        );
+                    });
+
+                    ui.add(egui::TextEdit::singleline(&mut line.lot_number).desired_width(80.0));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut line.use_by)
+                            .desired_width(90.0)
+                            .hint_text(tr("AAAA-MM-JJ")),
+                    );
+
+                    if ui.small_button("🗑").clicked() {
+                        line_to_remove = Some(line_idx);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(line_idx) = line_to_remove {
+            self.receiving_form.lines.remove(line_idx);
+        }
+    }
+
+    fn receiving_report_ui(&self, ui: &mut egui::Ui) {
+        if let Some(report) = &self.receiving_report {
+            ui.separator();
+            ui.label(trf(
+                "{} ligne(s) réceptionnée(s)",
+                &[&report.received_lines],
+            ));
+            for name in &report.rejected_lines {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 100, 100),
+                    trf(
+                        "{} : unité incompatible ou quantité nulle, ligne ignorée",
+                        &[name],
+                    ),
+                );
+            }
+            for discrepancy in &report.discrepancies {
+                let currency = self
+                    .catalog
+                    .ingredient_currency(&discrepancy.ingredient_name);
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 180, 80),
+                    trf(
+                        "{} (lot {}) : commandé {}, facturé {} par {}, écart {}",
+                        &[
+                            &discrepancy.ingredient_name,
+                            &discrepancy.lot_number,
+                            &locale::money(discrepancy.ordered_unit_price, currency),
+                            &locale::money(discrepancy.invoiced_unit_price, currency),
+                            &Self::unit_label(&discrepancy.unit),
+                            &locale::signed_money(discrepancy.total_difference(), currency),
+                        ],
+                    ),
+                );
+            }
+        }
+
+        ui.separator();
+
+        egui::CollapsingHeader::new(tr("Lots en Stock")).show(ui, |ui| {
+            let today = chrono::Local::now().date_naive();
+            for lot in self
+                .catalog
+                .inventory
+                .lots
+                .iter()
+                .filter(|lot| lot.remaining_quantity > 0.0)
+            {
+                let ingredient = lot.ingredient.borrow();
+                let use_by = lot
+                    .use_by
+                    .map(|date| date.format(DATE_FORMAT).to_string())
+                    .unwrap_or_else(|| "-".to_owned());
+                let text = trf(
+                    "{} — lot {} — {} {} — DLC {}",
+                    &[
+                        &ingredient.name,
+                        &lot.lot_number,
+                        &locale::number(lot.remaining_quantity, 2),
+                        &Self::unit_label(&ingredient.unit),
+                        &use_by,
+                    ],
+                );
+                if lot.is_expired(today) {
+                    ui.colored_label(egui::Color32::from_rgb(255, 100, 100), text);
+                } else {
+                    ui.label(text);
+                }
+            }
+        });
+    }
+
+    fn validate_receiving(&mut self) {
+        let form = &mut self.receiving_form;
+        form.error = None;
+
+        let Some(received_on) = parse_date(&form.received_on) else {
+            form.error = Some(tr("Date de réception invalide (AAAA-MM-JJ)").to_owned());
+            return;
+        };
+
+        let mut lines = Vec::new();
+        for line in &form.lines {
+            let Some(ingredient) = line
+                .ingredient_idx
+                .and_then(|idx| self.catalog.ingredients.get(idx))
+            else {
+                form.error = Some(tr("Chaque ligne doit avoir un ingrédient").to_owned());
+                return;
+            };
+            let use_by = if line.use_by.trim().is_empty() {
+                None
+            } else if let Some(date) = parse_date(&line.use_by) {
+                Some(date)
+            } else {
+                form.error = Some(trf("DLC invalide : {}", &[&line.use_by]));
+                return;
+            };
+
+            lines.push(DeliveryLine {
+                ingredient: ingredient.clone(),
+                quantity: line.quantity,
+                unit: line.unit.clone(),
+                unit_price: line.unit_price,
+                ordered_unit_price: line.has_ordered_price.then_some(line.ordered_unit_price),
+                lot_number: line.lot_number.clone(),
+                use_by,
+            });
+        }
+
+        if lines.is_empty() {
+            form.error = Some(tr("La réception ne contient aucune ligne").to_owned());
+            return;
+        }
+
+        // Prices before the delivery, to undo the updates it makes
+        let mut received: Vec<Rc<RefCell<Ingredient>>> = Vec::new();
+        for line in &lines {
+            if !received
+                .iter()
+                .any(|ingredient| Rc::ptr_eq(ingredient, &line.ingredient))
+            {
+                received.push(line.ingredient.clone());
+            }
+        }
+        let prices_before: Vec<Ingredient> = received
+            .iter()
+            .map(|ingredient| copy_ingredient(&ingredient.borrow()))
+            .collect();
+        let first_lot = self.catalog.inventory.lots.len();
+
+        let order_reference = form.order_reference.trim();
+        let delivery = Delivery {
+            supplier: form.supplier.clone(),
+            order_reference: (!order_reference.is_empty()).then(|| order_reference.to_owned()),
+            received_on,
+            lines,
+        };
+        let description = trf("Réception de {}", &[&delivery.supplier]);
+
+        self.receiving_report = Some(
+            self.catalog
+                .inventory
+                .receive(delivery, self.catalog.settings.price_update_policy),
+        );
+        self.record_delivery(description, &received, prices_before, first_lot);
+        self.receiving_form = ReceivingForm::default();
+    }
+
+    /// Records the delivery just received as one undo step: the price updates of `received`,
+    /// whose fields were `before`, and the lots added from `first_lot`.
+    fn record_delivery(
+        &mut self,
+        description: String,
+        received: &[Rc<RefCell<Ingredient>>],
+        before: Vec<Ingredient>,
+        first_lot: usize,
+    ) {
+        let mut edits: Vec<Edit> = received
+            .iter()
+            .zip(before)
+            .filter(|(ingredient, before)| ingredient.borrow().cost_price != before.cost_price)
+            .map(|(ingredient, before)| Edit::UpdateIngredient {
+                ingredient: ingredient.clone(),
+                before,
+                after: copy_ingredient(&ingredient.borrow()),
+            })
+            .collect();
+        let lots = self.catalog.inventory.lots[first_lot..].to_vec();
+        if !lots.is_empty() {
+            edits.push(Edit::ReceiveLots {
+                index: first_lot,
+                lots,
+            });
+        }
+        if !edits.is_empty() {
+            self.history.record(Edit::Group { description, edits });
+            self.history.seal();
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use poc_fnb::{Plate, PlateComponent};
+
+use super::{TemplateApp, today_text};
+use crate::cost_tree::CostNode;
+use crate::costing::{self, PlateCost};
+use crate::currency::Currency;
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::pdf::{self, Font, PAGE_HEIGHT, PAGE_WIDTH, PdfDocument};
+use crate::recipe_method::{Photo, PrepStep};
+
+const MARGIN: f32 = 50.0;
+const BODY_SIZE: f32 = 10.0;
+const LINE_HEIGHT: f32 = 15.0;
+const PHOTO_MAX_WIDTH: f32 = 300.0;
+const PHOTO_MAX_HEIGHT: f32 = 220.0;
+
+/// "1. Text (20 min, 180 °C)".
+fn step_text(number: usize, step: &PrepStep) -> String {
+    let mut details = Vec::new();
+    if let Some(minutes) = step.duration_minutes {
+        details.push(format!("{minutes:.0} min"));
+    }
+    if let Some(celsius) = step.temperature_celsius {
+        details.push(format!("{celsius:.0} °C"));
+    }
+    if details.is_empty() {
+        format!("{number}. {}", step.text.trim())
+    } else {
+        format!("{number}. {} ({})", step.text.trim(), details.join(", "))
+    }
+}
+
+/// Lays text out top to bottom, starting a new page when the current one is full.
+struct CardWriter {
+    document: PdfDocument,
+    y: f32,
+}
+
+impl CardWriter {
+    fn new() -> Self {
+        let mut document = PdfDocument::new();
+        document.add_page();
+        Self {
+            document,
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.document.add_page();
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&mut self, x: f32, size: f32, font: Font, text: &str) {
+        let y = self.y;
+        if let Some(page) = self.document.last_page_mut() {
+            page.text(x, y, size, font, text);
+        }
+    }
+
+    fn text_right(&mut self, right: f32, size: f32, font: Font, text: &str) {
+        self.text(right - pdf::text_width(text, size), size, font, text);
+    }
+
+    fn rule(&mut self) {
+        let y = self.y + LINE_HEIGHT * 0.6;
+        if let Some(page) = self.document.last_page_mut() {
+            page.line(MARGIN, y, PAGE_WIDTH - MARGIN, y, 0.5);
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(LINE_HEIGHT * 3.0);
+        self.y -= LINE_HEIGHT;
+        let y = self.y - 4.0;
+        if let Some(page) = self.document.last_page_mut() {
+            page.fill_rect(MARGIN, y, PAGE_WIDTH - 2.0 * MARGIN, LINE_HEIGHT + 2.0, 0.9);
+        }
+        self.text(MARGIN + 4.0, 12.0, Font::Bold, text);
+        self.y -= LINE_HEIGHT * 1.5;
+    }
+
+    fn key_value(&mut self, key: &str, value: &str) {
+        self.ensure_space(LINE_HEIGHT);
+        self.text(MARGIN, BODY_SIZE, Font::Regular, key);
+        self.text_right(PAGE_WIDTH - MARGIN, BODY_SIZE, Font::Bold, value);
+        self.y -= LINE_HEIGHT;
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        for line in pdf::wrap_text(text, BODY_SIZE, PAGE_WIDTH - 2.0 * MARGIN) {
+            self.ensure_space(LINE_HEIGHT);
+            self.text(MARGIN, BODY_SIZE, Font::Regular, &line);
+            self.y -= LINE_HEIGHT;
+        }
+    }
+
+    /// Draws `photo` scaled down to fit the photo box, keeping its aspect ratio.
+    fn photo(&mut self, photo: &Photo) {
+        let scale = (PHOTO_MAX_WIDTH / photo.width as f32)
+            .min(PHOTO_MAX_HEIGHT / photo.height as f32)
+            .min(1.0);
+        let width = photo.width as f32 * scale;
+        let height = photo.height as f32 * scale;
+
+        self.ensure_space(height);
+        let image = self
+            .document
+            .add_jpeg(photo.width, photo.height, photo.jpeg.clone());
+        let y = self.y - height + LINE_HEIGHT;
+        if let Some(page) = self.document.last_page_mut() {
+            page.image(image, MARGIN, y, width, height);
+        }
+        self.y -= height + LINE_HEIGHT;
+    }
+}
+
+impl TemplateApp {
+    /// Builds the technical sheet (fiche technique) of `plate` as a PDF file.
+    fn recipe_card_pdf(&self, plate: &Plate) -> Vec<u8> {
+        let valuation = self.catalog.valuation();
+        let cost = PlateCost::of(plate, &valuation);
+        let currency = self.catalog.settings.currency;
+        let mut card = CardWriter::new();
+
+        card.text(MARGIN, 20.0, Font::Bold, &plate.name);
+        card.text_right(
+            PAGE_WIDTH - MARGIN,
+            BODY_SIZE,
+            Font::Regular,
+            &trf("Fiche Technique — {}", &[&today_text()]),
+        );
+        card.y -= LINE_HEIGHT * 2.0;
+
+        card.key_value(
+            tr("Rendement par lot"),
+            &format!(
+                "{} {}",
+                locale::number(plate.batch_quantity, 2),
+                Self::unit_label(&plate.batch_unit)
+            ),
+        );
+        card.key_value(
+            tr("Temps de préparation par lot"),
+            &format!(
+                "{} h",
+                locale::number(plate.batch_preparation_time_hours, 2)
+            ),
+        );
+
+        card.heading(tr("Composants"));
+        let columns = [MARGIN, 260.0, 420.0, 430.0, PAGE_WIDTH - MARGIN];
+        card.text(columns[0], BODY_SIZE, Font::Bold, tr("Composant"));
+        card.text(columns[1], BODY_SIZE, Font::Bold, tr("Nature"));
+        card.text_right(columns[2], BODY_SIZE, Font::Bold, tr("Quantité"));
+        card.text(columns[3], BODY_SIZE, Font::Bold, tr("Unité"));
+        card.text_right(columns[4], BODY_SIZE, Font::Bold, tr("Coût"));
+        card.y -= LINE_HEIGHT;
+        card.rule();
+
+        for component in &plate.components {
+            let (name, nature, quantity, unit) = match component {
+                PlateComponent::Ingredient {
+                    ingredient,
+                    quantity,
+                    unit,
+                } => (
+                    ingredient.borrow().name.clone(),
+                    tr("Ingrédient"),
+                    *quantity,
+                    Self::unit_label(unit),
+                ),
+                PlateComponent::Plate {
+                    plate: sub_plate,
+                    quantity,
+                } => {
+                    let sub_plate = sub_plate.borrow();
+                    (
+                        sub_plate.name.clone(),
+                        tr("Sous-recette (voir sa fiche)"),
+                        *quantity,
+                        Self::unit_label(&sub_plate.batch_unit),
+                    )
+                }
+            };
+            let component_cost = costing::component_cost(component, &valuation);
+
+            card.ensure_space(LINE_HEIGHT);
+            card.text(columns[0], BODY_SIZE, Font::Regular, &name);
+            card.text(columns[1], BODY_SIZE, Font::Regular, nature);
+            card.text_right(
+                columns[2],
+                BODY_SIZE,
+                Font::Regular,
+                &locale::number(quantity, 2),
+            );
+            card.text(columns[3], BODY_SIZE, Font::Regular, unit);
+            card.text_right(
+                columns[4],
+                BODY_SIZE,
+                Font::Regular,
+                &locale::money(component_cost, currency),
+            );
+            card.y -= LINE_HEIGHT;
+        }
+        if plate.components.is_empty() {
+            card.paragraph(tr("Aucun composant."));
+        }
+
+        Self::costs_section(&mut card, &cost, currency);
+        Self::cost_breakdown_section(&mut card, &CostNode::of(plate, &valuation), currency);
+        self.method_section(&mut card, &plate.name);
+
+        card.document.to_bytes()
+    }
+
+    /// Batch costs, selling price and margin.
+    fn costs_section(card: &mut CardWriter, cost: &PlateCost, currency: Currency) {
+        card.heading(tr("Coûts par lot"));
+        card.key_value(
+            tr("Coût ingrédients"),
+            &locale::money(cost.ingredient_cost, currency),
+        );
+        card.key_value(
+            tr("Coût main-d'œuvre"),
+            &locale::money(cost.labor_cost, currency),
+        );
+        card.key_value(
+            tr("Coût total"),
+            &locale::money(cost.total_cost_price(), currency),
+        );
+        card.key_value(
+            tr("Prix de vente"),
+            &locale::money(cost.selling_price, currency),
+        );
+        card.key_value(
+            tr("Marge brute"),
+            &locale::money(cost.gross_margin(), currency),
+        );
+        card.key_value(
+            tr("Taux de marge"),
+            &locale::percent(cost.margin_rate() * 100.0, 1),
+        );
+    }
+
+    /// Every level of the cost tree, sub-recipes indented under the plate using them.
+    fn cost_breakdown_section(card: &mut CardWriter, tree: &CostNode, currency: Currency) {
+        if tree.children.iter().all(|child| child.children.is_empty()) {
+            // Same as the component list
+            return;
+        }
+        card.heading(tr("Décomposition du coût"));
+        for (depth, node) in tree.lines() {
+            card.ensure_space(LINE_HEIGHT);
+            let indent = MARGIN + 14.0 * (depth - 1) as f32;
+            card.text(indent, BODY_SIZE, Font::Regular, &node.name);
+            card.text_right(
+                PAGE_WIDTH - MARGIN,
+                BODY_SIZE,
+                Font::Regular,
+                &Self::cost_node_text(node, tree.cost, currency),
+            );
+            card.y -= LINE_HEIGHT;
+        }
+    }
+
+    /// Numbered steps, chef notes and photo.
+    fn method_section(&self, card: &mut CardWriter, plate_name: &str) {
+        let method = self.catalog.recipe_methods.get(plate_name);
+        card.heading(tr("Méthode"));
+        match method.filter(|method| !method.steps.is_empty()) {
+            Some(method) => {
+                for (idx, step) in method.steps.iter().enumerate() {
+                    card.paragraph(&step_text(idx + 1, step));
+                }
+            }
+            None => card.paragraph(tr("Méthode non renseignée.")),
+        }
+
+        if let Some(method) = method.filter(|method| !method.chef_notes.trim().is_empty()) {
+            card.heading(tr("Notes du Chef"));
+            card.paragraph(&method.chef_notes);
+        }
+
+        if let Some(photo) = method.and_then(|method| method.photo.as_ref()) {
+            card.heading(tr("Photo"));
+            card.photo(photo);
+        }
+    }
+
+    pub(super) fn export_recipe_card(&mut self, plate_idx: usize) {
+        let Some(plate_rc) = self.catalog.plates.get(plate_idx) else {
+            return;
+        };
+        let plate = plate_rc.borrow();
+        let bytes = self.recipe_card_pdf(&plate);
+        let file_name = trf("Fiche technique - {}.pdf", &[&plate.name]);
+        drop(plate);
+
+        self.export_error = super::file_dialog::save_bytes(&file_name, &bytes).err();
+    }
+}
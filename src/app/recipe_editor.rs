@@ -0,0 +1,174 @@
+use poc_fnb::{Plate, QuantityUnit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::{TemplateApp, money_input, number_input};
+use crate::catalog::{copy_plate, same_name};
+use crate::currency::Currency;
+use crate::history::Edit;
+use crate::i18n::tr;
+
+/// Fields of the plate being edited, see `TemplateApp::editing_recipe_idx`.
+pub(super) struct RecipeEditForm {
+    name: String,
+    selling_price: f64,
+    prep_time_hours: f64,
+    batch_quantity: f64,
+    batch_unit: QuantityUnit,
+}
+
+impl RecipeEditForm {
+    pub(super) fn new(plate: &Plate) -> Self {
+        Self {
+            name: plate.name.clone(),
+            selling_price: plate.selling_price,
+            prep_time_hours: plate.batch_preparation_time_hours,
+            batch_quantity: plate.batch_quantity,
+            batch_unit: plate.batch_unit.clone(),
+        }
+    }
+
+    /// Why the form cannot be saved, if it cannot.
+    ///
+    /// `plate` may be mutably borrowed, so it is only compared by pointer.
+    fn error(
+        &self,
+        plates: &[Rc<RefCell<Plate>>],
+        plate: &Rc<RefCell<Plate>>,
+    ) -> Option<&'static str> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Some(tr("Le nom est obligatoire"));
+        }
+        let taken = plates
+            .iter()
+            .any(|other| !Rc::ptr_eq(other, plate) && same_name(&other.borrow().name, name));
+        if taken {
+            return Some(tr("Une autre recette porte déjà ce nom"));
+        }
+        if self.batch_quantity <= 0.0 {
+            return Some(tr("La quantité par lot doit être positive"));
+        }
+        if self.prep_time_hours < 0.0 {
+            return Some(tr("Le temps de préparation ne peut pas être négatif"));
+        }
+        if self.selling_price < 0.0 {
+            return Some(tr("Le prix de vente ne peut pas être négatif"));
+        }
+        None
+    }
+}
+
+/// What the user chose in the recipe edit form.
+pub(super) enum RecipeFormAction {
+    Save,
+    Cancel,
+}
+
+impl TemplateApp {
+    pub(super) fn start_recipe_edit(&mut self, idx: usize) {
+        if let Some(plate) = self.catalog.plates.get(idx) {
+            self.recipe_form = Some(RecipeEditForm::new(&plate.borrow()));
+            self.editing_recipe_idx = Some(idx);
+        }
+    }
+
+    /// Edit form shown in the card of `plate` while it is being edited.
+    pub(super) fn recipe_form_ui(
+        ui: &mut egui::Ui,
+        form: &mut RecipeEditForm,
+        plates: &[Rc<RefCell<Plate>>],
+        plate: &Rc<RefCell<Plate>>,
+        currency: Currency,
+    ) -> Option<RecipeFormAction> {
+        let mut action = None;
+        ui.group(|ui| {
+            egui::Grid::new("recipe_edit_form")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label(tr("Nom :"));
+                    ui.text_edit_singleline(&mut form.name);
+                    ui.end_row();
+
+                    ui.label(tr("Prix de Vente :"));
+                    ui.add(money_input(&mut form.selling_price, currency).speed(0.1));
+                    ui.end_row();
+
+                    ui.label(tr("Temps de Préparation (h) :"));
+                    ui.add(
+                        number_input(&mut form.prep_time_hours)
+                            .speed(0.1)
+                            .range(0.0..=24.0),
+                    );
+                    ui.end_row();
+
+                    ui.label(tr("Quantité par Lot :"));
+                    ui.add(number_input(&mut form.batch_quantity).speed(0.1));
+                    ui.end_row();
+
+                    ui.label(tr("Unité du Lot :"));
+                    egui::ComboBox::from_id_salt("recipe_edit_unit")
+                        .selected_text(Self::unit_label(&form.batch_unit))
+                        .show_ui(ui, |ui| {
+                            for unit in Self::get_unit_variants() {
+                                let label = Self::unit_label(&unit);
+                                ui.selectable_value(&mut form.batch_unit, unit, label);
+                            }
+                        });
+                    ui.end_row();
+                });
+
+            let error = form.error(plates, plate);
+            if let Some(error) = error {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+            }
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(error.is_none(), egui::Button::new(tr("Enregistrer")))
+                    .clicked()
+                {
+                    action = Some(RecipeFormAction::Save);
+                }
+                if ui.button(tr("Annuler")).clicked() {
+                    action = Some(RecipeFormAction::Cancel);
+                }
+            });
+        });
+        action
+    }
+
+    pub(super) fn finish_recipe_edit(&mut self, action: &RecipeFormAction) {
+        let form = self.recipe_form.take();
+        let idx = self.editing_recipe_idx.take();
+        let (RecipeFormAction::Save, Some(form), Some(plate)) = (
+            action,
+            form,
+            idx.and_then(|idx| self.catalog.plates.get(idx).cloned()),
+        ) else {
+            return;
+        };
+
+        let mut after = copy_plate(&plate.borrow());
+        let former_name = std::mem::replace(&mut after.name, form.name.trim().to_owned());
+        after.selling_price = form.selling_price;
+        after.batch_preparation_time_hours = form.prep_time_hours;
+        after.batch_quantity = form.batch_quantity;
+        after.batch_unit = form.batch_unit;
+
+        if let Some(covers) = self.production_forecast.remove(&former_name) {
+            self.production_forecast.insert(after.name.clone(), covers);
+        }
+        let edit = Edit::update_plate(&self.catalog, &plate, after);
+        self.history.apply(&mut self.catalog, edit);
+    }
+
+    /// Appends a copy of the plate at `idx` and opens it for editing.
+    pub(super) fn duplicate_recipe(&mut self, idx: usize) {
+        let Some(plate) = self.catalog.plates.get(idx).cloned() else {
+            return;
+        };
+        let edit = Edit::duplicate_plate(&self.catalog, &plate);
+        self.history.apply(&mut self.catalog, edit);
+        self.start_recipe_edit(self.catalog.plates.len() - 1);
+    }
+}
@@ -0,0 +1,187 @@
+use std::sync::mpsc::TryRecvError;
+
+use super::{TemplateApp, file_dialog, number_input};
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::recipe_method::{Photo, PrepStep, RecipeMethod};
+
+/// Checkbox enabling an optional value, and the value itself when enabled.
+fn optional_value_ui(
+    ui: &mut egui::Ui,
+    value: &mut Option<f64>,
+    label: &str,
+    suffix: &str,
+    default: f64,
+) {
+    let mut enabled = value.is_some();
+    if ui.checkbox(&mut enabled, label).changed() {
+        *value = enabled.then_some(default);
+    }
+    if let Some(value) = value {
+        ui.add(number_input(value).speed(1.0).suffix(suffix));
+    }
+}
+
+/// Texture of `photo`, uploaded once and then kept in the egui memory.
+fn photo_texture(ctx: &egui::Context, photo: &Photo) -> Option<egui::TextureHandle> {
+    let id = egui::Id::new(("recipe_photo", &photo.file_name, photo.jpeg.len()));
+    if let Some(texture) = ctx.data(|data| data.get_temp::<egui::TextureHandle>(id)) {
+        return Some(texture);
+    }
+
+    let (size, rgba) = photo.to_rgba()?;
+    let image = egui::ColorImage::from_rgba_unmultiplied(size, &rgba);
+    let texture = ctx.load_texture(
+        format!("recipe_photo_{}", photo.file_name),
+        image,
+        egui::TextureOptions::default(),
+    );
+    ctx.data_mut(|data| data.insert_temp(id, texture.clone()));
+    Some(texture)
+}
+
+impl TemplateApp {
+    /// Editor for the steps, chef notes and photo of a plate.
+    ///
+    /// Returns `true` when the user asks to pick a photo.
+    pub(super) fn recipe_method_ui(
+        ui: &mut egui::Ui,
+        plate_name: &str,
+        method: &mut RecipeMethod,
+    ) -> bool {
+        let mut move_step = None;
+        let mut step_to_remove = None;
+        let step_count = method.steps.len();
+
+        for (step_idx, step) in method.steps.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}.", step_idx + 1));
+                ui.add(
+                    egui::TextEdit::multiline(&mut step.text)
+                        .desired_rows(1)
+                        .hint_text(tr("Description de l'étape...")),
+                );
+                if ui
+                    .add_enabled(step_idx > 0, egui::Button::new("⬆").small())
+                    .clicked()
+                {
+                    move_step = Some((step_idx, step_idx - 1));
+                }
+                if ui
+                    .add_enabled(step_idx + 1 < step_count, egui::Button::new("⬇").small())
+                    .clicked()
+                {
+                    move_step = Some((step_idx, step_idx + 1));
+                }
+                if ui.small_button("🗑").clicked() {
+                    step_to_remove = Some(step_idx);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add_space(16.0);
+                optional_value_ui(ui, &mut step.duration_minutes, tr("Durée"), " min", 10.0);
+                optional_value_ui(
+                    ui,
+                    &mut step.temperature_celsius,
+                    tr("Température"),
+                    " °C",
+                    180.0,
+                );
+            });
+        }
+
+        if let Some((from, to)) = move_step {
+            method.move_step(from, to);
+        }
+        if let Some(step_idx) = step_to_remove {
+            method.steps.remove(step_idx);
+        }
+        if ui.button(tr("➕ Ajouter une Étape")).clicked() {
+            method.steps.push(PrepStep::default());
+        }
+
+        ui.separator();
+        ui.label(tr("Notes du Chef :"));
+        ui.add(
+            egui::TextEdit::multiline(&mut method.chef_notes)
+                .desired_rows(2)
+                .hint_text(tr("Astuces, dressage, points de vigilance...")),
+        );
+
+        ui.separator();
+        let mut pick_photo = false;
+        match &method.photo {
+            Some(photo) => {
+                match photo_texture(ui.ctx(), photo) {
+                    Some(texture) => {
+                        ui.add(egui::Image::new(&texture).max_width(240.0));
+                    }
+                    None => {
+                        ui.label(trf("Photo illisible : {}", &[&photo.file_name]));
+                    }
+                }
+                ui.horizontal(|ui| {
+                    pick_photo = ui.button(tr("📷 Remplacer la Photo")).clicked();
+                    if ui.button(tr("Retirer la Photo")).clicked() {
+                        method.photo = None;
+                    }
+                });
+            }
+            None => {
+                pick_photo = ui
+                    .button(tr("📷 Ajouter une Photo"))
+                    .on_hover_text(trf("Photo de {} (PNG ou JPEG)", &[&plate_name]))
+                    .clicked();
+            }
+        }
+        pick_photo
+    }
+
+    pub(super) fn pick_photo(&mut self, ctx: &egui::Context, plate_idx: usize) {
+        let Some(plate_rc) = self.catalog.plates.get(plate_idx) else {
+            return;
+        };
+        let plate_name = plate_rc.borrow().name.clone();
+        let receiver = file_dialog::pick_file(ctx, tr("Image"), &["png", "jpg", "jpeg"]);
+        self.photo_pick = Some((plate_name, receiver));
+    }
+
+    /// Attaches the picked photo once the file dialog is done.
+    pub(super) fn poll_photo_pick(&mut self) {
+        let Some((plate_name, receiver)) = &self.photo_pick else {
+            return;
+        };
+        let file = match receiver.try_recv() {
+            Ok(file) => file,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.photo_pick = None;
+                return;
+            }
+        };
+
+        match file.and_then(|file| Photo::from_file(&file.name, &file.bytes)) {
+            Ok(photo) => {
+                let before = self
+                    .catalog
+                    .recipe_methods
+                    .get(plate_name)
+                    .cloned()
+                    .unwrap_or_default();
+                let after = RecipeMethod {
+                    photo: Some(photo),
+                    ..before.clone()
+                };
+                let edit = Edit::UpdateMethod {
+                    plate_name: plate_name.clone(),
+                    before,
+                    after,
+                };
+                self.history.apply(&mut self.catalog, edit);
+                self.photo_error = None;
+            }
+            Err(error) => self.photo_error = Some(error),
+        }
+        self.photo_pick = None;
+    }
+}
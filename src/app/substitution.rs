@@ -0,0 +1,188 @@
+use super::{TemplateApp, number_input};
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::money::Money;
+use crate::substitution::Substitution;
+
+pub(super) struct SubstitutionForm {
+    pub ingredient_idx: Option<usize>,
+    replacement_idx: Option<usize>,
+    factor: f64,
+    remove_ingredient: bool,
+}
+
+impl Default for SubstitutionForm {
+    fn default() -> Self {
+        Self {
+            ingredient_idx: None,
+            replacement_idx: None,
+            factor: 1.0,
+            remove_ingredient: false,
+        }
+    }
+}
+
+impl TemplateApp {
+    pub(super) fn substitution_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_substitution;
+        egui::Window::new(tr("Substitution d'Ingrédient"))
+            .id(egui::Id::new("substitution_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                self.substitution_ui(ui);
+            });
+        self.show_substitution = open;
+    }
+
+    /// The substitution described by the form, once both ingredients are chosen.
+    fn substitution(&self) -> Option<Substitution> {
+        let form = &self.substitution_form;
+        let ingredient = self.catalog.ingredients.get(form.ingredient_idx?)?;
+        let replacement = self.catalog.ingredients.get(form.replacement_idx?)?;
+        (form.ingredient_idx != form.replacement_idx).then(|| Substitution {
+            ingredient: ingredient.clone(),
+            replacement: replacement.clone(),
+            factor: form.factor,
+        })
+    }
+
+    fn ingredient_combo(&mut self, ui: &mut egui::Ui, id: &str, replacement: bool) {
+        let form = &mut self.substitution_form;
+        let selected_idx = if replacement {
+            &mut form.replacement_idx
+        } else {
+            &mut form.ingredient_idx
+        };
+        let selected_text = selected_idx
+            .and_then(|idx| self.catalog.ingredients.get(idx))
+            .map_or_else(
+                || tr("Sélectionner...").to_owned(),
+                |ingredient| ingredient.borrow().name.clone(),
+            );
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (idx, ingredient_rc) in self.catalog.ingredients.iter().enumerate() {
+                    let ingredient = ingredient_rc.borrow();
+                    ui.selectable_value(selected_idx, Some(idx), &ingredient.name);
+                }
+            });
+    }
+
+    fn substitution_ui(&mut self, ui: &mut egui::Ui) {
+        let currency = self.catalog.settings.currency;
+        egui::Grid::new("substitution_form")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label(tr("Ingrédient à remplacer :"));
+                self.ingredient_combo(ui, "substitution_ingredient", false);
+                ui.end_row();
+
+                ui.label(tr("Remplacer par :"));
+                self.ingredient_combo(ui, "substitution_replacement", true);
+                ui.end_row();
+
+                ui.label(tr("Facteur de quantité :"));
+                ui.add(
+                    number_input(&mut self.substitution_form.factor)
+                        .speed(0.01)
+                        .range(0.0..=100.0)
+                        .prefix("× "),
+                )
+                .on_hover_text(tr(
+                    "Nouvelle quantité = ancienne quantité × facteur, dans la même unité si le nouvel ingrédient s'y convertit, sinon dans la sienne",
+                ));
+                ui.end_row();
+            });
+        ui.checkbox(
+            &mut self.substitution_form.remove_ingredient,
+            tr("Supprimer l'ingrédient remplacé du catalogue"),
+        );
+
+        ui.separator();
+        let Some(substitution) = self.substitution() else {
+            ui.label(tr("Choisissez deux ingrédients différents."));
+            return;
+        };
+
+        let valuation = self.catalog.valuation();
+        let impacts = substitution.preview(&self.catalog, &valuation);
+        if impacts.is_empty() {
+            ui.label(tr("Aucune recette n'utilise cet ingrédient."));
+        } else {
+            egui::Grid::new("substitution_preview")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr("Recette"));
+                    ui.strong(tr("Coût avant"));
+                    ui.strong(tr("Coût après"));
+                    ui.strong(tr("Écart"));
+                    ui.strong(tr("Taux de marge après"));
+                    ui.end_row();
+
+                    for impact in &impacts {
+                        let plate_name = impact.plate.borrow().name.clone();
+                        if impact.path.is_empty() {
+                            ui.label(plate_name);
+                        } else {
+                            ui.label(format!("{plate_name} (via {})", impact.path.join(" → ")));
+                        }
+                        ui.label(locale::money(impact.before.total_cost_price(), currency));
+                        ui.label(locale::money(impact.after.total_cost_price(), currency));
+                        let delta = impact.cost_delta();
+                        let color = if delta > Money::ZERO {
+                            egui::Color32::from_rgb(255, 100, 100)
+                        } else {
+                            egui::Color32::from_rgb(100, 255, 100)
+                        };
+                        ui.colored_label(color, locale::signed_money(delta, currency));
+                        ui.label(locale::percent(impact.after.margin_rate() * 100.0, 1));
+                        ui.end_row();
+                    }
+                });
+        }
+        self.incompatible_uses_ui(ui, &substitution);
+
+        let remove_ingredient = self.substitution_form.remove_ingredient;
+        if ui
+            .add_enabled(
+                !impacts.is_empty() || remove_ingredient,
+                egui::Button::new(tr("Appliquer la Substitution")),
+            )
+            .clicked()
+        {
+            let edit = Edit::substitute_ingredient(&self.catalog, &substitution, remove_ingredient);
+            self.history.apply(&mut self.catalog, edit);
+            self.substitution_form = SubstitutionForm::default();
+        }
+    }
+
+    /// Recipe quantities that the substitution moves to the unit of the replacement.
+    fn incompatible_uses_ui(&self, ui: &mut egui::Ui, substitution: &Substitution) {
+        let uses = substitution.incompatible_uses(&self.catalog.plates);
+        if uses.is_empty() {
+            return;
+        }
+        let to = Self::unit_label(&substitution.replacement.borrow().unit);
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 200, 100),
+            trf(
+                "⚠ {} quantité(s) de recette ne peuvent pas être converties en {} :",
+                &[&uses.len(), &to],
+            ),
+        );
+        for incompatible in &uses {
+            ui.label(format!(
+                "• {} : {} {} → {} {to}",
+                incompatible.plate.borrow().name,
+                locale::number(incompatible.quantity, 2),
+                Self::unit_label(&incompatible.unit),
+                locale::number(incompatible.converted_quantity, 2)
+            ));
+        }
+        ui.label(tr(
+            "Le facteur les convertit dans l'unité du nouvel ingrédient.",
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+use super::TemplateApp;
+use crate::costing;
+use crate::i18n::tr;
+use crate::locale;
+use crate::money::Rounding;
+use crate::valuation::{CostMethod, Valuation};
+
+fn rounding_label(rounding: Rounding) -> &'static str {
+    tr(match rounding {
+        Rounding::PerLine => "Par ligne",
+        Rounding::OnTotal => "Sur le total",
+    })
+}
+
+fn cost_method_label(method: CostMethod) -> &'static str {
+    tr(match method {
+        CostMethod::LastPrice => "Dernier Prix",
+        CostMethod::WeightedAverage => "Prix Moyen Pondéré",
+        CostMethod::Fifo => "FIFO",
+    })
+}
+
+impl TemplateApp {
+    pub(super) fn valuation_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_valuation;
+        egui::Window::new(tr("Méthode de Valorisation"))
+            .id(egui::Id::new("valuation_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                self.valuation_ui(ui);
+            });
+        self.show_valuation = open;
+    }
+
+    fn valuation_ui(&mut self, ui: &mut egui::Ui) {
+        let settings_before = self.catalog.settings;
+        let currency = settings_before.currency;
+        ui.horizontal(|ui| {
+            ui.label(tr("Valorisation des ingrédients :"));
+            for method in CostMethod::ALL {
+                ui.radio_value(
+                    &mut self.catalog.settings.cost_method,
+                    method,
+                    cost_method_label(method),
+                );
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr("Arrondi au centime :"));
+            for rounding in Rounding::ALL {
+                ui.radio_value(
+                    &mut self.catalog.settings.rounding,
+                    rounding,
+                    rounding_label(rounding),
+                );
+            }
+        })
+        .response
+        .on_hover_text(tr(
+            "Par ligne : chaque composant est arrondi avant d'être additionné, comme sur une facture",
+        ));
+        self.record_settings_change(settings_before);
+
+        ui.separator();
+        ui.label(tr("Coût ingrédients par lot selon chaque méthode (écart par rapport à la méthode choisie) :"));
+
+        let conversion = self.catalog.price_conversion();
+        let rounding = self.catalog.settings.rounding;
+        let valuations = CostMethod::ALL
+            .map(|method| Valuation::new(&self.catalog.inventory, method, conversion, rounding));
+        let selected = CostMethod::ALL
+            .iter()
+            .position(|method| *method == self.catalog.settings.cost_method)
+            .unwrap_or_default();
+
+        egui::Grid::new("valuation_preview")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong(tr("Recette"));
+                for method in CostMethod::ALL {
+                    ui.strong(cost_method_label(method));
+                }
+                ui.end_row();
+
+                for plate_rc in &self.catalog.plates {
+                    let plate = plate_rc.borrow();
+                    ui.label(&plate.name);
+
+                    let costs = valuations
+                        .each_ref()
+                        .map(|valuation| costing::ingredient_cost(&plate, valuation));
+                    let reference = costs[selected];
+                    for (idx, cost) in costs.iter().enumerate() {
+                        if idx == selected {
+                            ui.strong(locale::money(*cost, currency));
+                        } else {
+                            ui.label(format!(
+                                "{} ({})",
+                                locale::money(*cost, currency),
+                                locale::signed_money(*cost - reference, currency)
+                            ));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
@@ -0,0 +1,56 @@
+/// Height assumed for items that were never laid out.
+const ESTIMATED_HEIGHT: f32 = 150.0;
+
+/// Vertical scroll area laying out only the visible items, for long lists of cards whose
+/// height varies (e.g. with collapsing headers).
+///
+/// The height of each item is measured when it is shown and remembered under its id in
+/// the egui memory, so ids must be stable from frame to frame.
+pub(super) fn show(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    item_ids: &[egui::Id],
+    mut add_item: impl FnMut(&mut egui::Ui, usize),
+) {
+    egui::ScrollArea::vertical()
+        .id_salt(id_salt)
+        .auto_shrink([false, false])
+        .show_viewport(ui, |ui, viewport| {
+            let ctx = ui.ctx().clone();
+            let spacing = ui.spacing().item_spacing.y;
+            let heights: Vec<f32> = item_ids
+                .iter()
+                .map(|id| {
+                    ctx.data(|data| data.get_temp(*id))
+                        .unwrap_or(ESTIMATED_HEIGHT)
+                })
+                .collect();
+            let total_height: f32 = heights.iter().map(|height| height + spacing).sum();
+            let origin = ui.max_rect().min;
+            let width = ui.available_width();
+            ui.set_min_height(total_height);
+
+            let mut top = 0.0;
+            for (row, (id, height)) in item_ids.iter().zip(&heights).enumerate() {
+                let bottom = top + height;
+                if bottom >= viewport.min.y && top <= viewport.max.y {
+                    let rect = egui::Rect::from_min_size(
+                        origin + egui::vec2(0.0, top),
+                        egui::vec2(width, *height),
+                    );
+                    let measured = ui
+                        .scope_builder(egui::UiBuilder::new().max_rect(rect).id_salt(id), |ui| {
+                            add_item(ui, row);
+                        })
+                        .response
+                        .rect
+                        .height();
+                    if (measured - height).abs() > 0.5 {
+                        ctx.data_mut(|data| data.insert_temp(*id, measured));
+                        ctx.request_repaint();
+                    }
+                }
+                top = bottom + spacing;
+            }
+        });
+}
@@ -0,0 +1,278 @@
+use poc_fnb::QuantityUnit;
+
+use super::{DATE_FORMAT, TemplateApp, number_input, parse_date, today_text};
+use crate::history::Edit;
+use crate::i18n::{tr, trf};
+use crate::locale;
+use crate::waste::{Loss, WasteReason, WastedItem};
+
+fn waste_reason_label(reason: WasteReason) -> &'static str {
+    tr(match reason {
+        WasteReason::Expired => "DLC dépassée",
+        WasteReason::Spoiled => "Avarié",
+        WasteReason::Burnt => "Brûlé / Raté",
+        WasteReason::Overproduction => "Surproduction",
+        WasteReason::Damaged => "Endommagé",
+        WasteReason::Other => "Autre",
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WasteKind {
+    Ingredient,
+    Plate,
+}
+
+/// Loss being typed in the waste window.
+pub(super) struct WasteForm {
+    kind: WasteKind,
+    item_idx: Option<usize>,
+    quantity: f64,
+    unit: QuantityUnit,
+    reason: WasteReason,
+    date: String,
+    error: Option<String>,
+}
+
+impl Default for WasteForm {
+    fn default() -> Self {
+        Self {
+            kind: WasteKind::Ingredient,
+            item_idx: None,
+            quantity: 1.0,
+            unit: QuantityUnit::Gram,
+            reason: WasteReason::Expired,
+            date: today_text(),
+            error: None,
+        }
+    }
+}
+
+impl TemplateApp {
+    pub(super) fn waste_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_waste;
+        egui::Window::new(tr("Pertes et Gaspillage"))
+            .id(egui::Id::new("waste_log_window"))
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    self.waste_form_ui(ui);
+                    ui.separator();
+                    self.waste_report_ui(ui);
+                });
+            });
+        self.show_waste = open;
+    }
+
+    fn waste_form_ui(&mut self, ui: &mut egui::Ui) {
+        let form = &mut self.waste_form;
+
+        ui.horizontal(|ui| {
+            let previous_kind = form.kind;
+            ui.radio_value(&mut form.kind, WasteKind::Ingredient, tr("Ingrédient"));
+            ui.radio_value(&mut form.kind, WasteKind::Plate, tr("Lot de Recette"));
+            if form.kind != previous_kind {
+                form.item_idx = None;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Article :"));
+            let names: Vec<String> = match form.kind {
+                WasteKind::Ingredient => self
+                    .catalog
+                    .ingredients
+                    .iter()
+                    .map(|ingredient| ingredient.borrow().name.clone())
+                    .collect(),
+                WasteKind::Plate => self
+                    .catalog
+                    .plates
+                    .iter()
+                    .map(|plate| plate.borrow().name.clone())
+                    .collect(),
+            };
+            let selected_text = form
+                .item_idx
+                .and_then(|idx| names.get(idx).cloned())
+                .unwrap_or_else(|| tr("Sélectionner...").to_owned());
+            egui::ComboBox::from_id_salt("waste_item")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (idx, name) in names.iter().enumerate() {
+                        if ui
+                            .selectable_label(form.item_idx == Some(idx), name)
+                            .clicked()
+                        {
+                            form.item_idx = Some(idx);
+                            form.unit = match form.kind {
+                                WasteKind::Ingredient => {
+                                    self.catalog.ingredients[idx].borrow().unit.clone()
+                                }
+                                WasteKind::Plate => {
+                                    self.catalog.plates[idx].borrow().batch_unit.clone()
+                                }
+                            };
+                        }
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Quantité :"));
+            ui.add(
+                number_input(&mut form.quantity)
+                    .speed(0.1)
+                    .range(0.0..=100000.0),
+            );
+            egui::ComboBox::from_id_salt("waste_unit")
+                .selected_text(Self::unit_label(&form.unit))
+                .show_ui(ui, |ui| {
+                    for unit in Self::get_unit_variants() {
+                        let label = Self::unit_label(&unit);
+                        ui.selectable_value(&mut form.unit, unit, label);
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Motif :"));
+            egui::ComboBox::from_id_salt("waste_reason")
+                .selected_text(waste_reason_label(form.reason))
+                .show_ui(ui, |ui| {
+                    for reason in WasteReason::ALL {
+                        ui.selectable_value(&mut form.reason, reason, waste_reason_label(reason));
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("Date :"));
+            ui.text_edit_singleline(&mut form.date)
+                .on_hover_text(tr("AAAA-MM-JJ"));
+        });
+
+        if ui.button(tr("Enregistrer la Perte")).clicked() {
+            self.record_waste();
+        }
+
+        if let Some(error) = &self.waste_form.error {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), error);
+        }
+    }
+
+    fn record_waste(&mut self) {
+        let form = &mut self.waste_form;
+        form.error = None;
+
+        let item = match form.kind {
+            WasteKind::Ingredient => form
+                .item_idx
+                .and_then(|idx| self.catalog.ingredients.get(idx))
+                .map(|ingredient| WastedItem::Ingredient(ingredient.clone())),
+            WasteKind::Plate => form
+                .item_idx
+                .and_then(|idx| self.catalog.plates.get(idx))
+                .map(|plate| WastedItem::Plate(plate.clone())),
+        };
+        let Some(item) = item else {
+            form.error = Some(tr("Sélectionner un article").to_owned());
+            return;
+        };
+        let Some(date) = parse_date(&form.date) else {
+            form.error = Some(tr("Date invalide (AAAA-MM-JJ)").to_owned());
+            return;
+        };
+
+        let loss = Loss {
+            item,
+            quantity: form.quantity,
+            unit: form.unit.clone(),
+            reason: form.reason,
+            date,
+        };
+        if let Some(edit) = Edit::record_waste(&self.catalog, loss) {
+            self.history.apply(&mut self.catalog, edit);
+            self.waste_form = WasteForm::default();
+        } else {
+            self.waste_form.error =
+                Some(tr("Unité incompatible avec celle de l'article").to_owned());
+        }
+    }
+
+    fn waste_report_ui(&self, ui: &mut egui::Ui) {
+        let report = self.catalog.waste_log.report();
+        let currency = self.catalog.settings.currency;
+        ui.heading(trf(
+            "Total des Pertes : {}",
+            &[&locale::money(report.total, currency)],
+        ));
+
+        egui::CollapsingHeader::new(tr("Par Motif"))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("waste_by_reason")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (reason, value) in &report.by_reason {
+                            ui.label(waste_reason_label(*reason));
+                            ui.label(locale::money(*value, currency));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        egui::CollapsingHeader::new(tr("Par Type d'Ingrédient"))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("waste_by_type")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (ingredient_type, value) in &report.by_type {
+                            ui.label(Self::ingredient_type_label(ingredient_type));
+                            ui.label(locale::money(*value, currency));
+                            ui.end_row();
+                        }
+                        if report.labor > 0.0 {
+                            ui.label(tr("Main-d'œuvre"));
+                            ui.label(locale::money(report.labor, currency));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        egui::CollapsingHeader::new(tr("Par Semaine"))
+            .default_open(true)
+            .show(ui, |ui| {
+                egui::Grid::new("waste_by_week")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for ((year, week), value) in &report.by_week {
+                            ui.label(trf("{} — semaine {}", &[year, week]));
+                            ui.label(locale::money(*value, currency));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        egui::CollapsingHeader::new(tr("Journal")).show(ui, |ui| {
+            egui::Grid::new("waste_entries")
+                .striped(true)
+                .show(ui, |ui| {
+                    for entry in self.catalog.waste_log.entries.iter().rev() {
+                        ui.label(entry.loss.date.format(DATE_FORMAT).to_string());
+                        ui.label(entry.loss.item.name());
+                        ui.label(format!(
+                            "{} {}",
+                            locale::number(entry.loss.quantity, 2),
+                            Self::unit_label(&entry.loss.unit)
+                        ));
+                        ui.label(waste_reason_label(entry.loss.reason));
+                        ui.label(locale::money(entry.value, currency));
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
@@ -0,0 +1,146 @@
+//! Where the catalog is kept between sessions.
+//!
+//! On desktop, the catalog is saved in an `SQLite` database next to the state eframe keeps in
+//! `app.ron`, in the [`eframe::storage_dir`] of [`APP_ID`]; catalogs saved before the
+//! database existed are read from `app.ron`, under [`CATALOG_KEY`]. On the web, the catalog
+//! stays in the eframe storage of the browser.
+
+use crate::persistence::CatalogFile;
+
+/// Name of the app, which also locates its storage.
+pub const APP_ID: &str = "Gestion des Coûts F&B";
+
+/// Storage key of the catalog in eframe storage, kept apart from the UI state.
+pub const CATALOG_KEY: &str = "catalog";
+
+#[cfg(not(target_arch = "wasm32"))]
+mod sqlite;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{AppLock, AppStorage};
+#[cfg(not(target_arch = "wasm32"))]
+pub use sqlite::SqliteStore;
+
+/// Somewhere a catalog is saved.
+pub trait CatalogStore {
+    /// The saved catalog, `None` if none was saved yet.
+    fn load_catalog(&self) -> Result<Option<CatalogFile>, String>;
+
+    /// Replaces the saved catalog.
+    fn save_catalog(&mut self, catalog: &CatalogFile) -> Result<(), String>;
+}
+
+/// The catalog as one RON value under [`CATALOG_KEY`].
+impl CatalogStore for dyn eframe::Storage + '_ {
+    /// Unlike [`eframe::get_value`], fails on a value that does not parse.
+    fn load_catalog(&self) -> Result<Option<CatalogFile>, String> {
+        self.get_string(CATALOG_KEY)
+            .map(|value| ron::from_str(&value).map_err(|err| err.to_string()))
+            .transpose()
+    }
+
+    fn save_catalog(&mut self, catalog: &CatalogFile) -> Result<(), String> {
+        eframe::set_value(self, CATALOG_KEY, catalog);
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use super::{APP_ID, CATALOG_KEY, CatalogStore, SqliteStore};
+    use crate::persistence::CatalogFile;
+
+    /// The storage of the desktop app for the current user, which may not exist yet.
+    ///
+    /// The app keeps its catalog in memory and writes it back every few seconds, so a
+    /// catalog saved here while it runs is overwritten; it is read on the next start. The
+    /// app holds an [`AppLock`] meanwhile, for other programs to check with
+    /// [`AppStorage::is_locked`].
+    pub struct AppStorage {
+        dir: PathBuf,
+    }
+
+    /// Marks the storage as in use by the app until dropped.
+    pub struct AppLock {
+        path: PathBuf,
+    }
+
+    impl Drop for AppLock {
+        fn drop(&mut self) {
+            // A lock that stays behind only makes other programs refuse to write
+            std::fs::remove_file(&self.path).ok();
+        }
+    }
+
+    impl AppStorage {
+        pub fn locate() -> Result<Self, String> {
+            let dir = eframe::storage_dir(APP_ID)
+                .ok_or_else(|| "No data directory on this system".to_owned())?;
+            Ok(Self { dir })
+        }
+
+        pub fn database_path(&self) -> PathBuf {
+            self.dir.join("catalog.sqlite")
+        }
+
+        /// File marking the storage as in use, holding the id of the app process.
+        pub fn lock_path(&self) -> PathBuf {
+            self.dir.join("app.lock")
+        }
+
+        /// Marks the storage as in use by the running app. A lock left behind by an app that
+        /// did not exit cleanly is taken over.
+        pub fn lock(&self) -> Result<AppLock, String> {
+            let path = self.lock_path();
+            std::fs::create_dir_all(&self.dir)
+                .and_then(|()| std::fs::write(&path, std::process::id().to_string()))
+                .map_err(|err| format!("{}: {err}", path.display()))?;
+            Ok(AppLock { path })
+        }
+
+        /// Whether the app is running, in which case it would save its own catalog over
+        /// any other change.
+        pub fn is_locked(&self) -> bool {
+            self.lock_path().exists()
+        }
+
+        fn database(&self) -> Result<SqliteStore, String> {
+            std::fs::create_dir_all(&self.dir)
+                .map_err(|err| format!("{}: {err}", self.dir.display()))?;
+            SqliteStore::open(&self.database_path())
+        }
+
+        /// The catalog saved in `app.ron` before the database existed.
+        fn legacy_catalog(&self) -> Result<Option<CatalogFile>, String> {
+            let path = self.dir.join("app.ron");
+            let error = |err: &dyn std::fmt::Display| format!("{}: {err}", path.display());
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(error(&err)),
+            };
+            let values: HashMap<String, String> =
+                ron::from_str(&text).map_err(|err| error(&err))?;
+            values
+                .get(CATALOG_KEY)
+                .map(|value| ron::from_str(value).map_err(|err| error(&err)))
+                .transpose()
+        }
+    }
+
+    impl CatalogStore for AppStorage {
+        fn load_catalog(&self) -> Result<Option<CatalogFile>, String> {
+            match self.database()?.load_catalog()? {
+                Some(catalog) => Ok(Some(catalog)),
+                None => self.legacy_catalog(),
+            }
+        }
+
+        fn save_catalog(&mut self, catalog: &CatalogFile) -> Result<(), String> {
+            self.database()?.save_catalog(catalog)
+        }
+    }
+}
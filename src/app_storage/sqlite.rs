@@ -0,0 +1,989 @@
+//! Catalog in an `SQLite` database, one table per kind of record.
+//!
+//! Enumerations are stored by variant name ("Chf", "Gluten") and the settings and recipe
+//! methods as JSON documents. Ingredients, plates and stock lots refer to each other and to
+//! their suppliers through foreign keys; waste entries keep the name of what was thrown
+//! away, which may since have been deleted.
+//!
+//! Saving compares the catalog with the saved rows and writes only the differences, so rows
+//! keep their ids: ingredients and plates are matched by name, and each table keeps the order
+//! of its records in a `position` column.
+
+use rusqlite::types::{ToSqlOutput, Value as SqlValue};
+use rusqlite::{Connection, OptionalExtension as _, ToSql, Transaction, params_from_iter};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::iter;
+use std::path::Path;
+
+use super::CatalogStore;
+use crate::currency::{ExchangeRate, ExchangeRates};
+use crate::persistence::{
+    CatalogFile, ComponentRecord, IngredientRecord, PlateRecord, StockLotRecord, WasteRecord,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Schema changes, in order: the database is at version `n` once the first `n` are applied.
+///
+/// Applied migrations must never change; changes to the schema go in a new one.
+const MIGRATIONS: &[&str] = &["
+    CREATE TABLE settings (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        document TEXT NOT NULL
+    );
+    CREATE TABLE suppliers (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        contact TEXT
+    );
+    CREATE INDEX suppliers_name ON suppliers (name);
+    CREATE TABLE ingredients (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        supplier_id INTEGER NOT NULL REFERENCES suppliers (id),
+        cost_price REAL NOT NULL,
+        reference_quantity REAL NOT NULL,
+        unit TEXT NOT NULL,
+        ingredient_type TEXT NOT NULL,
+        currency TEXT,
+        position INTEGER NOT NULL
+    );
+    CREATE INDEX ingredients_name ON ingredients (name);
+    CREATE TABLE ingredient_allergens (
+        ingredient_id INTEGER NOT NULL REFERENCES ingredients (id) ON DELETE CASCADE,
+        allergen TEXT NOT NULL,
+        PRIMARY KEY (ingredient_id, allergen)
+    );
+    CREATE TABLE plates (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        selling_price REAL NOT NULL,
+        batch_preparation_time_hours REAL NOT NULL,
+        batch_quantity REAL NOT NULL,
+        batch_unit TEXT NOT NULL,
+        method TEXT NOT NULL,
+        position INTEGER NOT NULL
+    );
+    CREATE INDEX plates_name ON plates (name);
+    CREATE TABLE plate_components (
+        plate_id INTEGER NOT NULL REFERENCES plates (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        ingredient_id INTEGER REFERENCES ingredients (id),
+        sub_plate_id INTEGER REFERENCES plates (id),
+        quantity REAL NOT NULL,
+        unit TEXT,
+        PRIMARY KEY (plate_id, position),
+        CHECK ((ingredient_id IS NULL) <> (sub_plate_id IS NULL))
+    );
+    CREATE TABLE stock_lots (
+        id INTEGER PRIMARY KEY,
+        ingredient_id INTEGER NOT NULL REFERENCES ingredients (id),
+        supplier_id INTEGER NOT NULL REFERENCES suppliers (id),
+        lot_number TEXT NOT NULL,
+        received_on TEXT NOT NULL,
+        use_by TEXT,
+        unit_cost REAL NOT NULL,
+        received_quantity REAL NOT NULL,
+        remaining_quantity REAL NOT NULL,
+        position INTEGER NOT NULL
+    );
+    CREATE INDEX stock_lots_ingredient ON stock_lots (ingredient_id);
+    CREATE TABLE waste (
+        id INTEGER PRIMARY KEY,
+        item TEXT NOT NULL,
+        is_plate INTEGER NOT NULL,
+        quantity REAL NOT NULL,
+        unit TEXT NOT NULL,
+        reason TEXT NOT NULL,
+        date TEXT NOT NULL,
+        value REAL NOT NULL,
+        value_by_type TEXT NOT NULL,
+        position INTEGER NOT NULL
+    );
+    CREATE TABLE exchange_rates (
+        id INTEGER PRIMARY KEY,
+        from_currency TEXT NOT NULL,
+        to_currency TEXT NOT NULL,
+        effective_from TEXT NOT NULL,
+        rate REAL NOT NULL,
+        position INTEGER NOT NULL
+    );
+"];
+
+/// Name of the variant of an enumeration without data, such as "Chf".
+fn variant_name(value: &impl serde::Serialize) -> Result<String> {
+    match serde_json::to_value(value)? {
+        Value::String(name) => Ok(name),
+        other => Err(format!("Not a variant name: {other}").into()),
+    }
+}
+
+fn from_variant_name<T: serde::de::DeserializeOwned>(name: String) -> Result<T> {
+    Ok(serde_json::from_value(Value::String(name))?)
+}
+
+/// A catalog saved in an `SQLite` database.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    /// Opens the database at `path`, creating it if needed, and brings its schema up to date.
+    pub fn open(path: &Path) -> std::result::Result<Self, String> {
+        let open = || -> Result<Self> {
+            let mut store = Self {
+                connection: Connection::open(path)?,
+            };
+            store.connection.pragma_update(None, "foreign_keys", true)?;
+            store.migrate()?;
+            Ok(store)
+        };
+        open().map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    fn migrate(&mut self) -> Result<()> {
+        let version: usize = self
+            .connection
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > MIGRATIONS.len() {
+            return Err(
+                format!("Schema version {version} is newer than this version of the app").into(),
+            );
+        }
+        for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = self.connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", idx + 1)?;
+            transaction.commit()?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> Result<Option<CatalogFile>> {
+        let settings: Option<String> = self
+            .connection
+            .query_row("SELECT document FROM settings", [], |row| row.get(0))
+            .optional()?;
+        let Some(settings) = settings else {
+            return Ok(None);
+        };
+        Ok(Some(CatalogFile {
+            settings: serde_json::from_str(&settings)?,
+            ingredients: self.read_ingredients()?,
+            plates: self.read_plates()?,
+            stock_lots: self.read_stock_lots()?,
+            waste: self.read_waste()?,
+            exchange_rates: self.read_exchange_rates()?,
+        }))
+    }
+
+    fn read_ingredients(&self) -> Result<Vec<IngredientRecord>> {
+        let mut allergens = self
+            .connection
+            .prepare("SELECT ingredient_id, allergen FROM ingredient_allergens")?;
+        let mut allergens_by_id: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in allergens.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (id, allergen) = row?;
+            allergens_by_id.entry(id).or_default().push(allergen);
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT i.id, i.name, i.cost_price, i.reference_quantity, i.unit,
+                    i.ingredient_type, i.currency, s.name, s.contact
+             FROM ingredients i JOIN suppliers s ON s.id = i.supplier_id
+             ORDER BY i.position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut ingredients = Vec::new();
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            let currency: Option<String> = row.get(6)?;
+            ingredients.push(IngredientRecord {
+                name: row.get(1)?,
+                cost_price: row.get(2)?,
+                reference_quantity: row.get(3)?,
+                unit: row.get(4)?,
+                ingredient_type: row.get(5)?,
+                supplier: row.get(7)?,
+                supplier_contact: row.get(8)?,
+                allergens: allergens_by_id
+                    .remove(&id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(from_variant_name)
+                    .collect::<Result<_>>()?,
+                currency: currency.map(from_variant_name).transpose()?,
+            });
+        }
+        Ok(ingredients)
+    }
+
+    fn read_plates(&self) -> Result<Vec<PlateRecord>> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, name, selling_price, batch_preparation_time_hours, batch_quantity,
+                    batch_unit, method
+             FROM plates ORDER BY position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut plates = Vec::new();
+        let mut index_by_id = HashMap::new();
+        while let Some(row) = rows.next()? {
+            let method: String = row.get(6)?;
+            index_by_id.insert(row.get::<_, i64>(0)?, plates.len());
+            plates.push(PlateRecord {
+                name: row.get(1)?,
+                components: Vec::new(),
+                selling_price: row.get(2)?,
+                batch_preparation_time_hours: row.get(3)?,
+                batch_quantity: row.get(4)?,
+                batch_unit: row.get(5)?,
+                method: serde_json::from_str(&method)?,
+            });
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT c.plate_id, i.name, p.name, c.quantity, c.unit
+             FROM plate_components c
+             LEFT JOIN ingredients i ON i.id = c.ingredient_id
+             LEFT JOIN plates p ON p.id = c.sub_plate_id
+             ORDER BY c.plate_id, c.position",
+        )?;
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let Some(&idx) = index_by_id.get(&row.get::<_, i64>(0)?) else {
+                continue;
+            };
+            let ingredient: Option<String> = row.get(1)?;
+            let sub_plate: Option<String> = row.get(2)?;
+            let quantity = row.get(3)?;
+            let component = match (ingredient, sub_plate) {
+                (Some(ingredient), _) => ComponentRecord::Ingredient {
+                    ingredient,
+                    quantity,
+                    unit: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                },
+                (None, Some(plate)) => ComponentRecord::Plate { plate, quantity },
+                (None, None) => continue,
+            };
+            plates[idx].components.push(component);
+        }
+        Ok(plates)
+    }
+
+    fn read_stock_lots(&self) -> Result<Vec<StockLotRecord>> {
+        let mut statement = self.connection.prepare(
+            "SELECT i.name, s.name, l.lot_number, l.received_on, l.use_by, l.unit_cost,
+                    l.received_quantity, l.remaining_quantity
+             FROM stock_lots l
+             JOIN ingredients i ON i.id = l.ingredient_id
+             JOIN suppliers s ON s.id = l.supplier_id
+             ORDER BY l.position",
+        )?;
+        let lots = statement
+            .query_map([], |row| {
+                Ok(StockLotRecord {
+                    ingredient: row.get(0)?,
+                    supplier: row.get(1)?,
+                    lot_number: row.get(2)?,
+                    received_on: row.get(3)?,
+                    use_by: row.get(4)?,
+                    unit_cost: row.get(5)?,
+                    received_quantity: row.get(6)?,
+                    remaining_quantity: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(lots)
+    }
+
+    fn read_waste(&self) -> Result<Vec<WasteRecord>> {
+        let mut statement = self.connection.prepare(
+            "SELECT item, is_plate, quantity, unit, reason, date, value, value_by_type
+             FROM waste ORDER BY position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut waste = Vec::new();
+        while let Some(row) = rows.next()? {
+            let value_by_type: String = row.get(7)?;
+            waste.push(WasteRecord {
+                item: row.get(0)?,
+                is_plate: row.get(1)?,
+                quantity: row.get(2)?,
+                unit: row.get(3)?,
+                reason: from_variant_name(row.get(4)?)?,
+                date: row.get(5)?,
+                value: row.get(6)?,
+                value_by_type: serde_json::from_str(&value_by_type)?,
+            });
+        }
+        Ok(waste)
+    }
+
+    fn read_exchange_rates(&self) -> Result<ExchangeRates> {
+        let mut statement = self.connection.prepare(
+            "SELECT from_currency, to_currency, effective_from, rate
+             FROM exchange_rates ORDER BY position",
+        )?;
+        let mut rows = statement.query([])?;
+        let mut rates = Vec::new();
+        while let Some(row) = rows.next()? {
+            rates.push(ExchangeRate {
+                from: from_variant_name(row.get(0)?)?,
+                to: from_variant_name(row.get(1)?)?,
+                effective_from: row.get(2)?,
+                rate: row.get(3)?,
+            });
+        }
+        Ok(ExchangeRates { rates })
+    }
+
+    /// Replaces the saved catalog with `catalog`, entirely or not at all.
+    ///
+    /// Only the rows that changed are written, so saving an unchanged catalog writes nothing
+    /// and rows keep their ids from one save to the next.
+    fn write(&mut self, catalog: &CatalogFile) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        // Rows refer to each other by id; they only need to agree once every table is written
+        transaction.pragma_update(None, "defer_foreign_keys", true)?;
+
+        sync(
+            &transaction,
+            &SETTINGS,
+            &[row(&[&1, &serde_json::to_string(&catalog.settings)?])?],
+        )?;
+        let supplier_ids = write_suppliers(&transaction, catalog)?;
+        let ingredient_ids = write_ingredients(&transaction, &catalog.ingredients, &supplier_ids)?;
+        write_plates(&transaction, &catalog.plates, &ingredient_ids)?;
+        write_stock_lots(
+            &transaction,
+            &catalog.stock_lots,
+            &ingredient_ids,
+            &supplier_ids,
+        )?;
+        write_waste(&transaction, &catalog.waste)?;
+        write_exchange_rates(&transaction, &catalog.exchange_rates)?;
+
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// Row ids of the suppliers by name and contact.
+type SupplierIds<'a> = HashMap<(&'a str, Option<&'a str>), i64>;
+
+/// Row ids by name. Names are not unique in a catalog; references by name go to the first
+/// one, as `Catalog::find_ingredient` does.
+type IdsByName<'a> = HashMap<&'a str, i64>;
+
+/// The suppliers of the ingredients and of the lots.
+fn write_suppliers<'a>(
+    transaction: &Transaction<'_>,
+    catalog: &'a CatalogFile,
+) -> Result<SupplierIds<'a>> {
+    let mut suppliers: Vec<(&str, Option<&str>)> = Vec::new();
+    let keys = catalog
+        .ingredients
+        .iter()
+        .map(|ingredient| {
+            (
+                ingredient.supplier.as_str(),
+                ingredient.supplier_contact.as_deref(),
+            )
+        })
+        .chain(
+            catalog
+                .stock_lots
+                .iter()
+                .map(|lot| (lot.supplier.as_str(), None)),
+        );
+    for key in keys {
+        if !suppliers.contains(&key) {
+            suppliers.push(key);
+        }
+    }
+    let rows = suppliers
+        .iter()
+        .map(|(name, contact)| row(&[name, contact]))
+        .collect::<Result<Vec<_>>>()?;
+    let ids = sync(transaction, &SUPPLIERS, &rows)?;
+    Ok(suppliers.into_iter().zip(ids).collect())
+}
+
+/// The ingredients and their allergens.
+fn write_ingredients<'a>(
+    transaction: &Transaction<'_>,
+    ingredients: &'a [IngredientRecord],
+    supplier_ids: &SupplierIds<'_>,
+) -> Result<IdsByName<'a>> {
+    let rows = ingredients
+        .iter()
+        .enumerate()
+        .map(|(position, ingredient)| {
+            let supplier_id = supplier_ids[&(
+                ingredient.supplier.as_str(),
+                ingredient.supplier_contact.as_deref(),
+            )];
+            let currency = ingredient.currency.as_ref().map(variant_name).transpose()?;
+            row(&[
+                &ingredient.name,
+                &supplier_id,
+                &ingredient.cost_price,
+                &ingredient.reference_quantity,
+                &ingredient.unit,
+                &ingredient.ingredient_type,
+                &currency,
+                &position,
+            ])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ids = sync(transaction, &INGREDIENTS, &rows)?;
+
+    let mut allergen_rows = Vec::new();
+    let mut ids_by_name = IdsByName::new();
+    for (ingredient, id) in ingredients.iter().zip(ids) {
+        for allergen in &ingredient.allergens {
+            allergen_rows.push(row(&[&id, &variant_name(allergen)?])?);
+        }
+        ids_by_name.entry(&ingredient.name).or_insert(id);
+    }
+    sync(transaction, &INGREDIENT_ALLERGENS, &allergen_rows)?;
+    Ok(ids_by_name)
+}
+
+/// The plates, then their components, since sub-recipes may come later.
+fn write_plates(
+    transaction: &Transaction<'_>,
+    plates: &[PlateRecord],
+    ingredient_ids: &IdsByName<'_>,
+) -> Result<()> {
+    let rows = plates
+        .iter()
+        .enumerate()
+        .map(|(position, plate)| {
+            row(&[
+                &plate.name,
+                &plate.selling_price,
+                &plate.batch_preparation_time_hours,
+                &plate.batch_quantity,
+                &plate.batch_unit,
+                &serde_json::to_string(&plate.method)?,
+                &position,
+            ])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let ids = sync(transaction, &PLATES, &rows)?;
+    let mut plate_ids = IdsByName::new();
+    for (plate, &id) in plates.iter().zip(&ids) {
+        plate_ids.entry(&plate.name).or_insert(id);
+    }
+
+    let mut component_rows = Vec::new();
+    for (plate, plate_id) in plates.iter().zip(ids) {
+        for (position, component) in plate.components.iter().enumerate() {
+            let (ingredient_id, sub_plate_id, quantity, unit) = match component {
+                ComponentRecord::Ingredient {
+                    ingredient,
+                    quantity,
+                    unit,
+                } => (
+                    ingredient_ids.get(ingredient.as_str()).copied(),
+                    None,
+                    quantity,
+                    Some(unit),
+                ),
+                ComponentRecord::Plate { plate, quantity } => {
+                    (None, plate_ids.get(plate.as_str()).copied(), quantity, None)
+                }
+            };
+            // Loading fails on them too, and a `Catalog` cannot hold any
+            if ingredient_id.is_none() && sub_plate_id.is_none() {
+                return Err(format!("{} uses an unknown item", plate.name).into());
+            }
+            component_rows.push(row(&[
+                &plate_id,
+                &position,
+                &ingredient_id,
+                &sub_plate_id,
+                quantity,
+                &unit,
+            ])?);
+        }
+    }
+    sync(transaction, &PLATE_COMPONENTS, &component_rows)?;
+    Ok(())
+}
+
+fn write_stock_lots(
+    transaction: &Transaction<'_>,
+    lots: &[StockLotRecord],
+    ingredient_ids: &IdsByName<'_>,
+    supplier_ids: &SupplierIds<'_>,
+) -> Result<()> {
+    let mut rows = Vec::new();
+    for (position, lot) in lots.iter().enumerate() {
+        let Some(ingredient_id) = ingredient_ids.get(lot.ingredient.as_str()) else {
+            return Err(format!("Stock lot of unknown ingredient {}", lot.ingredient).into());
+        };
+        rows.push(row(&[
+            ingredient_id,
+            &supplier_ids[&(lot.supplier.as_str(), None)],
+            &lot.lot_number,
+            &lot.received_on,
+            &lot.use_by,
+            &lot.unit_cost,
+            &lot.received_quantity,
+            &lot.remaining_quantity,
+            &position,
+        ])?);
+    }
+    sync(transaction, &STOCK_LOTS, &rows)?;
+    Ok(())
+}
+
+fn write_waste(transaction: &Transaction<'_>, waste: &[WasteRecord]) -> Result<()> {
+    let rows = waste
+        .iter()
+        .enumerate()
+        .map(|(position, entry)| {
+            row(&[
+                &entry.item,
+                &entry.is_plate,
+                &entry.quantity,
+                &entry.unit,
+                &variant_name(&entry.reason)?,
+                &entry.date,
+                &entry.value,
+                &serde_json::to_string(&entry.value_by_type)?,
+                &position,
+            ])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    sync(transaction, &WASTE, &rows)?;
+    Ok(())
+}
+
+fn write_exchange_rates(transaction: &Transaction<'_>, rates: &ExchangeRates) -> Result<()> {
+    let rows = rates
+        .rates
+        .iter()
+        .enumerate()
+        .map(|(position, rate)| {
+            row(&[
+                &variant_name(&rate.from)?,
+                &variant_name(&rate.to)?,
+                &rate.effective_from,
+                &rate.rate,
+                &position,
+            ])
+        })
+        .collect::<Result<Vec<_>>>()?;
+    sync(transaction, &EXCHANGE_RATES, &rows)?;
+    Ok(())
+}
+
+/// A table written row by row. Rows are told apart by their first `key_len` columns: the
+/// name of an ingredient or a plate, the position of a component in its plate, and so on.
+struct Table {
+    name: &'static str,
+    columns: &'static [&'static str],
+    key_len: usize,
+}
+
+const SETTINGS: Table = Table {
+    name: "settings",
+    columns: &["id", "document"],
+    key_len: 1,
+};
+
+const SUPPLIERS: Table = Table {
+    name: "suppliers",
+    columns: &["name", "contact"],
+    key_len: 2,
+};
+
+const INGREDIENTS: Table = Table {
+    name: "ingredients",
+    columns: &[
+        "name",
+        "supplier_id",
+        "cost_price",
+        "reference_quantity",
+        "unit",
+        "ingredient_type",
+        "currency",
+        "position",
+    ],
+    key_len: 1,
+};
+
+const INGREDIENT_ALLERGENS: Table = Table {
+    name: "ingredient_allergens",
+    columns: &["ingredient_id", "allergen"],
+    key_len: 2,
+};
+
+const PLATES: Table = Table {
+    name: "plates",
+    columns: &[
+        "name",
+        "selling_price",
+        "batch_preparation_time_hours",
+        "batch_quantity",
+        "batch_unit",
+        "method",
+        "position",
+    ],
+    key_len: 1,
+};
+
+const PLATE_COMPONENTS: Table = Table {
+    name: "plate_components",
+    columns: &[
+        "plate_id",
+        "position",
+        "ingredient_id",
+        "sub_plate_id",
+        "quantity",
+        "unit",
+    ],
+    key_len: 2,
+};
+
+/// Lots have no name; the same lot is the same ingredient, supplier, number and date.
+const STOCK_LOTS: Table = Table {
+    name: "stock_lots",
+    columns: &[
+        "ingredient_id",
+        "supplier_id",
+        "lot_number",
+        "received_on",
+        "use_by",
+        "unit_cost",
+        "received_quantity",
+        "remaining_quantity",
+        "position",
+    ],
+    key_len: 4,
+};
+
+/// Waste entries are only ever added, so all but their valuation tells them apart.
+const WASTE: Table = Table {
+    name: "waste",
+    columns: &[
+        "item",
+        "is_plate",
+        "quantity",
+        "unit",
+        "reason",
+        "date",
+        "value",
+        "value_by_type",
+        "position",
+    ],
+    key_len: 6,
+};
+
+const EXCHANGE_RATES: Table = Table {
+    name: "exchange_rates",
+    columns: &[
+        "from_currency",
+        "to_currency",
+        "effective_from",
+        "rate",
+        "position",
+    ],
+    key_len: 3,
+};
+
+/// Values of a row, in the order of the columns of its [`Table`].
+fn row(values: &[&dyn ToSql]) -> Result<Vec<SqlValue>> {
+    values
+        .iter()
+        .map(|value| match value.to_sql()? {
+            ToSqlOutput::Borrowed(value) => Ok(value.into()),
+            ToSqlOutput::Owned(value) => Ok(value),
+            other => Err(format!("Unsupported SQL value: {other:?}").into()),
+        })
+        .collect()
+}
+
+/// Makes `table` hold `rows`, in that order: rows matching a saved one by key are updated if
+/// they differ, the others inserted, and saved rows left unmatched deleted.
+///
+/// Returns the row ids of `rows`. Rows sharing a key are matched in the order they were saved.
+fn sync(transaction: &Transaction<'_>, table: &Table, rows: &[Vec<SqlValue>]) -> Result<Vec<i64>> {
+    let Table {
+        name,
+        columns,
+        key_len,
+    } = *table;
+    let key = |values: &[SqlValue]| format!("{:?}", &values[..key_len]);
+
+    let mut saved: HashMap<String, VecDeque<(i64, Vec<SqlValue>)>> = HashMap::new();
+    let mut statement = transaction.prepare(&format!(
+        "SELECT rowid, {} FROM {name} ORDER BY rowid",
+        columns.join(", ")
+    ))?;
+    let mut saved_rows = statement.query([])?;
+    while let Some(saved_row) = saved_rows.next()? {
+        let values = (1..=columns.len())
+            .map(|idx| saved_row.get(idx))
+            .collect::<rusqlite::Result<Vec<SqlValue>>>()?;
+        saved
+            .entry(key(&values))
+            .or_default()
+            .push_back((saved_row.get(0)?, values));
+    }
+
+    let placeholders: Vec<String> = (1..=columns.len()).map(|idx| format!("?{idx}")).collect();
+    let insert = format!(
+        "INSERT INTO {name} ({}) VALUES ({})",
+        columns.join(", "),
+        placeholders.join(", ")
+    );
+    // The row id is ?1, so the columns after the key start at ?2
+    let assignments: Vec<String> = columns[key_len..]
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| format!("{column} = ?{}", idx + 2))
+        .collect();
+    let update = format!(
+        "UPDATE {name} SET {} WHERE rowid = ?1",
+        assignments.join(", ")
+    );
+
+    let mut ids = Vec::with_capacity(rows.len());
+    for values in rows {
+        if let Some((id, saved_values)) = saved.get_mut(&key(values)).and_then(VecDeque::pop_front)
+        {
+            if saved_values != *values {
+                let id = SqlValue::Integer(id);
+                transaction
+                    .prepare_cached(&update)?
+                    .execute(params_from_iter(iter::once(&id).chain(&values[key_len..])))?;
+            }
+            ids.push(id);
+        } else {
+            transaction
+                .prepare_cached(&insert)?
+                .execute(params_from_iter(values))?;
+            ids.push(transaction.last_insert_rowid());
+        }
+    }
+
+    let delete = format!("DELETE FROM {name} WHERE rowid = ?1");
+    for (id, _) in saved.into_values().flatten() {
+        transaction.prepare_cached(&delete)?.execute([id])?;
+    }
+    Ok(ids)
+}
+
+impl CatalogStore for SqliteStore {
+    fn load_catalog(&self) -> std::result::Result<Option<CatalogFile>, String> {
+        self.read().map_err(|err| err.to_string())
+    }
+
+    fn save_catalog(&mut self, catalog: &CatalogFile) -> std::result::Result<(), String> {
+        self.write(catalog).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"{
+    "settings": {
+        "currency": "Chf",
+        "rounding": "OnTotal"
+    },
+    "ingredients": [
+        {
+            "name": "Farine",
+            "cost_price": 1.2,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "grocery",
+            "supplier": "Moulin",
+            "supplier_contact": "a@b",
+            "allergens": [
+                "Gluten"
+            ]
+        },
+        {
+            "name": "Mozzarella",
+            "cost_price": 9.5,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "dairy",
+            "supplier": "Latte",
+            "currency": "Eur",
+            "allergens": [
+                "Milk"
+            ]
+        },
+        {
+            "name": "Sel",
+            "cost_price": 0.5,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "grocery",
+            "supplier": "Moulin"
+        }
+    ],
+    "plates": [
+        {
+            "name": "Pizza",
+            "components": [
+                {
+                    "kind": "Plate",
+                    "plate": "Pâte",
+                    "quantity": 1
+                },
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Mozzarella",
+                    "quantity": 125,
+                    "unit": "g"
+                }
+            ],
+            "selling_price": 12,
+            "batch_preparation_time_hours": 0.1,
+            "batch_quantity": 1,
+            "batch_unit": "piece",
+            "method": {
+                "steps": [],
+                "chef_notes": "chaud",
+                "photo": null
+            }
+        },
+        {
+            "name": "Pâte",
+            "components": [
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Farine",
+                    "quantity": 500,
+                    "unit": "g"
+                },
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Sel",
+                    "quantity": 10,
+                    "unit": "g"
+                }
+            ],
+            "selling_price": 0,
+            "batch_preparation_time_hours": 0.5,
+            "batch_quantity": 4,
+            "batch_unit": "piece"
+        }
+    ],
+    "stock_lots": [
+        {
+            "ingredient": "Farine",
+            "supplier": "Moulin",
+            "lot_number": "L1",
+            "received_on": "2026-01-02",
+            "use_by": null,
+            "unit_cost": 1.1,
+            "received_quantity": 10,
+            "remaining_quantity": 4
+        }
+    ],
+    "waste": [
+        {
+            "item": "Pizza",
+            "is_plate": true,
+            "quantity": 1,
+            "unit": "piece",
+            "reason": "Burnt",
+            "date": "2026-02-03",
+            "value": 4.5,
+            "value_by_type": [
+                [
+                    "grocery",
+                    1.5
+                ]
+            ]
+        },
+        {
+            "item": "Ancienne Farine",
+            "is_plate": false,
+            "quantity": 2,
+            "unit": "kg",
+            "reason": "Expired",
+            "date": "2026-02-04",
+            "value": 2.2,
+            "value_by_type": [
+                [
+                    "grocery",
+                    2.2
+                ]
+            ]
+        }
+    ],
+    "exchange_rates": {
+        "rates": [
+            {
+                "from": "Eur",
+                "to": "Chf",
+                "effective_from": "2026-01-01",
+                "rate": 0.95
+            }
+        ]
+    }
+}"#;
+
+    fn store() -> SqliteStore {
+        SqliteStore::open(Path::new(":memory:")).expect("in-memory database")
+    }
+
+    fn catalog() -> CatalogFile {
+        CatalogFile::from_json(CATALOG).expect("valid JSON")
+    }
+
+    fn id_of(store: &SqliteStore, table: &str, name: &str) -> i64 {
+        store
+            .connection
+            .query_row(
+                &format!("SELECT id FROM {table} WHERE name = ?1"),
+                [name],
+                |row| row.get(0),
+            )
+            .expect("saved row")
+    }
+
+    #[test]
+    fn catalog_survives_a_round_trip() {
+        let mut store = store();
+        assert_eq!(store.load_catalog(), Ok(None));
+        store.save_catalog(&catalog()).expect("saved");
+        assert_eq!(store.load_catalog(), Ok(Some(catalog())));
+    }
+
+    #[test]
+    fn saving_again_writes_only_the_differences() {
+        let mut store = store();
+        store.save_catalog(&catalog()).expect("saved");
+        let flour = id_of(&store, "ingredients", "Farine");
+        let dough = id_of(&store, "plates", "Pâte");
+
+        let mut changed = catalog();
+        changed.ingredients[0].cost_price = 1.3;
+        changed.ingredients.swap(0, 2);
+        changed
+            .ingredients
+            .retain(|ingredient| ingredient.name != "Mozzarella");
+        changed.plates[0]
+            .components
+            .retain(|component| matches!(component, ComponentRecord::Plate { .. }));
+        changed.waste.remove(0);
+        store.save_catalog(&changed).expect("saved");
+
+        assert_eq!(store.load_catalog(), Ok(Some(changed)));
+        assert_eq!(id_of(&store, "ingredients", "Farine"), flour);
+        assert_eq!(id_of(&store, "plates", "Pâte"), dough);
+    }
+}
@@ -0,0 +1,434 @@
+//! Costing of a catalog file from the command line, for scripts and scheduled reports.
+//!
+//! Costs are computed as in the app, with the cost method, currency and rounding of the
+//! catalog. The exit code is 0 on success, 1 when the catalog has blocking issues (red
+//! alerts) and 2 when the command or the catalog file is invalid.
+
+#![warn(clippy::all, rust_2018_idioms)]
+
+use std::process::ExitCode;
+
+use eframe_todo_list::catalog::Catalog;
+use eframe_todo_list::cost_tree::{CostNode, CostNodeKind};
+use eframe_todo_list::costing::PlateCost;
+use eframe_todo_list::dependencies;
+use eframe_todo_list::health::{self, Status};
+use eframe_todo_list::i18n::{self, Language, tr, trf};
+use eframe_todo_list::locale;
+use eframe_todo_list::money::Money;
+use eframe_todo_list::persistence::{CatalogFile, unit_code};
+use eframe_todo_list::report;
+
+/// Exit code of a catalog with red alerts.
+const ISSUES_FOUND: u8 = 1;
+
+/// Exit code of an invalid command or catalog file.
+const INVALID_INPUT: u8 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Command {
+    List,
+    Cost { plate: String },
+    MarginReport,
+    Impact { ingredient: String, price: f64 },
+    Export { format: ExportFormat },
+    Validate,
+}
+
+/// Command line, once parsed.
+struct Invocation {
+    catalog_path: String,
+    command: Command,
+}
+
+fn usage() -> String {
+    let commands = [
+        ("list", tr("ingrédients et recettes du catalogue")),
+        (tr("cost <recette>"), tr("détail du coût d'une recette")),
+        ("margin-report", tr("marges des plats vendus")),
+        (
+            tr("impact --ingredient <nom> --price <prix>"),
+            tr("effet d'un nouveau prix, dans la devise de l'ingrédient"),
+        ),
+        (
+            "export --format csv|json",
+            tr("coûts de toutes les recettes"),
+        ),
+        ("validate", tr("problèmes du catalogue")),
+    ];
+    let mut text = format!(
+        "{}\n\n{}",
+        tr("Utilisation : fnb [--lang fr|en] <catalogue.json> <commande>"),
+        tr("Commandes :")
+    );
+    for (command, description) in commands {
+        text.push_str(&format!("\n  {command:<42} {description}"));
+    }
+    text.push_str("\n\n");
+    text.push_str(tr(
+        "Code de sortie : 0 si tout va bien, 1 si le catalogue a des alertes rouges, 2 si la commande ou le fichier est invalide.",
+    ));
+    text
+}
+
+/// Value following `name` in `args`.
+fn option_value<'a>(args: &'a [String], name: &str) -> Result<&'a str, String> {
+    let position = args
+        .iter()
+        .position(|arg| arg == name)
+        .ok_or_else(|| trf("Option {} manquante", &[&name]))?;
+    args.get(position + 1)
+        .map(String::as_str)
+        .ok_or_else(|| trf("Valeur manquante après {}", &[&name]))
+}
+
+fn parse_command(name: &str, args: &[String]) -> Result<Command, String> {
+    match name {
+        "list" => Ok(Command::List),
+        "cost" => {
+            let plate = args
+                .first()
+                .ok_or_else(|| tr("Nom de recette manquant").to_owned())?;
+            Ok(Command::Cost {
+                plate: plate.clone(),
+            })
+        }
+        "margin-report" => Ok(Command::MarginReport),
+        "impact" => {
+            let ingredient = option_value(args, "--ingredient")?.to_owned();
+            let price = option_value(args, "--price")?;
+            let price = locale::parse_number(price)
+                .filter(|price| *price >= 0.0)
+                .ok_or_else(|| trf("Prix invalide : {}", &[&price]))?;
+            Ok(Command::Impact { ingredient, price })
+        }
+        "export" => {
+            let format = match option_value(args, "--format")? {
+                "csv" => ExportFormat::Csv,
+                "json" => ExportFormat::Json,
+                other => return Err(trf("Format inconnu : {}", &[&other])),
+            };
+            Ok(Command::Export { format })
+        }
+        "validate" => Ok(Command::Validate),
+        other => Err(trf("Commande inconnue : {}", &[&other])),
+    }
+}
+
+/// Parses the arguments, setting the language of the output on the way.
+fn parse_args(mut args: Vec<String>) -> Result<Invocation, String> {
+    if let Some(position) = args.iter().position(|arg| arg == "--lang") {
+        let language = match args.get(position + 1).map(String::as_str) {
+            Some("fr") => Language::French,
+            Some("en") => Language::English,
+            _ => return Err(tr("Langue attendue après --lang : fr ou en").to_owned()),
+        };
+        i18n::set_language(language);
+        args.drain(position..=position + 1);
+    }
+
+    let mut args = args.into_iter();
+    let (Some(catalog_path), Some(command)) = (args.next(), args.next()) else {
+        return Err(usage());
+    };
+    let rest: Vec<String> = args.collect();
+    Ok(Invocation {
+        catalog_path,
+        command: parse_command(&command, &rest)?,
+    })
+}
+
+fn load_catalog(path: &str) -> Result<Catalog, String> {
+    let json = std::fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    let file = CatalogFile::from_json(&json).map_err(|err| format!("{path}: {err}"))?;
+    Catalog::try_from(&file).map_err(|err| format!("{path}: {err}"))
+}
+
+fn status_label(status: Status) -> &'static str {
+    tr(match status {
+        Status::Green => "OK",
+        Status::Amber => "À surveiller",
+        Status::Red => "Alerte",
+    })
+}
+
+fn list(catalog: &Catalog) {
+    let valuation = catalog.valuation();
+    let currency = catalog.settings.currency;
+
+    println!("{} ({})", tr("Ingrédients"), catalog.ingredients.len());
+    for ingredient in &catalog.ingredients {
+        let ingredient = ingredient.borrow();
+        let price = locale::unit_money(
+            ingredient.cost_price,
+            catalog.ingredient_currency(&ingredient.name),
+        );
+        println!(
+            "  {:<32} {price} / {}",
+            ingredient.name,
+            unit_code(&ingredient.unit)
+        );
+    }
+
+    println!();
+    println!("{} ({})", tr("Plats (Recettes)"), catalog.plates.len());
+    for plate in &catalog.plates {
+        let plate = plate.borrow();
+        let cost = PlateCost::of(&plate, &valuation);
+        println!(
+            "  {:<32} {} {}  {}  {}",
+            plate.name,
+            locale::number(plate.batch_quantity, 2),
+            unit_code(&plate.batch_unit),
+            trf(
+                "coût {}",
+                &[&locale::money(cost.total_cost_price(), currency)]
+            ),
+            trf("vente {}", &[&locale::money(cost.selling_price, currency)]),
+        );
+    }
+}
+
+fn cost(catalog: &Catalog, plate_name: &str) -> Result<(), String> {
+    let plate = catalog
+        .find_plate(plate_name)
+        .ok_or_else(|| trf("Recette inconnue : {}", &[&plate_name]))?;
+    let plate = plate.borrow();
+    let valuation = catalog.valuation();
+    let currency = catalog.settings.currency;
+    let tree = CostNode::of(&plate, &valuation);
+
+    println!("{}", plate.name);
+    println!(
+        "{}",
+        trf(
+            "Coût du lot : {} ({} {})",
+            &[
+                &locale::money(tree.cost, currency),
+                &locale::number(tree.quantity, 2),
+                &unit_code(&plate.batch_unit),
+            ],
+        )
+    );
+    for (depth, node) in tree.lines() {
+        let unit = node.unit.as_ref().map_or("h", unit_code);
+        let marker = match node.kind {
+            CostNodeKind::Plate => "+",
+            CostNodeKind::Ingredient => "-",
+            CostNodeKind::Labor => "~",
+        };
+        println!(
+            "{:indent$}{marker} {} — {} {unit} × {} = {} ({})",
+            "",
+            node.name,
+            locale::number(node.quantity, 2),
+            locale::unit_money(node.unit_cost, currency),
+            locale::money(node.cost, currency),
+            locale::percent(node.percent_of(tree.cost), 1),
+            indent = depth * 2,
+        );
+    }
+
+    let cost = PlateCost::of(&plate, &valuation);
+    println!();
+    for (label, amount) in [
+        (tr("Coût Ingrédients :"), cost.ingredient_cost),
+        (tr("Coût Main-d'œuvre :"), cost.labor_cost),
+        (tr("Coût Total :"), cost.total_cost_price()),
+        (tr("Prix de vente"), cost.selling_price),
+        (tr("Marge Brute :"), cost.gross_margin()),
+    ] {
+        println!("{label:<24} {}", locale::money(amount, currency));
+    }
+    println!(
+        "{:<24} {}",
+        tr("Taux de Marge :"),
+        locale::percent(cost.margin_rate() * 100.0, 1)
+    );
+    Ok(())
+}
+
+/// Prints the margins of the plates sold and returns whether one of them is red.
+fn margin_report(catalog: &Catalog) -> bool {
+    let valuation = catalog.valuation();
+    let currency = catalog.settings.currency;
+    let thresholds = catalog.settings.margin_thresholds;
+    let mut red = false;
+
+    println!(
+        "{:<32} {:>14} {:>14} {:>14} {:>14} {:>14}  {}",
+        tr("Plat"),
+        tr("Coût"),
+        tr("Prix de vente"),
+        tr("Marge brute"),
+        tr("Taux de marge"),
+        tr("Food cost %"),
+        tr("Statut"),
+    );
+    for plate in &catalog.plates {
+        let plate = plate.borrow();
+        let cost = PlateCost::of(&plate, &valuation);
+        let Some(status) = thresholds.status(&cost) else {
+            continue;
+        };
+        red |= status == Status::Red;
+        println!(
+            "{:<32} {:>14} {:>14} {:>14} {:>14} {:>14}  {}",
+            plate.name,
+            locale::money(cost.total_cost_price(), currency),
+            locale::money(cost.selling_price, currency),
+            locale::money(cost.gross_margin(), currency),
+            locale::percent(cost.margin_rate() * 100.0, 1),
+            locale::percent(cost.food_cost_rate() * 100.0, 1),
+            status_label(status),
+        );
+    }
+    red
+}
+
+fn impact(catalog: &mut Catalog, ingredient_name: &str, price: f64) -> Result<(), String> {
+    let ingredient = catalog
+        .find_ingredient(ingredient_name)
+        .ok_or_else(|| trf("Ingrédient inconnu : {}", &[&ingredient_name]))?
+        .clone();
+    let dependents = dependencies::ingredient_dependents(catalog, &ingredient);
+    if dependents.is_empty() {
+        println!("{}", tr("Aucune recette n'utilise cet ingrédient."));
+        return Ok(());
+    }
+
+    let costs = |catalog: &Catalog| {
+        let valuation = catalog.valuation();
+        dependents
+            .iter()
+            .map(|dependent| PlateCost::of(&dependent.plate.borrow(), &valuation))
+            .collect::<Vec<_>>()
+    };
+    let before = costs(catalog);
+    // The catalog is dropped at the end of the command, so the new price is never saved
+    let repriced_lots = catalog.set_ingredient_price(&ingredient, price);
+    let after = costs(catalog);
+
+    if repriced_lots {
+        println!(
+            "{}",
+            tr(
+                "Le stock de l'ingrédient est valorisé au nouveau prix, comme s'il avait été acheté à ce prix."
+            )
+        );
+    }
+
+    let currency = catalog.settings.currency;
+    println!(
+        "{:<32} {:>14} {:>14} {:>14} {:>20}",
+        tr("Plat"),
+        tr("Coût avant"),
+        tr("Coût après"),
+        tr("Écart"),
+        tr("Taux de marge après"),
+    );
+    for ((dependent, before), after) in dependents.iter().zip(&before).zip(&after) {
+        println!(
+            "{:<32} {:>14} {:>14} {:>14} {:>20}",
+            dependent.plate.borrow().name,
+            locale::money(before.total_cost_price(), currency),
+            locale::money(after.total_cost_price(), currency),
+            locale::signed_money(
+                after.total_cost_price() - before.total_cost_price(),
+                currency
+            ),
+            if after.selling_price > Money::ZERO {
+                locale::percent(after.margin_rate() * 100.0, 1)
+            } else {
+                "—".to_owned()
+            },
+        );
+    }
+    Ok(())
+}
+
+/// `field` quoted if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn export(catalog: &Catalog, format: ExportFormat) -> Result<(), String> {
+    let records = report::plate_cost_records(catalog);
+    match format {
+        ExportFormat::Csv => {
+            println!(
+                "plate,batch_quantity,batch_unit,currency,ingredient_cost,labor_cost,total_cost,selling_price,gross_margin,margin_rate,food_cost_rate,status"
+            );
+            for record in &records {
+                println!(
+                    "{},{},{},{},{:.2},{:.2},{:.2},{:.2},{:.2},{:.4},{:.4},{}",
+                    csv_field(&record.plate),
+                    record.batch_quantity,
+                    record.batch_unit,
+                    record.currency,
+                    record.ingredient_cost,
+                    record.labor_cost,
+                    record.total_cost,
+                    record.selling_price,
+                    record.gross_margin,
+                    record.margin_rate,
+                    record.food_cost_rate,
+                    record.status.unwrap_or_default(),
+                );
+            }
+        }
+        ExportFormat::Json => {
+            let json = serde_json::to_string_pretty(&records).map_err(|err| err.to_string())?;
+            println!("{json}");
+        }
+    }
+    Ok(())
+}
+
+/// Prints every issue of the catalog and returns whether one of them is red.
+fn validate(catalog: &Catalog) -> bool {
+    let issues = health::validate(catalog, &catalog.valuation());
+    if issues.is_empty() {
+        println!("{}", tr("Aucun problème détecté."));
+    }
+    for issue in &issues {
+        println!("[{}] {}", status_label(issue.status()), issue.message());
+    }
+    issues.iter().any(|issue| issue.status() == Status::Red)
+}
+
+fn run(invocation: &Invocation) -> Result<bool, String> {
+    let mut catalog = load_catalog(&invocation.catalog_path)?;
+    match &invocation.command {
+        Command::List => list(&catalog),
+        Command::Cost { plate } => cost(&catalog, plate)?,
+        Command::MarginReport => return Ok(margin_report(&catalog)),
+        Command::Impact { ingredient, price } => impact(&mut catalog, ingredient, *price)?,
+        Command::Export { format } => export(&catalog, *format)?,
+        Command::Validate => return Ok(validate(&catalog)),
+    }
+    Ok(false)
+}
+
+fn main() -> ExitCode {
+    let result =
+        parse_args(std::env::args().skip(1).collect()).and_then(|invocation| run(&invocation));
+    match result {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::from(ISSUES_FOUND),
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::from(INVALID_INPUT)
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Endpoints of the server, all answering JSON:
+//!
+//! - `GET /ingredients`: ingredients with their price, in their own currency.
+//! - `GET /plates`: costs and margin of every plate.
+//! - `GET /plates/{name}`: costs of a plate and their breakdown by component.
+//! - `PUT /ingredients/{name}/price`: sets the price of an ingredient from a body such as
+//!   `{"cost_price": 1.35}`, in the currency of the ingredient. Unless the cost method is
+//!   the last price, its stock is valued at the new price, as `fnb impact` does. Answers
+//!   the ingredient with the new costs of the plates using it under `plates`, and whether
+//!   its stock was revalued under `repriced_stock`.
+//!
+//! The server must own the catalog while it changes prices: the desktop app keeps its
+//! catalog in memory and saves it over the server's changes, so prices are refused with a
+//! 409 status while it runs.
+//!
+//! Names are percent-encoded in paths. Errors answer `{"error": "..."}` with a 4xx or 5xx
+//! status.
+
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use eframe_todo_list::app_storage::{AppStorage, CatalogStore as _};
+use eframe_todo_list::catalog::Catalog;
+use eframe_todo_list::cost_tree::CostNode;
+use eframe_todo_list::dependencies;
+use eframe_todo_list::persistence::{CatalogFile, IngredientRecord};
+use eframe_todo_list::report::{self, CostNodeRecord, PlateCostRecord};
+
+/// Where the catalog is read and saved.
+pub enum Source {
+    /// The storage of the desktop app.
+    App(AppStorage),
+
+    /// A catalog file, as read by `fnb`.
+    File(PathBuf),
+}
+
+impl Source {
+    fn describe(&self) -> String {
+        match self {
+            Self::App(storage) => storage.database_path().display().to_string(),
+            Self::File(path) => path.display().to_string(),
+        }
+    }
+
+    /// The catalog; empty if the app has not saved one yet.
+    fn load(&self) -> Result<CatalogFile, String> {
+        match self {
+            Self::App(storage) => Ok(storage.load_catalog()?.unwrap_or_default()),
+            Self::File(path) => std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|json| CatalogFile::from_json(&json))
+                .map_err(|err| format!("{}: {err}", path.display())),
+        }
+    }
+
+    /// The catalog ready for costing; fails if it refers to unknown items.
+    fn catalog(&self) -> Result<Catalog, ApiError> {
+        Catalog::try_from(&self.load().map_err(ApiError::internal)?).map_err(ApiError::internal)
+    }
+
+    /// Fails while the desktop app runs, as it would save its own catalog over a change.
+    fn check_writable(&self) -> Result<(), ApiError> {
+        match self {
+            Self::App(storage) if storage.is_locked() => Err(ApiError::new(
+                409,
+                format!(
+                    "The app is open and would overwrite the change; close it first (or remove {} if it is not running)",
+                    storage.lock_path().display()
+                ),
+            )),
+            Self::App(_) | Self::File(_) => Ok(()),
+        }
+    }
+
+    fn save(&mut self, catalog: &CatalogFile) -> Result<(), String> {
+        match self {
+            Self::App(storage) => storage.save_catalog(catalog),
+            Self::File(path) => std::fs::write(&*path, catalog.to_json())
+                .map_err(|err| format!("{}: {err}", path.display())),
+        }
+    }
+}
+
+/// A failed request.
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// A failure of the server rather than of the request.
+    fn internal(message: String) -> Self {
+        Self::new(500, message)
+    }
+}
+
+/// Answers requests on `address` until the process is stopped.
+pub fn serve(address: &str, source: &mut Source) -> Result<(), String> {
+    let server = Server::http(address).map_err(|err| format!("{address}: {err}"))?;
+    println!("http://{address}/ ({})", source.describe());
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(&mut request, source) {
+            Ok(body) => (200, body),
+            Err(err) => (err.status, json!({ "error": err.message })),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(json_header());
+        if let Err(err) = request.respond(response) {
+            eprintln!("{err}");
+        }
+    }
+    Ok(())
+}
+
+fn json_header() -> Header {
+    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("Invalid constant header")
+}
+
+fn handle(request: &mut Request, source: &mut Source) -> Result<Value, ApiError> {
+    let segments = path_segments(request.url())
+        .ok_or_else(|| ApiError::new(400, "Invalid percent-encoding in the path"))?;
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, ["ingredients"]) => {
+            let catalog = source.load().map_err(ApiError::internal)?;
+            Ok(Value::Array(
+                catalog
+                    .ingredients
+                    .iter()
+                    .map(|record| ingredient_json(record, &catalog))
+                    .collect(),
+            ))
+        }
+        (Method::Get, ["plates"]) => to_json(&report::plate_cost_records(&source.catalog()?)),
+        (Method::Get, ["plates", name]) => plate_json(&source.catalog()?, name),
+        (Method::Put, ["ingredients", name, "price"]) => {
+            let mut body = String::new();
+            request
+                .as_reader()
+                .read_to_string(&mut body)
+                .map_err(|err| ApiError::new(400, err.to_string()))?;
+            update_price(source, name, &body)
+        }
+        _ => Err(ApiError::new(404, "Not found")),
+    }
+}
+
+/// The decoded segments of the path of `url`, `None` if one is not valid percent-encoded
+/// UTF-8.
+fn path_segments(url: &str) -> Option<Vec<String>> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(percent_decode)
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|err| ApiError::internal(err.to_string()))
+}
+
+fn ingredient_json(record: &IngredientRecord, catalog: &CatalogFile) -> Value {
+    json!({
+        "name": record.name,
+        "cost_price": record.cost_price,
+        "currency": record.currency.unwrap_or(catalog.settings.currency).code(),
+        "reference_quantity": record.reference_quantity,
+        "unit": record.unit,
+        "ingredient_type": record.ingredient_type,
+        "supplier": record.supplier,
+    })
+}
+
+fn plate_json(catalog: &Catalog, name: &str) -> Result<Value, ApiError> {
+    let plate = catalog
+        .find_plate(name)
+        .ok_or_else(|| ApiError::new(404, format!("Unknown plate: {name}")))?;
+    let plate = plate.borrow();
+    let valuation = catalog.valuation();
+    let mut body = to_json(&PlateCostRecord::of(&plate, catalog, &valuation))?;
+    let components = CostNodeRecord::components(&CostNode::of(&plate, &valuation));
+    body["components"] = to_json(&components)?;
+    Ok(body)
+}
+
+fn update_price(source: &mut Source, name: &str, body: &str) -> Result<Value, ApiError> {
+    let body: Value =
+        serde_json::from_str(body).map_err(|err| ApiError::new(400, err.to_string()))?;
+    let price = body["cost_price"]
+        .as_f64()
+        .filter(|price| *price >= 0.0)
+        .ok_or_else(|| ApiError::new(400, "Expected cost_price to be a number, zero or more"))?;
+
+    source.check_writable()?;
+    let mut catalog = source.catalog()?;
+    let idx = catalog
+        .ingredients
+        .iter()
+        .position(|ingredient| ingredient.borrow().name == name)
+        .ok_or_else(|| ApiError::new(404, format!("Unknown ingredient: {name}")))?;
+    let ingredient = catalog.ingredients[idx].clone();
+    let repriced_stock = catalog.set_ingredient_price(&ingredient, price);
+    let file = CatalogFile::from(&catalog);
+    source.save(&file).map_err(ApiError::internal)?;
+
+    let mut body = ingredient_json(&file.ingredients[idx], &file);
+    let valuation = catalog.valuation();
+    let plates: Vec<PlateCostRecord> = dependencies::ingredient_dependents(&catalog, &ingredient)
+        .iter()
+        .map(|dependent| PlateCostRecord::of(&dependent.plate.borrow(), &catalog, &valuation))
+        .collect();
+    body["plates"] = to_json(&plates)?;
+    body["repriced_stock"] = json!(repriced_stock);
+    Ok(body)
+}
@@ -0,0 +1,48 @@
+//! HTTP/JSON API over the catalog, for point-of-sale and ordering tools.
+//!
+//! Built with the `server` feature, on desktop only:
+//!
+//! ```text
+//! cargo run --features server --bin fnb-server -- [--addr 127.0.0.1:8080] [--catalog catalogue.json]
+//! ```
+//!
+//! It serves the catalog saved by the desktop app, or the catalog file given with
+//! `--catalog`, read again on every request. See [`api`] for the endpoints. Prices are not
+//! changed while the app is open, as it would save its own catalog over them.
+
+#![warn(clippy::all, rust_2018_idioms)]
+
+#[cfg(not(target_arch = "wasm32"))]
+mod api;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> std::process::ExitCode {
+    use api::Source;
+    use eframe_todo_list::app_storage::AppStorage;
+
+    const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let option = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|position| args.get(position + 1))
+    };
+
+    let source = match option("--catalog") {
+        Some(path) => Ok(Source::File(path.into())),
+        None => AppStorage::locate().map(Source::App),
+    };
+    let address = option("--addr").map_or(DEFAULT_ADDRESS, String::as_str);
+    match source.and_then(|mut source| api::serve(address, &mut source)) {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+// A browser page cannot listen for connections
+#[cfg(target_arch = "wasm32")]
+fn main() {}
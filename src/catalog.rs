@@ -0,0 +1,188 @@
+use poc_fnb::{Ingredient, Plate, PlateComponent};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+use crate::allergen::Allergen;
+use crate::currency::{Currency, ExchangeRates, PriceConversion};
+use crate::health::MarginThresholds;
+use crate::inventory::{Inventory, PriceUpdatePolicy};
+use crate::money::Rounding;
+use crate::recipe_method::RecipeMethod;
+use crate::valuation::{CostMethod, Valuation};
+use crate::waste::WasteLog;
+
+/// Settings stored with the catalog rather than with the app.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct CatalogSettings {
+    pub cost_method: CostMethod,
+    pub price_update_policy: PriceUpdatePolicy,
+    pub margin_thresholds: MarginThresholds,
+    pub currency: Currency,
+    pub rounding: Rounding,
+}
+
+/// Everything the kitchen manages: ingredients, recipes, stock and waste.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    pub settings: CatalogSettings,
+    pub ingredients: Vec<Rc<RefCell<Ingredient>>>,
+    pub plates: Vec<Rc<RefCell<Plate>>>,
+    pub inventory: Inventory,
+    pub waste_log: WasteLog,
+
+    /// Preparation method per plate name, since `Plate` only holds quantities.
+    pub recipe_methods: BTreeMap<String, RecipeMethod>,
+
+    /// Allergens per ingredient name, since `Ingredient` has no such field.
+    pub allergens: BTreeMap<String, BTreeSet<Allergen>>,
+
+    /// Currency of the supplier per ingredient name, for ingredients not bought in the
+    /// catalog currency. Their cost price and the unit costs of their lots are in it.
+    pub ingredient_currencies: BTreeMap<String, Currency>,
+
+    pub exchange_rates: ExchangeRates,
+}
+
+impl Catalog {
+    pub fn find_ingredient(&self, name: &str) -> Option<&Rc<RefCell<Ingredient>>> {
+        self.ingredients
+            .iter()
+            .find(|ingredient| ingredient.borrow().name == name)
+    }
+
+    pub fn find_plate(&self, name: &str) -> Option<&Rc<RefCell<Plate>>> {
+        self.plates.iter().find(|plate| plate.borrow().name == name)
+    }
+
+    /// Whether an ingredient already has `name`, in the sense of [`same_name`].
+    pub fn has_ingredient_named(&self, name: &str) -> bool {
+        self.ingredients
+            .iter()
+            .any(|ingredient| same_name(&ingredient.borrow().name, name))
+    }
+
+    /// Whether a plate already has `name`, in the sense of [`same_name`].
+    pub fn has_plate_named(&self, name: &str) -> bool {
+        self.plates
+            .iter()
+            .any(|plate| same_name(&plate.borrow().name, name))
+    }
+
+    pub fn ingredient_index(&self, ingredient: &Rc<RefCell<Ingredient>>) -> Option<usize> {
+        self.ingredients
+            .iter()
+            .position(|other| Rc::ptr_eq(other, ingredient))
+    }
+
+    pub fn plate_index(&self, plate: &Rc<RefCell<Plate>>) -> Option<usize> {
+        self.plates
+            .iter()
+            .position(|other| Rc::ptr_eq(other, plate))
+    }
+
+    /// Currency the prices of `ingredient_name` are expressed in.
+    pub fn ingredient_currency(&self, ingredient_name: &str) -> Currency {
+        self.ingredient_currencies
+            .get(ingredient_name)
+            .copied()
+            .unwrap_or(self.settings.currency)
+    }
+
+    /// Converts ingredient prices to the catalog currency.
+    pub fn price_conversion(&self) -> PriceConversion<'_> {
+        PriceConversion::new(
+            &self.ingredient_currencies,
+            &self.exchange_rates,
+            self.settings.currency,
+        )
+    }
+
+    /// Values ingredients with the catalog cost method, in the catalog currency.
+    pub fn valuation(&self) -> Valuation<'_> {
+        Valuation::new(
+            &self.inventory,
+            self.settings.cost_method,
+            self.price_conversion(),
+            self.settings.rounding,
+        )
+    }
+
+    /// Sets the price of `ingredient`, for its `reference_quantity`.
+    ///
+    /// Methods valuing the stock would not see the new price before the next delivery, so
+    /// the lots in stock are valued as if they had been bought at that price. Returns whether
+    /// any lot was.
+    pub fn set_ingredient_price(
+        &mut self,
+        ingredient: &Rc<RefCell<Ingredient>>,
+        price: f64,
+    ) -> bool {
+        let reference_quantity = {
+            let mut ingredient = ingredient.borrow_mut();
+            ingredient.cost_price = price;
+            ingredient.reference_quantity
+        };
+        if self.settings.cost_method == CostMethod::LastPrice || reference_quantity <= 0.0 {
+            return false;
+        }
+        let mut repriced_lots = false;
+        for lot in &mut self.inventory.lots {
+            if Rc::ptr_eq(&lot.ingredient, ingredient) {
+                lot.unit_cost = price / reference_quantity;
+                repriced_lots = true;
+            }
+        }
+        repriced_lots
+    }
+}
+
+/// Whether two item names would be mistaken for each other, ignoring case and surrounding
+/// spaces. Names are compared this way before being given, so that they stay unambiguous.
+pub fn same_name(first: &str, second: &str) -> bool {
+    first.trim().to_lowercase() == second.trim().to_lowercase()
+}
+
+/// Copies every field, since `Ingredient` is not `Clone`.
+pub fn copy_ingredient(ingredient: &Ingredient) -> Ingredient {
+    Ingredient {
+        name: ingredient.name.clone(),
+        cost_price: ingredient.cost_price,
+        reference_quantity: ingredient.reference_quantity,
+        unit: ingredient.unit.clone(),
+        ingredient_type: ingredient.ingredient_type.clone(),
+        supplier: ingredient.supplier.clone(),
+    }
+}
+
+/// Copies the component; the ingredient or sub-recipe it uses is shared, not copied.
+pub fn copy_component(component: &PlateComponent) -> PlateComponent {
+    match component {
+        PlateComponent::Ingredient {
+            ingredient,
+            quantity,
+            unit,
+        } => PlateComponent::Ingredient {
+            ingredient: ingredient.clone(),
+            quantity: *quantity,
+            unit: unit.clone(),
+        },
+        PlateComponent::Plate { plate, quantity } => PlateComponent::Plate {
+            plate: plate.clone(),
+            quantity: *quantity,
+        },
+    }
+}
+
+/// Copies the plate and its component list, since `Plate` is not `Clone`.
+pub fn copy_plate(plate: &Plate) -> Plate {
+    Plate {
+        name: plate.name.clone(),
+        components: plate.components.iter().map(copy_component).collect(),
+        selling_price: plate.selling_price,
+        batch_preparation_time_hours: plate.batch_preparation_time_hours,
+        batch_quantity: plate.batch_quantity,
+        batch_unit: plate.batch_unit.clone(),
+    }
+}
@@ -0,0 +1,144 @@
+//! Cost of a plate broken down level by level, from its sub-recipes down to raw ingredients.
+
+use poc_fnb::{Plate, PlateComponent, QuantityUnit};
+
+use crate::costing::{self, PlateCost};
+use crate::i18n::tr;
+use crate::money::Money;
+use crate::valuation::Valuation;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CostNodeKind {
+    Plate,
+    Ingredient,
+    /// Preparation time of the parent plate; its quantity is in hours.
+    Labor,
+}
+
+/// One component with its share of the cost; sub-recipes have their own components and
+/// labor as children, scaled to the quantity used.
+#[derive(Clone, Debug)]
+pub struct CostNode {
+    pub kind: CostNodeKind,
+    pub name: String,
+    pub quantity: f64,
+
+    /// `None` for labor, counted in hours.
+    pub unit: Option<QuantityUnit>,
+
+    /// Cost of one `unit` (or one hour).
+    pub unit_cost: Money,
+    pub cost: Money,
+    pub children: Vec<CostNode>,
+}
+
+impl CostNode {
+    /// One batch of `plate`: its components and labor, whose costs add up to the batch cost.
+    pub fn of(plate: &Plate, valuation: &Valuation<'_>) -> Self {
+        let cost = PlateCost::of(plate, valuation).total_cost_price();
+        Self {
+            kind: CostNodeKind::Plate,
+            name: plate.name.clone(),
+            quantity: plate.batch_quantity,
+            unit: Some(plate.batch_unit.clone()),
+            unit_cost: costing::batch_cost_per_unit(plate, valuation),
+            cost,
+            children: children(plate, 1.0, valuation, &mut vec![plate.name.clone()]),
+        }
+    }
+
+    /// Share of `total` this node accounts for, in percent.
+    pub fn percent_of(&self, total: Money) -> f64 {
+        self.cost.ratio_to(total) * 100.0
+    }
+
+    /// The descendants of this node depth first, with their depth (1 for the children).
+    pub fn lines(&self) -> Vec<(usize, &Self)> {
+        let mut lines = Vec::new();
+        self.push_lines(1, &mut lines);
+        lines
+    }
+
+    fn push_lines<'a>(&'a self, depth: usize, lines: &mut Vec<(usize, &'a Self)>) {
+        for child in &self.children {
+            lines.push((depth, child));
+            child.push_lines(depth + 1, lines);
+        }
+    }
+}
+
+/// Nodes of `scale` batches of `plate`; `path` holds the plates being expanded, so that a
+/// plate containing itself is not expanded forever.
+fn children(
+    plate: &Plate,
+    scale: f64,
+    valuation: &Valuation<'_>,
+    path: &mut Vec<String>,
+) -> Vec<CostNode> {
+    let mut nodes: Vec<CostNode> = plate
+        .components
+        .iter()
+        .map(|component| {
+            let cost = costing::component_cost(component, valuation).times(scale);
+            match component {
+                PlateComponent::Ingredient {
+                    ingredient,
+                    quantity,
+                    unit,
+                } => CostNode {
+                    kind: CostNodeKind::Ingredient,
+                    name: ingredient.borrow().name.clone(),
+                    quantity: quantity * scale,
+                    unit: Some(unit.clone()),
+                    unit_cost: cost.per(quantity * scale),
+                    cost,
+                    children: Vec::new(),
+                },
+                PlateComponent::Plate {
+                    plate: sub_plate,
+                    quantity,
+                } => {
+                    let sub_plate = sub_plate.borrow();
+                    let quantity = quantity * scale;
+                    let sub_children = if path.contains(&sub_plate.name) {
+                        Vec::new()
+                    } else {
+                        path.push(sub_plate.name.clone());
+                        let share = if sub_plate.batch_quantity > 0.0 {
+                            quantity / sub_plate.batch_quantity
+                        } else {
+                            0.0
+                        };
+                        let sub_children = children(&sub_plate, share, valuation, path);
+                        path.pop();
+                        sub_children
+                    };
+                    CostNode {
+                        kind: CostNodeKind::Plate,
+                        name: sub_plate.name.clone(),
+                        quantity,
+                        unit: Some(sub_plate.batch_unit.clone()),
+                        unit_cost: costing::batch_cost_per_unit(&sub_plate, valuation),
+                        cost,
+                        children: sub_children,
+                    }
+                }
+            }
+        })
+        .collect();
+
+    let labor_cost = Money::from_f64(plate.labor_cost()).round().times(scale);
+    if !labor_cost.is_zero() {
+        let hours = plate.batch_preparation_time_hours * scale;
+        nodes.push(CostNode {
+            kind: CostNodeKind::Labor,
+            name: tr("Main-d'œuvre").to_owned(),
+            quantity: hours,
+            unit: None,
+            unit_cost: labor_cost.per(hours),
+            cost: labor_cost,
+            children: Vec::new(),
+        });
+    }
+    nodes
+}
@@ -0,0 +1,160 @@
+use poc_fnb::{Ingredient, IngredientType, Plate, PlateComponent, QuantityUnit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::money::Money;
+use crate::units;
+use crate::valuation::Valuation;
+
+/// Cost of one batch of a plate, with ingredients valued by a [`Valuation`], in exact
+/// amounts rounded to the cent.
+///
+/// Mirrors `Plate::ingredient_cost` and friends, which always use `Ingredient::cost_price`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlateCost {
+    pub ingredient_cost: Money,
+    pub labor_cost: Money,
+    pub selling_price: Money,
+}
+
+impl PlateCost {
+    pub fn of(plate: &Plate, valuation: &Valuation<'_>) -> Self {
+        Self {
+            ingredient_cost: ingredient_cost(plate, valuation),
+            labor_cost: Money::from_f64(plate.labor_cost()).round(),
+            selling_price: Money::from_f64(plate.selling_price).round(),
+        }
+    }
+
+    pub fn total_cost_price(&self) -> Money {
+        self.ingredient_cost + self.labor_cost
+    }
+
+    pub fn gross_margin(&self) -> Money {
+        self.selling_price - self.total_cost_price()
+    }
+
+    /// Gross margin as a fraction of the selling price.
+    pub fn margin_rate(&self) -> f64 {
+        self.gross_margin().ratio_to(self.selling_price)
+    }
+
+    /// Ingredient cost as a fraction of the selling price.
+    pub fn food_cost_rate(&self) -> f64 {
+        self.ingredient_cost.ratio_to(self.selling_price)
+    }
+}
+
+/// Ingredient cost of one batch of `plate`, sub-recipes included, rounded according to the
+/// rounding of `valuation`.
+pub fn ingredient_cost(plate: &Plate, valuation: &Valuation<'_>) -> Money {
+    valuation.rounding().total(
+        plate
+            .components
+            .iter()
+            .map(|component| component_cost(component, valuation)),
+    )
+}
+
+/// Exact cost of one component, before any rounding.
+pub fn component_cost(component: &PlateComponent, valuation: &Valuation<'_>) -> Money {
+    match component {
+        PlateComponent::Ingredient {
+            ingredient,
+            quantity,
+            unit,
+        } => {
+            let ingredient_unit = ingredient.borrow().unit.clone();
+            // Incompatible units are costed as if they were the ingredient unit
+            let quantity = units::convert(*quantity, unit, &ingredient_unit).unwrap_or(*quantity);
+            Money::from_f64(valuation.cost_of(ingredient, quantity))
+        }
+        PlateComponent::Plate { plate, quantity } => {
+            batch_cost_per_unit(&plate.borrow(), valuation).times(*quantity)
+        }
+    }
+}
+
+/// Cost of one [`units::base_unit`] of `ingredient`, so that ingredients bought in different
+/// units can be compared.
+pub fn cost_per_base_unit(
+    ingredient: &Rc<RefCell<Ingredient>>,
+    valuation: &Valuation<'_>,
+) -> (f64, QuantityUnit) {
+    let unit = ingredient.borrow().unit.clone();
+    let base_unit = units::base_unit(&unit);
+    let base_per_unit = units::convert(1.0, &unit, &base_unit).unwrap_or(1.0);
+    let unit_cost = valuation.unit_cost_of(ingredient);
+    if base_per_unit > 0.0 {
+        (unit_cost / base_per_unit, base_unit)
+    } else {
+        (unit_cost, unit)
+    }
+}
+
+/// Total cost of one `Plate::batch_unit` of `plate`, not rounded.
+pub fn batch_cost_per_unit(plate: &Plate, valuation: &Valuation<'_>) -> Money {
+    PlateCost::of(plate, valuation)
+        .total_cost_price()
+        .per(plate.batch_quantity)
+}
+
+/// Ingredient cost of one batch of `plate` split by `IngredientType`, sub-recipes included.
+///
+/// Labor of the sub-recipes is not attributed to any type.
+pub fn ingredient_cost_by_type(
+    plate: &Plate,
+    valuation: &Valuation<'_>,
+) -> Vec<(IngredientType, f64)> {
+    let mut totals = Vec::new();
+    for component in &plate.components {
+        match component {
+            PlateComponent::Ingredient { ingredient, .. } => {
+                let ingredient_type = ingredient.borrow().ingredient_type.clone();
+                add_to(
+                    &mut totals,
+                    ingredient_type,
+                    component_cost(component, valuation).to_f64(),
+                );
+            }
+            PlateComponent::Plate {
+                plate: sub_plate,
+                quantity,
+            } => {
+                let sub_plate = sub_plate.borrow();
+                if sub_plate.batch_quantity > 0.0 {
+                    let share = quantity / sub_plate.batch_quantity;
+                    for (ingredient_type, cost) in ingredient_cost_by_type(&sub_plate, valuation) {
+                        add_to(&mut totals, ingredient_type, cost * share);
+                    }
+                }
+            }
+        }
+    }
+    totals
+}
+
+/// Food-cost rate of every plate, in catalog order.
+pub fn food_cost_rates(
+    plates: &[Rc<RefCell<Plate>>],
+    valuation: &Valuation<'_>,
+) -> Vec<(String, f64)> {
+    plates
+        .iter()
+        .map(|plate| {
+            let plate = plate.borrow();
+            (
+                plate.name.clone(),
+                PlateCost::of(&plate, valuation).food_cost_rate(),
+            )
+        })
+        .collect()
+}
+
+/// Adds `value` to the total of `key`, for keys that are neither `Hash` nor `Ord`.
+pub fn add_to<K: PartialEq>(totals: &mut Vec<(K, f64)>, key: K, value: f64) {
+    match totals.iter_mut().find(|(existing, _)| *existing == key) {
+        Some((_, total)) => *total += value,
+        None => totals.push((key, value)),
+    }
+}
@@ -0,0 +1,217 @@
+//! Currencies prices are expressed in, and conversion between them.
+
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Currency of the amounts of a catalog.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Currency {
+    #[default]
+    Eur,
+    Chf,
+    Gbp,
+    Usd,
+}
+
+impl Currency {
+    pub const ALL: [Self; 4] = [Self::Eur, Self::Chf, Self::Gbp, Self::Usd];
+
+    /// ISO 4217 code.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Eur => "EUR",
+            Self::Chf => "CHF",
+            Self::Gbp => "GBP",
+            Self::Usd => "USD",
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Eur => "€",
+            Self::Chf => "CHF",
+            Self::Gbp => "£",
+            Self::Usd => "$",
+        }
+    }
+}
+
+/// Value of one `from` in `to`, from `effective_from` until the next rate of the same pair.
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ExchangeRate {
+    pub from: Currency,
+    pub to: Currency,
+    pub effective_from: NaiveDate,
+    pub rate: f64,
+}
+
+/// Exchange rates entered by hand, with the dates they apply from.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct ExchangeRates {
+    pub rates: Vec<ExchangeRate>,
+}
+
+impl ExchangeRates {
+    /// Value of one `from` in `to` on `date`: the latest rate of the pair in effect, entered
+    /// in either direction.
+    pub fn rate(&self, from: Currency, to: Currency, date: NaiveDate) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.rates
+            .iter()
+            .filter(|rate| rate.effective_from <= date && rate.rate > 0.0)
+            .filter_map(|rate| {
+                if rate.from == from && rate.to == to {
+                    Some((rate.effective_from, rate.rate))
+                } else if rate.from == to && rate.to == from {
+                    Some((rate.effective_from, 1.0 / rate.rate))
+                } else {
+                    None
+                }
+            })
+            // Of two rates of the same day, the one entered last
+            .max_by_key(|(effective_from, _)| *effective_from)
+            .map(|(_, rate)| rate)
+    }
+}
+
+/// Converts the prices of ingredients from the currency of their supplier to the catalog
+/// currency.
+#[derive(Clone, Copy, Debug)]
+pub struct PriceConversion<'a> {
+    ingredient_currencies: &'a BTreeMap<String, Currency>,
+    rates: &'a ExchangeRates,
+    currency: Currency,
+    today: NaiveDate,
+}
+
+impl<'a> PriceConversion<'a> {
+    /// Conversion to `currency` of the ingredients listed in `ingredient_currencies`; the
+    /// others are already priced in `currency`.
+    pub fn new(
+        ingredient_currencies: &'a BTreeMap<String, Currency>,
+        rates: &'a ExchangeRates,
+        currency: Currency,
+    ) -> Self {
+        Self {
+            ingredient_currencies,
+            rates,
+            currency,
+            today: chrono::Local::now().date_naive(),
+        }
+    }
+
+    /// Currency the prices of the ingredient named `ingredient_name` are expressed in.
+    pub fn currency_of(&self, ingredient_name: &str) -> Currency {
+        self.ingredient_currencies
+            .get(ingredient_name)
+            .copied()
+            .unwrap_or(self.currency)
+    }
+
+    /// `amount` of the ingredient named `ingredient_name`, paid on `date`, in the catalog
+    /// currency. Amounts without a rate for that date are left as they are.
+    pub fn convert_on(&self, ingredient_name: &str, amount: f64, date: NaiveDate) -> f64 {
+        self.rates
+            .rate(self.currency_of(ingredient_name), self.currency, date)
+            .map_or(amount, |rate| amount * rate)
+    }
+
+    /// `convert_on` at today's rate, for prices that are not tied to a purchase.
+    pub fn convert(&self, ingredient_name: &str, amount: f64) -> f64 {
+        self.convert_on(ingredient_name, amount, self.today)
+    }
+
+    /// Whether the ingredient named `ingredient_name` is priced in a currency without a
+    /// rate to the catalog currency today.
+    pub fn lacks_rate(&self, ingredient_name: &str) -> bool {
+        self.rates
+            .rate(self.currency_of(ingredient_name), self.currency, self.today)
+            .is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).expect("valid date")
+    }
+
+    fn rate(month: u32, day: u32, rate: f64) -> ExchangeRate {
+        ExchangeRate {
+            from: Currency::Eur,
+            to: Currency::Chf,
+            effective_from: date(month, day),
+            rate,
+        }
+    }
+
+    fn rates() -> ExchangeRates {
+        ExchangeRates {
+            rates: vec![rate(3, 1, 0.93), rate(1, 1, 0.95)],
+        }
+    }
+
+    #[test]
+    fn the_latest_rate_in_effect_applies() {
+        let rates = rates();
+        assert_eq!(
+            rates.rate(Currency::Eur, Currency::Chf, date(2, 1)),
+            Some(0.95)
+        );
+        assert_eq!(
+            rates.rate(Currency::Eur, Currency::Chf, date(3, 1)),
+            Some(0.93)
+        );
+        assert_eq!(rates.rate(Currency::Eur, Currency::Gbp, date(3, 1)), None);
+        assert_eq!(
+            rates.rate(
+                Currency::Eur,
+                Currency::Chf,
+                NaiveDate::from_ymd_opt(2025, 12, 31).expect("valid date")
+            ),
+            None
+        );
+        assert_eq!(
+            rates.rate(Currency::Gbp, Currency::Gbp, date(1, 1)),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn rates_apply_in_both_directions() {
+        let inverse = rates().rate(Currency::Chf, Currency::Eur, date(2, 1));
+        assert!(inverse.is_some_and(|inverse| (inverse - 1.0 / 0.95).abs() < 1e-9));
+    }
+
+    #[test]
+    fn of_two_rates_of_the_same_day_the_last_entered_applies() {
+        let mut rates = rates();
+        rates.rates.push(rate(3, 1, 0.94));
+        assert_eq!(
+            rates.rate(Currency::Eur, Currency::Chf, date(3, 2)),
+            Some(0.94)
+        );
+    }
+
+    #[test]
+    fn prices_are_converted_from_the_currency_of_their_ingredient() {
+        let ingredient_currencies = BTreeMap::from([
+            ("Mozzarella".to_owned(), Currency::Eur),
+            ("Cheddar".to_owned(), Currency::Gbp),
+        ]);
+        let rates = rates();
+        let conversion = PriceConversion::new(&ingredient_currencies, &rates, Currency::Chf);
+
+        assert!((conversion.convert_on("Mozzarella", 10.0, date(2, 1)) - 9.5).abs() < 1e-9);
+        assert!((conversion.convert_on("Farine", 10.0, date(2, 1)) - 10.0).abs() < 1e-9);
+        // Without a rate the amount is left as it is, and flagged
+        assert!((conversion.convert_on("Cheddar", 10.0, date(2, 1)) - 10.0).abs() < 1e-9);
+        assert!(conversion.lacks_rate("Cheddar"));
+        assert!(!conversion.lacks_rate("Farine"));
+    }
+}
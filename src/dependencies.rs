@@ -0,0 +1,248 @@
+//! Which recipes use an ingredient or a sub-recipe, directly or through other sub-recipes.
+
+use poc_fnb::{Ingredient, Plate, PlateComponent};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::catalog::Catalog;
+
+/// A plate depending on the item looked up.
+#[derive(Clone, Debug)]
+pub struct Dependent {
+    pub plate: Rc<RefCell<Plate>>,
+
+    /// Sub-recipes leading from `plate` to the item, outermost first; empty for a direct use.
+    pub path: Vec<String>,
+}
+
+impl Dependent {
+    pub fn is_direct(&self) -> bool {
+        self.path.is_empty()
+    }
+}
+
+/// Shortest chain of sub-recipes from `plate` to a component matching `uses`.
+fn path_to(
+    plate: &Plate,
+    uses: &dyn Fn(&PlateComponent) -> bool,
+    visited: &mut Vec<*const RefCell<Plate>>,
+) -> Option<Vec<String>> {
+    if plate.components.iter().any(uses) {
+        return Some(Vec::new());
+    }
+    // Breadth first, so that the first chain found is one of the shortest
+    let mut queue = VecDeque::new();
+    queue_sub_plates(plate, &[], visited, &mut queue);
+    while let Some((sub_plate, path)) = queue.pop_front() {
+        let sub_plate = sub_plate.borrow();
+        if sub_plate.components.iter().any(uses) {
+            return Some(path);
+        }
+        queue_sub_plates(&sub_plate, &path, visited, &mut queue);
+    }
+    None
+}
+
+/// Queues the sub-recipes of `plate` not visited yet, each with the chain leading to it
+/// from `path`, the chain leading to `plate`.
+fn queue_sub_plates(
+    plate: &Plate,
+    path: &[String],
+    visited: &mut Vec<*const RefCell<Plate>>,
+    queue: &mut VecDeque<(Rc<RefCell<Plate>>, Vec<String>)>,
+) {
+    for component in &plate.components {
+        let PlateComponent::Plate {
+            plate: sub_plate, ..
+        } = component
+        else {
+            continue;
+        };
+        // Also guards against recipes that contain themselves
+        if visited.contains(&Rc::as_ptr(sub_plate)) {
+            continue;
+        }
+        visited.push(Rc::as_ptr(sub_plate));
+
+        let mut sub_path = path.to_vec();
+        sub_path.push(sub_plate.borrow().name.clone());
+        queue.push_back((sub_plate.clone(), sub_path));
+    }
+}
+
+fn dependents(
+    catalog: &Catalog,
+    skip: Option<&Rc<RefCell<Plate>>>,
+    uses: &dyn Fn(&PlateComponent) -> bool,
+) -> Vec<Dependent> {
+    catalog
+        .plates
+        .iter()
+        .filter(|plate| skip.is_none_or(|skip| !Rc::ptr_eq(plate, skip)))
+        .filter_map(|plate| {
+            let mut visited = vec![Rc::as_ptr(plate)];
+            path_to(&plate.borrow(), uses, &mut visited).map(|path| Dependent {
+                plate: plate.clone(),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Plates using `ingredient`, in catalog order.
+pub fn ingredient_dependents(
+    catalog: &Catalog,
+    ingredient: &Rc<RefCell<Ingredient>>,
+) -> Vec<Dependent> {
+    dependents(catalog, None, &|component| {
+        matches!(component, PlateComponent::Ingredient { ingredient: used, .. }
+            if Rc::ptr_eq(used, ingredient))
+    })
+}
+
+/// Plates using `plate` as a sub-recipe, in catalog order.
+pub fn plate_dependents(catalog: &Catalog, plate: &Rc<RefCell<Plate>>) -> Vec<Dependent> {
+    dependents(catalog, Some(plate), &|component| {
+        matches!(component, PlateComponent::Plate { plate: used, .. }
+            if Rc::ptr_eq(used, plate))
+    })
+}
+
+/// Sub-recipes through which `plate` contains itself, outermost first; `None` if it does not.
+///
+/// The chain is empty when `plate` is one of its own components.
+pub fn cycle_of(plate: &Rc<RefCell<Plate>>) -> Option<Vec<String>> {
+    let mut visited = vec![Rc::as_ptr(plate)];
+    let uses_itself = |component: &PlateComponent| {
+        matches!(component, PlateComponent::Plate { plate: used, .. }
+            if Rc::ptr_eq(used, plate))
+    };
+    path_to(&plate.borrow(), &uses_itself, &mut visited)
+}
+
+/// Whether `plate` is `other` or contains it, at any depth.
+///
+/// Adding `other` as a component of `plate` creates a cycle exactly when `other` uses `plate`.
+pub fn uses_plate(plate: &Rc<RefCell<Plate>>, other: &Rc<RefCell<Plate>>) -> bool {
+    if Rc::ptr_eq(plate, other) {
+        return true;
+    }
+    let mut visited = vec![Rc::as_ptr(plate)];
+    let uses_other = |component: &PlateComponent| {
+        matches!(component, PlateComponent::Plate { plate: used, .. }
+            if Rc::ptr_eq(used, other))
+    };
+    path_to(&plate.borrow(), &uses_other, &mut visited).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poc_fnb::{IngredientType, QuantityUnit, Supplier};
+
+    fn plate(name: &str, components: Vec<PlateComponent>) -> Rc<RefCell<Plate>> {
+        Rc::new(RefCell::new(Plate {
+            name: name.to_owned(),
+            components,
+            selling_price: 0.0,
+            batch_preparation_time_hours: 0.0,
+            batch_quantity: 1.0,
+            batch_unit: QuantityUnit::Piece,
+        }))
+    }
+
+    fn sub_recipe(plate: &Rc<RefCell<Plate>>) -> PlateComponent {
+        PlateComponent::Plate {
+            plate: plate.clone(),
+            quantity: 1.0,
+        }
+    }
+
+    fn paths(dependents: &[Dependent]) -> Vec<(String, Vec<String>)> {
+        dependents
+            .iter()
+            .map(|dependent| {
+                (
+                    dependent.plate.borrow().name.clone(),
+                    dependent.path.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Salt in a sauce, used by a base and by a pizza both directly and through the base.
+    fn catalog() -> (Catalog, Rc<RefCell<Ingredient>>, Rc<RefCell<Plate>>) {
+        let salt = Rc::new(RefCell::new(Ingredient {
+            name: "Sel".to_owned(),
+            cost_price: 0.5,
+            reference_quantity: 1.0,
+            unit: QuantityUnit::Kilogram,
+            ingredient_type: IngredientType::Grocery,
+            supplier: Supplier {
+                name: "Saline".to_owned(),
+                contact: None,
+            },
+        }));
+        let sauce = plate(
+            "Sauce",
+            vec![PlateComponent::Ingredient {
+                ingredient: salt.clone(),
+                quantity: 5.0,
+                unit: QuantityUnit::Gram,
+            }],
+        );
+        let base = plate("Base", vec![sub_recipe(&sauce)]);
+        // The longer chain comes first among the components
+        let pizza = plate("Pizza", vec![sub_recipe(&base), sub_recipe(&sauce)]);
+        let catalog = Catalog {
+            ingredients: vec![salt.clone()],
+            plates: vec![pizza, base, sauce.clone()],
+            ..Default::default()
+        };
+        (catalog, salt, sauce)
+    }
+
+    #[test]
+    fn ingredient_dependents_go_through_the_shortest_chain() {
+        let (catalog, salt, _) = catalog();
+        let path = |names: &[&str]| names.iter().map(|name| (*name).to_owned()).collect();
+        assert_eq!(
+            paths(&ingredient_dependents(&catalog, &salt)),
+            [
+                ("Pizza".to_owned(), path(&["Sauce"])),
+                ("Base".to_owned(), path(&["Sauce"])),
+                ("Sauce".to_owned(), Vec::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn plate_dependents_leave_out_the_plate_itself() {
+        let (catalog, _, sauce) = catalog();
+        let dependents = plate_dependents(&catalog, &sauce);
+        assert!(dependents.iter().all(Dependent::is_direct));
+        assert_eq!(
+            paths(&dependents),
+            [
+                ("Pizza".to_owned(), Vec::new()),
+                ("Base".to_owned(), Vec::new())
+            ]
+        );
+    }
+
+    #[test]
+    fn cycles_are_found_without_looping() {
+        let first = plate("Pâte mère", Vec::new());
+        let second = plate("Levain", vec![sub_recipe(&first)]);
+        first.borrow_mut().components.push(sub_recipe(&second));
+
+        assert_eq!(cycle_of(&first), Some(vec!["Levain".to_owned()]));
+        assert!(uses_plate(&first, &second));
+
+        let (catalog, _, sauce) = catalog();
+        assert_eq!(cycle_of(&sauce), None);
+        assert!(!uses_plate(&sauce, &catalog.plates[0]));
+        assert!(uses_plate(&catalog.plates[0], &sauce));
+    }
+}
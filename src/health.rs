@@ -0,0 +1,296 @@
+//! Margin alerts and consistency checks of the recipes.
+
+use poc_fnb::{Plate, PlateComponent};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::catalog::Catalog;
+use crate::costing::PlateCost;
+use crate::currency::Currency;
+use crate::dependencies;
+use crate::i18n::trf;
+use crate::locale;
+use crate::money::Money;
+use crate::units;
+use crate::valuation::Valuation;
+
+/// Distance to a threshold under which a plate is flagged amber, as a fraction of the
+/// selling price (5 points).
+const AMBER_BAND: f64 = 0.05;
+
+/// Limits below or above which a plate sold at a given price is flagged.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct MarginThresholds {
+    /// Minimum gross margin, as a fraction of the selling price.
+    pub min_margin_rate: f64,
+
+    /// Maximum ingredient cost, as a fraction of the selling price.
+    pub max_food_cost_rate: f64,
+}
+
+impl Default for MarginThresholds {
+    fn default() -> Self {
+        Self {
+            min_margin_rate: 0.70,
+            max_food_cost_rate: 0.30,
+        }
+    }
+}
+
+/// Red/amber/green status of a plate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Green,
+    Amber,
+    Red,
+}
+
+impl MarginThresholds {
+    /// Status of a plate costing `cost`, or `None` for plates without selling price, such as
+    /// sub-recipes.
+    pub fn status(&self, cost: &PlateCost) -> Option<Status> {
+        if cost.selling_price <= Money::ZERO {
+            return None;
+        }
+        let margin_rate = cost.margin_rate();
+        let food_cost_rate = cost.food_cost_rate();
+        let status = if cost.gross_margin().is_negative()
+            || margin_rate < self.min_margin_rate
+            || food_cost_rate > self.max_food_cost_rate
+        {
+            Status::Red
+        } else if margin_rate < self.min_margin_rate + AMBER_BAND
+            || food_cost_rate > self.max_food_cost_rate - AMBER_BAND
+        {
+            Status::Amber
+        } else {
+            Status::Green
+        };
+        Some(status)
+    }
+}
+
+/// Something worth fixing in the catalog.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Issue {
+    MarginBelow {
+        plate: String,
+        margin_rate: f64,
+        minimum: f64,
+    },
+    FoodCostAbove {
+        plate: String,
+        food_cost_rate: f64,
+        maximum: f64,
+    },
+    NoComponents {
+        plate: String,
+    },
+    /// A plate that is neither sold nor used as a sub-recipe.
+    NoSellingPrice {
+        plate: String,
+    },
+    /// A recipe quantity that cannot be converted to the purchase unit of its ingredient.
+    IncompatibleUnit {
+        plate: String,
+        ingredient: String,
+    },
+    ZeroPrice {
+        ingredient: String,
+    },
+    /// A recipe containing itself, directly or through `path`, which cannot be costed.
+    SubRecipeCycle {
+        plate: String,
+        path: Vec<String>,
+    },
+    /// An ingredient priced in a currency without a rate to the catalog currency, whose
+    /// costs are counted without conversion.
+    MissingExchangeRate {
+        ingredient: String,
+        from: Currency,
+        to: Currency,
+    },
+}
+
+impl Issue {
+    pub fn status(&self) -> Status {
+        match self {
+            Self::MarginBelow { .. }
+            | Self::FoodCostAbove { .. }
+            | Self::IncompatibleUnit { .. }
+            | Self::SubRecipeCycle { .. }
+            | Self::MissingExchangeRate { .. } => Status::Red,
+            Self::NoComponents { .. } | Self::NoSellingPrice { .. } | Self::ZeroPrice { .. } => {
+                Status::Amber
+            }
+        }
+    }
+
+    /// Short message in the current language, for the warnings list.
+    pub fn message(&self) -> String {
+        match self {
+            Self::MarginBelow {
+                plate,
+                margin_rate,
+                minimum,
+            } => trf(
+                "{} : marge de {} sous le minimum de {}",
+                &[
+                    plate,
+                    &locale::percent(margin_rate * 100.0, 1),
+                    &locale::percent(minimum * 100.0, 0),
+                ],
+            ),
+            Self::FoodCostAbove {
+                plate,
+                food_cost_rate,
+                maximum,
+            } => trf(
+                "{} : food cost de {} au-dessus du maximum de {}",
+                &[
+                    plate,
+                    &locale::percent(food_cost_rate * 100.0, 1),
+                    &locale::percent(maximum * 100.0, 0),
+                ],
+            ),
+            Self::NoComponents { plate } => trf("{} : recette sans composant", &[plate]),
+            Self::NoSellingPrice { plate } => trf(
+                "{} : ni prix de vente, ni utilisée comme sous-recette",
+                &[plate],
+            ),
+            Self::IncompatibleUnit { plate, ingredient } => trf(
+                "{} : quantité de {} dans une unité incompatible avec son unité d'achat",
+                &[plate, ingredient],
+            ),
+            Self::ZeroPrice { ingredient } => trf("{} : prix nul", &[ingredient]),
+            Self::SubRecipeCycle { plate, path } => {
+                let chain: Vec<&str> = std::iter::once(plate)
+                    .chain(path)
+                    .chain(std::iter::once(plate))
+                    .map(String::as_str)
+                    .collect();
+                trf(
+                    "{} : recette contenue dans elle-même ({})",
+                    &[plate, &chain.join(" → ")],
+                )
+            }
+            Self::MissingExchangeRate {
+                ingredient,
+                from,
+                to,
+            } => trf(
+                "{} : aucun taux de change {} → {} en vigueur",
+                &[ingredient, &from.code(), &to.code()],
+            ),
+        }
+    }
+}
+
+fn is_sub_recipe(plate: &Rc<RefCell<Plate>>, plates: &[Rc<RefCell<Plate>>]) -> bool {
+    plates.iter().any(|other| {
+        other.borrow().components.iter().any(|component| {
+            matches!(component, PlateComponent::Plate { plate: used, .. } if Rc::ptr_eq(used, plate))
+        })
+    })
+}
+
+/// Issues of one plate, margins checked against `thresholds`.
+pub fn check_plate(
+    plate_rc: &Rc<RefCell<Plate>>,
+    catalog: &Catalog,
+    valuation: &Valuation<'_>,
+    thresholds: &MarginThresholds,
+) -> Vec<Issue> {
+    let plate = plate_rc.borrow();
+    let name = || plate.name.clone();
+    let mut issues = Vec::new();
+
+    if plate.components.is_empty() {
+        issues.push(Issue::NoComponents { plate: name() });
+    }
+    for component in &plate.components {
+        if let PlateComponent::Ingredient {
+            ingredient, unit, ..
+        } = component
+        {
+            let ingredient = ingredient.borrow();
+            if units::convert(1.0, unit, &ingredient.unit).is_none() {
+                issues.push(Issue::IncompatibleUnit {
+                    plate: name(),
+                    ingredient: ingredient.name.clone(),
+                });
+            }
+        }
+    }
+
+    let cost = PlateCost::of(&plate, valuation);
+    if cost.selling_price <= Money::ZERO {
+        if !is_sub_recipe(plate_rc, &catalog.plates) {
+            issues.push(Issue::NoSellingPrice { plate: name() });
+        }
+        return issues;
+    }
+    if cost.margin_rate() < thresholds.min_margin_rate {
+        issues.push(Issue::MarginBelow {
+            plate: name(),
+            margin_rate: cost.margin_rate(),
+            minimum: thresholds.min_margin_rate,
+        });
+    }
+    if cost.food_cost_rate() > thresholds.max_food_cost_rate {
+        issues.push(Issue::FoodCostAbove {
+            plate: name(),
+            food_cost_rate: cost.food_cost_rate(),
+            maximum: thresholds.max_food_cost_rate,
+        });
+    }
+    issues
+}
+
+/// Recipes containing themselves, in catalog order.
+pub fn cycles(catalog: &Catalog) -> Vec<Issue> {
+    catalog
+        .plates
+        .iter()
+        .filter_map(|plate| {
+            dependencies::cycle_of(plate).map(|path| Issue::SubRecipeCycle {
+                plate: plate.borrow().name.clone(),
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Every issue of the catalog, ingredients first, with the thresholds of its settings.
+///
+/// Plates are only checked once no recipe contains itself, since costing one would never end.
+pub fn validate(catalog: &Catalog, valuation: &Valuation<'_>) -> Vec<Issue> {
+    let thresholds = catalog.settings.margin_thresholds;
+    let conversion = catalog.price_conversion();
+    let mut issues = Vec::new();
+    for ingredient in &catalog.ingredients {
+        let ingredient = ingredient.borrow();
+        if ingredient.cost_price <= 0.0 {
+            issues.push(Issue::ZeroPrice {
+                ingredient: ingredient.name.clone(),
+            });
+        }
+        if conversion.lacks_rate(&ingredient.name) {
+            issues.push(Issue::MissingExchangeRate {
+                ingredient: ingredient.name.clone(),
+                from: conversion.currency_of(&ingredient.name),
+                to: catalog.settings.currency,
+            });
+        }
+    }
+    let cycles = cycles(catalog);
+    if !cycles.is_empty() {
+        issues.extend(cycles);
+        return issues;
+    }
+    for plate in &catalog.plates {
+        issues.extend(check_plate(plate, catalog, valuation, &thresholds));
+    }
+    issues
+}
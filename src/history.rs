@@ -0,0 +1,1154 @@
+//! Undo/redo of catalog edits.
+//!
+//! Each edit keeps the ingredients and plates it touches by `Rc`, so undoing a deletion
+//! puts back the very same ingredient every recipe was pointing to.
+
+use poc_fnb::{Ingredient, Plate, PlateComponent};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use crate::allergen::Allergen;
+use crate::catalog::{Catalog, CatalogSettings, copy_component, copy_ingredient, copy_plate};
+use crate::currency::{Currency, ExchangeRates};
+use crate::i18n::{tr, trf};
+use crate::inventory::StockLot;
+use crate::recipe_method::RecipeMethod;
+use crate::substitution::Substitution;
+use crate::unit_change::UnitChange;
+use crate::units;
+use crate::waste::{Loss, WasteEntry, WastedItem};
+
+/// A recipe component removed together with the ingredient or sub-recipe it used.
+#[derive(Debug)]
+pub struct RemovedComponent {
+    pub plate: Rc<RefCell<Plate>>,
+    pub index: usize,
+    pub component: PlateComponent,
+}
+
+/// Components of every plate matching `uses`, by plate then by ascending position.
+fn usages(catalog: &Catalog, uses: impl Fn(&PlateComponent) -> bool) -> Vec<RemovedComponent> {
+    let mut removed = Vec::new();
+    for plate in &catalog.plates {
+        for (index, component) in plate.borrow().components.iter().enumerate() {
+            if uses(component) {
+                removed.push(RemovedComponent {
+                    plate: plate.clone(),
+                    index,
+                    component: copy_component(component),
+                });
+            }
+        }
+    }
+    removed
+}
+
+fn remove_components(removed: &[RemovedComponent]) {
+    // Backwards, so that the positions of the remaining components stay valid
+    for usage in removed.iter().rev() {
+        let mut plate = usage.plate.borrow_mut();
+        if usage.index < plate.components.len() {
+            plate.components.remove(usage.index);
+        }
+    }
+}
+
+fn restore_components(removed: &[RemovedComponent]) {
+    for usage in removed {
+        let mut plate = usage.plate.borrow_mut();
+        let index = usage.index.min(plate.components.len());
+        plate
+            .components
+            .insert(index, copy_component(&usage.component));
+    }
+}
+
+/// Items of `items` matching `taken`, each with its position.
+fn positioned<T: Clone>(items: &[T], taken: impl Fn(&T) -> bool) -> Vec<(usize, T)> {
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| taken(item))
+        .map(|(index, item)| (index, item.clone()))
+        .collect()
+}
+
+fn remove_positioned<T>(items: &mut Vec<T>, removed: &[(usize, T)]) {
+    // Backwards, so that the positions of the remaining items stay valid
+    for (index, _) in removed.iter().rev() {
+        if *index < items.len() {
+            items.remove(*index);
+        }
+    }
+}
+
+fn restore_positioned<T: Clone>(items: &mut Vec<T>, removed: &[(usize, T)]) {
+    for (index, item) in removed {
+        let index = (*index).min(items.len());
+        items.insert(index, item.clone());
+    }
+}
+
+/// One undoable change to the catalog.
+#[derive(Debug)]
+pub enum Edit {
+    AddIngredient {
+        ingredient: Rc<RefCell<Ingredient>>,
+        index: usize,
+    },
+    RemoveIngredient {
+        ingredient: Rc<RefCell<Ingredient>>,
+        index: usize,
+        usages: Vec<RemovedComponent>,
+    },
+    UpdateIngredient {
+        ingredient: Rc<RefCell<Ingredient>>,
+        before: Ingredient,
+        after: Ingredient,
+    },
+    AddPlate {
+        plate: Rc<RefCell<Plate>>,
+        index: usize,
+    },
+    RemovePlate {
+        plate: Rc<RefCell<Plate>>,
+        index: usize,
+        usages: Vec<RemovedComponent>,
+        method: Option<RecipeMethod>,
+    },
+    /// Any change to a plate, including its components.
+    UpdatePlate {
+        plate: Rc<RefCell<Plate>>,
+        before: Plate,
+        after: Plate,
+    },
+    UpdateMethod {
+        plate_name: String,
+        before: RecipeMethod,
+        after: RecipeMethod,
+    },
+    UpdateAllergens {
+        ingredient_name: String,
+        before: BTreeSet<Allergen>,
+        after: BTreeSet<Allergen>,
+    },
+    /// `None` when the ingredient is bought in the catalog currency.
+    UpdateIngredientCurrency {
+        ingredient_name: String,
+        before: Option<Currency>,
+        after: Option<Currency>,
+    },
+    UpdateSettings {
+        before: CatalogSettings,
+        after: CatalogSettings,
+    },
+    UpdateExchangeRates {
+        before: ExchangeRates,
+        after: ExchangeRates,
+    },
+    /// Lots added to the stock by a delivery, from `index` in `Inventory::lots`.
+    ReceiveLots { index: usize, lots: Vec<StockLot> },
+    /// Lots taken out of `Inventory::lots`, each with its position there.
+    RemoveLots { lots: Vec<(usize, StockLot)> },
+    /// Entries of `WasteLog::entries` turned to a deleted item, each with its position there.
+    DetachWaste { entries: Vec<(usize, WasteEntry)> },
+    /// A loss added at `index` in `WasteLog::entries`, with the quantities it took out of
+    /// the lots at the given positions in `Inventory::lots`.
+    RecordWaste {
+        index: usize,
+        entry: WasteEntry,
+        consumed: Vec<(usize, f64)>,
+    },
+    /// Several edits undone together.
+    Group {
+        description: String,
+        edits: Vec<Edit>,
+    },
+}
+
+/// Updates of every plate with a component matching `uses`, pointing it to `replace`'s result.
+fn replace_components(
+    catalog: &Catalog,
+    uses: impl Fn(&PlateComponent) -> bool,
+    replace: impl Fn(&PlateComponent) -> PlateComponent,
+) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    for plate in &catalog.plates {
+        let before = copy_plate(&plate.borrow());
+        if !before.components.iter().any(&uses) {
+            continue;
+        }
+        let mut after = copy_plate(&before);
+        for component in &mut after.components {
+            if uses(component) {
+                *component = replace(component);
+            }
+        }
+        edits.push(Edit::UpdatePlate {
+            plate: plate.clone(),
+            before,
+            after,
+        });
+    }
+    edits
+}
+
+/// Edits taking out what refers to `ingredient` besides recipes: its stock lots, allergens
+/// and currency. Its waste entries are kept, turned to a deleted item.
+fn ingredient_records_removal(
+    catalog: &Catalog,
+    ingredient: &Rc<RefCell<Ingredient>>,
+) -> Vec<Edit> {
+    let name = ingredient.borrow().name.clone();
+    let mut edits = Vec::new();
+    let lots = positioned(&catalog.inventory.lots, |lot| {
+        Rc::ptr_eq(&lot.ingredient, ingredient)
+    });
+    if !lots.is_empty() {
+        edits.push(Edit::RemoveLots { lots });
+    }
+    let entries = positioned(
+        &catalog.waste_log.entries,
+        |entry| matches!(&entry.loss.item, WastedItem::Ingredient(wasted) if Rc::ptr_eq(wasted, ingredient)),
+    );
+    if !entries.is_empty() {
+        edits.push(Edit::DetachWaste { entries });
+    }
+    if let Some(allergens) = catalog.allergens.get(&name) {
+        edits.push(Edit::UpdateAllergens {
+            ingredient_name: name.clone(),
+            before: allergens.clone(),
+            after: BTreeSet::new(),
+        });
+    }
+    if let Some(currency) = catalog.ingredient_currencies.get(&name) {
+        edits.push(Edit::UpdateIngredientCurrency {
+            ingredient_name: name,
+            before: Some(*currency),
+            after: None,
+        });
+    }
+    edits
+}
+
+/// Edit turning the waste entries of `plate` to a deleted item.
+fn plate_records_removal(catalog: &Catalog, plate: &Rc<RefCell<Plate>>) -> Vec<Edit> {
+    let entries = positioned(
+        &catalog.waste_log.entries,
+        |entry| matches!(&entry.loss.item, WastedItem::Plate(wasted) if Rc::ptr_eq(wasted, plate)),
+    );
+    if entries.is_empty() {
+        Vec::new()
+    } else {
+        vec![Edit::DetachWaste { entries }]
+    }
+}
+
+fn set_allergens(catalog: &mut Catalog, ingredient_name: &str, allergens: &BTreeSet<Allergen>) {
+    if allergens.is_empty() {
+        catalog.allergens.remove(ingredient_name);
+    } else {
+        catalog
+            .allergens
+            .insert(ingredient_name.to_owned(), allergens.clone());
+    }
+}
+
+fn set_ingredient_currency(
+    catalog: &mut Catalog,
+    ingredient_name: &str,
+    currency: Option<Currency>,
+) {
+    match currency {
+        Some(currency) => {
+            catalog
+                .ingredient_currencies
+                .insert(ingredient_name.to_owned(), currency);
+        }
+        None => {
+            catalog.ingredient_currencies.remove(ingredient_name);
+        }
+    }
+}
+
+fn set_method(catalog: &mut Catalog, plate_name: &str, method: &RecipeMethod) {
+    if method.is_empty() {
+        catalog.recipe_methods.remove(plate_name);
+    } else {
+        catalog
+            .recipe_methods
+            .insert(plate_name.to_owned(), method.clone());
+    }
+}
+
+impl Edit {
+    /// Appends `ingredient` to the catalog.
+    pub fn add_ingredient(catalog: &Catalog, ingredient: Ingredient) -> Self {
+        Self::AddIngredient {
+            ingredient: Rc::new(RefCell::new(ingredient)),
+            index: catalog.ingredients.len(),
+        }
+    }
+
+    /// Removes `ingredient`, every recipe component using it, and its stock lots, allergens
+    /// and currency. Its waste entries are kept.
+    pub fn remove_ingredient(catalog: &Catalog, ingredient: &Rc<RefCell<Ingredient>>) -> Self {
+        let mut edits = vec![Self::RemoveIngredient {
+            ingredient: ingredient.clone(),
+            index: catalog.ingredient_index(ingredient).unwrap_or_default(),
+            usages: usages(catalog, |component| {
+                matches!(component, PlateComponent::Ingredient { ingredient: used, .. }
+                    if Rc::ptr_eq(used, ingredient))
+            }),
+        }];
+        edits.extend(ingredient_records_removal(catalog, ingredient));
+        Self::Group {
+            description: trf("Suppression de {}", &[&ingredient.borrow().name]),
+            edits,
+        }
+    }
+
+    /// Appends `plate` to the catalog.
+    pub fn add_plate(catalog: &Catalog, plate: Plate) -> Self {
+        Self::AddPlate {
+            plate: Rc::new(RefCell::new(plate)),
+            index: catalog.plates.len(),
+        }
+    }
+
+    /// Removes `plate`, its method and every recipe component using it as a sub-recipe. Its
+    /// waste entries are kept.
+    pub fn remove_plate(catalog: &Catalog, plate: &Rc<RefCell<Plate>>) -> Self {
+        let mut edits = vec![Self::RemovePlate {
+            plate: plate.clone(),
+            index: catalog.plate_index(plate).unwrap_or_default(),
+            usages: usages(catalog, |component| {
+                matches!(component, PlateComponent::Plate { plate: used, .. }
+                    if Rc::ptr_eq(used, plate))
+            }),
+            method: catalog.recipe_methods.get(&plate.borrow().name).cloned(),
+        }];
+        edits.extend(plate_records_removal(catalog, plate));
+        Self::Group {
+            description: trf("Suppression de {}", &[&plate.borrow().name]),
+            edits,
+        }
+    }
+
+    /// Records `loss` valued at the current cost, taking wasted ingredients out of stock,
+    /// oldest lots first.
+    ///
+    /// Returns `None` if the loss unit cannot be converted or the item was deleted.
+    pub fn record_waste(catalog: &Catalog, loss: Loss) -> Option<Self> {
+        let consumed = loss
+            .stock_used()
+            .map(|(ingredient, quantity)| catalog.inventory.consumption(ingredient, quantity))
+            .unwrap_or_default();
+        let entry = WasteEntry::of(loss, &catalog.valuation())?;
+        Some(Self::RecordWaste {
+            index: catalog.waste_log.entries.len(),
+            entry,
+            consumed,
+        })
+    }
+
+    /// Points every recipe using `ingredient` to `replacement`, then removes `ingredient`.
+    pub fn replace_ingredient(
+        catalog: &Catalog,
+        ingredient: &Rc<RefCell<Ingredient>>,
+        replacement: &Rc<RefCell<Ingredient>>,
+    ) -> Self {
+        let substitution = Substitution {
+            ingredient: ingredient.clone(),
+            replacement: replacement.clone(),
+            factor: 1.0,
+        };
+        let mut edit = Self::substitute_ingredient(catalog, &substitution, true);
+        if let Self::Group { description, .. } = &mut edit {
+            *description = trf(
+                "Remplacement de {} par {}",
+                &[&ingredient.borrow().name, &replacement.borrow().name],
+            );
+        }
+        edit
+    }
+
+    /// Applies `substitution` to every recipe, optionally removing the substituted ingredient.
+    pub fn substitute_ingredient(
+        catalog: &Catalog,
+        substitution: &Substitution,
+        remove_ingredient: bool,
+    ) -> Self {
+        let mut edits = replace_components(
+            catalog,
+            |component| substitution.substitute(component).is_some(),
+            |component| {
+                substitution
+                    .substitute(component)
+                    .unwrap_or_else(|| copy_component(component))
+            },
+        );
+        if remove_ingredient {
+            edits.push(Self::RemoveIngredient {
+                ingredient: substitution.ingredient.clone(),
+                index: catalog
+                    .ingredient_index(&substitution.ingredient)
+                    .unwrap_or_default(),
+                usages: Vec::new(),
+            });
+            edits.extend(ingredient_records_removal(
+                catalog,
+                &substitution.ingredient,
+            ));
+        }
+        Self::Group {
+            description: trf(
+                "Substitution de {} par {}",
+                &[
+                    &substitution.ingredient.borrow().name,
+                    &substitution.replacement.borrow().name,
+                ],
+            ),
+            edits,
+        }
+    }
+
+    /// Replaces the fields of `plate` by those of `after`, keeping its method when it is
+    /// renamed and converting the quantities of the recipes using it when its batch unit
+    /// changes to a convertible one.
+    pub fn update_plate(catalog: &Catalog, plate: &Rc<RefCell<Plate>>, after: Plate) -> Self {
+        let before = copy_plate(&plate.borrow());
+        let description = trf("Modification de {}", &[&before.name]);
+        let mut edits = Vec::new();
+        if before.name != after.name {
+            if let Some(method) = catalog.recipe_methods.get(&before.name) {
+                edits.push(Self::UpdateMethod {
+                    plate_name: before.name.clone(),
+                    before: method.clone(),
+                    after: RecipeMethod::default(),
+                });
+                edits.push(Self::UpdateMethod {
+                    plate_name: after.name.clone(),
+                    before: RecipeMethod::default(),
+                    after: method.clone(),
+                });
+            }
+        }
+        if let Some(factor) = units::convert(1.0, &before.batch_unit, &after.batch_unit) {
+            let uses = |component: &PlateComponent| matches!(component, PlateComponent::Plate { plate: used, .. } if Rc::ptr_eq(used, plate));
+            edits.extend(replace_components(
+                catalog,
+                uses,
+                |component| match component {
+                    PlateComponent::Plate { plate, quantity } => PlateComponent::Plate {
+                        plate: plate.clone(),
+                        quantity: quantity * factor,
+                    },
+                    PlateComponent::Ingredient { .. } => copy_component(component),
+                },
+            ));
+        }
+        edits.push(Self::UpdatePlate {
+            plate: plate.clone(),
+            before,
+            after,
+        });
+        Self::Group { description, edits }
+    }
+
+    /// Appends a copy of `plate` and of its method, named after it.
+    pub fn duplicate_plate(catalog: &Catalog, plate: &Rc<RefCell<Plate>>) -> Self {
+        let mut copy = copy_plate(&plate.borrow());
+        let original_name = copy.name.clone();
+        copy.name = (1..)
+            .map(|number| match number {
+                1 => trf("{} (copie)", &[&original_name]),
+                _ => trf("{} (copie {})", &[&original_name, &number]),
+            })
+            .find(|name| !catalog.has_plate_named(name))
+            .unwrap_or_default();
+
+        let mut edits = Vec::new();
+        if let Some(method) = catalog.recipe_methods.get(&original_name) {
+            edits.push(Self::UpdateMethod {
+                plate_name: copy.name.clone(),
+                before: RecipeMethod::default(),
+                after: method.clone(),
+            });
+        }
+        edits.push(Self::add_plate(catalog, copy));
+        Self::Group {
+            description: trf("Duplication de {}", &[&original_name]),
+            edits,
+        }
+    }
+
+    /// Points every recipe using `plate` as a sub-recipe to `replacement`, then removes `plate`.
+    pub fn replace_plate(
+        catalog: &Catalog,
+        plate: &Rc<RefCell<Plate>>,
+        replacement: &Rc<RefCell<Plate>>,
+    ) -> Self {
+        let uses = |component: &PlateComponent| matches!(component, PlateComponent::Plate { plate: used, .. } if Rc::ptr_eq(used, plate));
+        let mut edits = replace_components(catalog, uses, |component| match component {
+            PlateComponent::Plate { quantity, .. } => PlateComponent::Plate {
+                plate: replacement.clone(),
+                quantity: *quantity,
+            },
+            PlateComponent::Ingredient { .. } => copy_component(component),
+        });
+        edits.push(Self::RemovePlate {
+            plate: plate.clone(),
+            index: catalog.plate_index(plate).unwrap_or_default(),
+            usages: Vec::new(),
+            method: catalog.recipe_methods.get(&plate.borrow().name).cloned(),
+        });
+        edits.extend(plate_records_removal(catalog, plate));
+        Self::Group {
+            description: trf(
+                "Remplacement de {} par {}",
+                &[&plate.borrow().name, &replacement.borrow().name],
+            ),
+            edits,
+        }
+    }
+
+    /// Replaces the fields of `ingredient` by those of `after`, keeping its allergens and
+    /// currency when it is renamed, and converts the recipe quantities that `unit_change`
+    /// cannot convert.
+    pub fn update_ingredient(
+        catalog: &Catalog,
+        ingredient: &Rc<RefCell<Ingredient>>,
+        after: Ingredient,
+        unit_change: Option<&UnitChange>,
+    ) -> Self {
+        let before = copy_ingredient(&ingredient.borrow());
+        let description = trf("Modification de {}", &[&before.name]);
+        let mut edits = Vec::new();
+        if before.name != after.name {
+            if let Some(allergens) = catalog.allergens.get(&before.name) {
+                edits.push(Self::UpdateAllergens {
+                    ingredient_name: before.name.clone(),
+                    before: allergens.clone(),
+                    after: BTreeSet::new(),
+                });
+                edits.push(Self::UpdateAllergens {
+                    ingredient_name: after.name.clone(),
+                    before: BTreeSet::new(),
+                    after: allergens.clone(),
+                });
+            }
+            if let Some(&currency) = catalog.ingredient_currencies.get(&before.name) {
+                edits.push(Self::UpdateIngredientCurrency {
+                    ingredient_name: before.name.clone(),
+                    before: Some(currency),
+                    after: None,
+                });
+                edits.push(Self::UpdateIngredientCurrency {
+                    ingredient_name: after.name.clone(),
+                    before: None,
+                    after: Some(currency),
+                });
+            }
+        }
+        edits.push(Self::UpdateIngredient {
+            ingredient: ingredient.clone(),
+            before,
+            after,
+        });
+        if let Some(change) = unit_change {
+            edits.extend(replace_components(
+                catalog,
+                |component| change.convert(component).is_some(),
+                |component| {
+                    change
+                        .convert(component)
+                        .unwrap_or_else(|| copy_component(component))
+                },
+            ));
+        }
+        Self::Group { description, edits }
+    }
+
+    /// Applies `update` to a copy of each of `ingredients`, as a single undo step.
+    pub fn update_ingredients(
+        ingredients: &[Rc<RefCell<Ingredient>>],
+        description: String,
+        update: impl Fn(&mut Ingredient),
+    ) -> Self {
+        let edits = ingredients
+            .iter()
+            .map(|ingredient| {
+                let before = copy_ingredient(&ingredient.borrow());
+                let mut after = copy_ingredient(&before);
+                update(&mut after);
+                Self::UpdateIngredient {
+                    ingredient: ingredient.clone(),
+                    before,
+                    after,
+                }
+            })
+            .collect();
+        Self::Group { description, edits }
+    }
+
+    /// Short description in the current language, for the Édition menu.
+    pub fn description(&self) -> String {
+        match self {
+            Self::AddIngredient { ingredient, .. } => {
+                trf("Ajout de {}", &[&ingredient.borrow().name])
+            }
+            Self::RemoveIngredient { ingredient, .. } => {
+                trf("Suppression de {}", &[&ingredient.borrow().name])
+            }
+            Self::UpdateIngredient { after, .. } => trf("Modification de {}", &[&after.name]),
+            Self::AddPlate { plate, .. } => trf("Ajout de {}", &[&plate.borrow().name]),
+            Self::RemovePlate { plate, .. } => trf("Suppression de {}", &[&plate.borrow().name]),
+            Self::UpdatePlate { after, .. } => trf("Modification de {}", &[&after.name]),
+            Self::UpdateMethod { plate_name, .. } => {
+                trf("Modification de la méthode de {}", &[plate_name])
+            }
+            Self::UpdateAllergens {
+                ingredient_name, ..
+            } => trf("Modification des allergènes de {}", &[ingredient_name]),
+            Self::UpdateIngredientCurrency {
+                ingredient_name, ..
+            } => trf("Modification de la devise de {}", &[ingredient_name]),
+            Self::UpdateSettings { .. } => tr("Modification des paramètres").to_owned(),
+            Self::UpdateExchangeRates { .. } => tr("Modification des taux de change").to_owned(),
+            Self::ReceiveLots { lots, .. } => trf("Réception de {} lot(s)", &[&lots.len()]),
+            Self::RemoveLots { lots } => trf("Suppression de {} lot(s)", &[&lots.len()]),
+            Self::DetachWaste { entries } => trf("Archivage de {} perte(s)", &[&entries.len()]),
+            Self::RecordWaste { entry, .. } => trf("Perte de {}", &[&entry.loss.item.name()]),
+            Self::Group { description, .. } => description.clone(),
+        }
+    }
+
+    fn apply(&self, catalog: &mut Catalog) {
+        match self {
+            Self::AddIngredient { ingredient, index } => {
+                let index = (*index).min(catalog.ingredients.len());
+                catalog.ingredients.insert(index, ingredient.clone());
+            }
+            Self::RemoveIngredient {
+                ingredient, usages, ..
+            } => {
+                remove_components(usages);
+                if let Some(index) = catalog.ingredient_index(ingredient) {
+                    catalog.ingredients.remove(index);
+                }
+            }
+            Self::UpdateIngredient {
+                ingredient, after, ..
+            } => *ingredient.borrow_mut() = copy_ingredient(after),
+            Self::AddPlate { plate, index } => {
+                let index = (*index).min(catalog.plates.len());
+                catalog.plates.insert(index, plate.clone());
+            }
+            Self::RemovePlate { plate, usages, .. } => {
+                remove_components(usages);
+                catalog.recipe_methods.remove(&plate.borrow().name);
+                if let Some(index) = catalog.plate_index(plate) {
+                    catalog.plates.remove(index);
+                }
+            }
+            Self::UpdatePlate { plate, after, .. } => *plate.borrow_mut() = copy_plate(after),
+            Self::UpdateMethod {
+                plate_name, after, ..
+            } => set_method(catalog, plate_name, after),
+            Self::UpdateAllergens {
+                ingredient_name,
+                after,
+                ..
+            } => set_allergens(catalog, ingredient_name, after),
+            Self::UpdateIngredientCurrency {
+                ingredient_name,
+                after,
+                ..
+            } => set_ingredient_currency(catalog, ingredient_name, *after),
+            Self::UpdateSettings { after, .. } => catalog.settings = *after,
+            Self::UpdateExchangeRates { after, .. } => catalog.exchange_rates.clone_from(after),
+            Self::ReceiveLots { index, lots } => {
+                let lots_in_stock = &mut catalog.inventory.lots;
+                let index = (*index).min(lots_in_stock.len());
+                lots_in_stock.splice(index..index, lots.iter().cloned());
+            }
+            Self::RemoveLots { lots } => remove_positioned(&mut catalog.inventory.lots, lots),
+            Self::RecordWaste {
+                index,
+                entry,
+                consumed,
+            } => {
+                let entries = &mut catalog.waste_log.entries;
+                entries.insert((*index).min(entries.len()), entry.clone());
+                for (lot, quantity) in consumed {
+                    if let Some(lot) = catalog.inventory.lots.get_mut(*lot) {
+                        lot.remaining_quantity -= quantity;
+                    }
+                }
+            }
+            Self::DetachWaste { entries } => {
+                for (index, entry) in entries {
+                    if let Some(kept) = catalog.waste_log.entries.get_mut(*index) {
+                        kept.loss.item = entry.loss.item.deleted();
+                    }
+                }
+            }
+            Self::Group { edits, .. } => {
+                for edit in edits {
+                    edit.apply(catalog);
+                }
+            }
+        }
+    }
+
+    fn revert(&self, catalog: &mut Catalog) {
+        match self {
+            Self::AddIngredient { ingredient, .. } => {
+                if let Some(index) = catalog.ingredient_index(ingredient) {
+                    catalog.ingredients.remove(index);
+                }
+            }
+            Self::RemoveIngredient {
+                ingredient,
+                index,
+                usages,
+            } => {
+                let index = (*index).min(catalog.ingredients.len());
+                catalog.ingredients.insert(index, ingredient.clone());
+                restore_components(usages);
+            }
+            Self::UpdateIngredient {
+                ingredient, before, ..
+            } => *ingredient.borrow_mut() = copy_ingredient(before),
+            Self::AddPlate { plate, .. } => {
+                if let Some(index) = catalog.plate_index(plate) {
+                    catalog.plates.remove(index);
+                }
+            }
+            Self::RemovePlate {
+                plate,
+                index,
+                usages,
+                method,
+            } => {
+                let index = (*index).min(catalog.plates.len());
+                catalog.plates.insert(index, plate.clone());
+                restore_components(usages);
+                if let Some(method) = method {
+                    catalog
+                        .recipe_methods
+                        .insert(plate.borrow().name.clone(), method.clone());
+                }
+            }
+            Self::UpdatePlate { plate, before, .. } => *plate.borrow_mut() = copy_plate(before),
+            Self::UpdateMethod {
+                plate_name, before, ..
+            } => set_method(catalog, plate_name, before),
+            Self::UpdateAllergens {
+                ingredient_name,
+                before,
+                ..
+            } => set_allergens(catalog, ingredient_name, before),
+            Self::UpdateIngredientCurrency {
+                ingredient_name,
+                before,
+                ..
+            } => set_ingredient_currency(catalog, ingredient_name, *before),
+            Self::UpdateSettings { before, .. } => catalog.settings = *before,
+            Self::UpdateExchangeRates { before, .. } => catalog.exchange_rates.clone_from(before),
+            Self::ReceiveLots { index, lots } => {
+                // Lots are only ever appended, so the delivery's are still where it put them
+                let lots_in_stock = &mut catalog.inventory.lots;
+                let end = (index + lots.len()).min(lots_in_stock.len());
+                lots_in_stock.drain((*index).min(end)..end);
+            }
+            Self::RemoveLots { lots } => restore_positioned(&mut catalog.inventory.lots, lots),
+            Self::RecordWaste {
+                index, consumed, ..
+            } => {
+                let entries = &mut catalog.waste_log.entries;
+                if *index < entries.len() {
+                    entries.remove(*index);
+                }
+                for (lot, quantity) in consumed {
+                    if let Some(lot) = catalog.inventory.lots.get_mut(*lot) {
+                        lot.remaining_quantity += quantity;
+                    }
+                }
+            }
+            Self::DetachWaste { entries } => {
+                for (index, entry) in entries {
+                    if let Some(kept) = catalog.waste_log.entries.get_mut(*index) {
+                        *kept = entry.clone();
+                    }
+                }
+            }
+            Self::Group { edits, .. } => {
+                for edit in edits.iter().rev() {
+                    edit.revert(catalog);
+                }
+            }
+        }
+    }
+
+    /// Folds `next` into `self` when both update the same thing, keeping the first `before`.
+    fn merge(&mut self, next: &Self) -> bool {
+        match (self, next) {
+            (
+                Self::UpdateIngredient {
+                    ingredient, after, ..
+                },
+                Self::UpdateIngredient {
+                    ingredient: next_ingredient,
+                    after: next_after,
+                    ..
+                },
+            ) if Rc::ptr_eq(ingredient, next_ingredient) => {
+                *after = copy_ingredient(next_after);
+                true
+            }
+            (
+                Self::UpdatePlate { plate, after, .. },
+                Self::UpdatePlate {
+                    plate: next_plate,
+                    after: next_after,
+                    ..
+                },
+            ) if Rc::ptr_eq(plate, next_plate) => {
+                *after = copy_plate(next_after);
+                true
+            }
+            (
+                Self::UpdateMethod {
+                    plate_name, after, ..
+                },
+                Self::UpdateMethod {
+                    plate_name: next_name,
+                    after: next_after,
+                    ..
+                },
+            ) if plate_name == next_name => {
+                after.clone_from(next_after);
+                true
+            }
+            (
+                Self::UpdateSettings { after, .. },
+                Self::UpdateSettings {
+                    after: next_after, ..
+                },
+            ) => {
+                *after = *next_after;
+                true
+            }
+            (
+                Self::UpdateExchangeRates { after, .. },
+                Self::UpdateExchangeRates {
+                    after: next_after, ..
+                },
+            ) => {
+                after.clone_from(next_after);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Undo and redo stacks of catalog edits.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+
+    /// Whether the next edit starts a new undo step rather than extending the last one.
+    sealed: bool,
+
+    /// Number of changes to the catalog so far, undos and redos included.
+    changes: u64,
+}
+
+impl History {
+    /// Applies `edit` to the catalog and records it as its own undo step.
+    pub fn apply(&mut self, catalog: &mut Catalog, edit: Edit) {
+        edit.apply(catalog);
+        self.undo.push(edit);
+        self.redo.clear();
+        self.sealed = true;
+        self.changes += 1;
+    }
+
+    /// Records an edit already made in place, such as a widget changing a value.
+    ///
+    /// Successive updates of the same item are merged until [`Self::seal`] is called,
+    /// so dragging a value is undone in one step.
+    pub fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        self.changes += 1;
+        if !self.sealed {
+            if let Some(last) = self.undo.last_mut() {
+                if last.merge(&edit) {
+                    return;
+                }
+            }
+        }
+        self.undo.push(edit);
+        self.sealed = false;
+    }
+
+    /// Ends the current undo step; call it when the user stops interacting with a widget.
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn undo(&mut self, catalog: &mut Catalog) {
+        if let Some(edit) = self.undo.pop() {
+            edit.revert(catalog);
+            self.redo.push(edit);
+            self.changes += 1;
+        }
+        self.sealed = true;
+    }
+
+    pub fn redo(&mut self, catalog: &mut Catalog) {
+        if let Some(edit) = self.redo.pop() {
+            edit.apply(catalog);
+            self.undo.push(edit);
+            self.changes += 1;
+        }
+        self.sealed = true;
+    }
+
+    /// Description of the edit [`Self::undo`] would revert.
+    pub fn undo_description(&self) -> Option<String> {
+        self.undo.last().map(Edit::description)
+    }
+
+    /// Description of the edit [`Self::redo`] would apply again.
+    pub fn redo_description(&self) -> Option<String> {
+        self.redo.last().map(Edit::description)
+    }
+
+    /// Number of changes so far; it differs from an earlier value once the catalog changed.
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::CatalogFile;
+    use crate::waste::WasteReason;
+    use chrono::NaiveDate;
+    use poc_fnb::{IngredientType, QuantityUnit, Supplier};
+
+    fn catalog_with_plate() -> (Catalog, Rc<RefCell<Plate>>) {
+        let plate = Rc::new(RefCell::new(Plate {
+            name: "Pizza".to_owned(),
+            components: Vec::new(),
+            selling_price: 10.0,
+            batch_preparation_time_hours: 0.0,
+            batch_quantity: 1.0,
+            batch_unit: QuantityUnit::Piece,
+        }));
+        let catalog = Catalog {
+            plates: vec![plate.clone()],
+            ..Default::default()
+        };
+        (catalog, plate)
+    }
+
+    /// Sets the selling price in place and records it, as a widget does.
+    fn set_price(history: &mut History, plate: &Rc<RefCell<Plate>>, price: f64) {
+        let before = copy_plate(&plate.borrow());
+        plate.borrow_mut().selling_price = price;
+        history.record(Edit::UpdatePlate {
+            plate: plate.clone(),
+            before,
+            after: copy_plate(&plate.borrow()),
+        });
+    }
+
+    #[test]
+    fn records_until_sealed_are_undone_in_one_step() {
+        let (mut catalog, plate) = catalog_with_plate();
+        let mut history = History::default();
+        set_price(&mut history, &plate, 11.0);
+        set_price(&mut history, &plate, 12.0);
+
+        history.undo(&mut catalog);
+        assert_eq!(plate.borrow().selling_price, 10.0);
+        assert!(history.undo_description().is_none());
+
+        history.redo(&mut catalog);
+        assert_eq!(plate.borrow().selling_price, 12.0);
+        assert!(history.redo_description().is_none());
+    }
+
+    #[test]
+    fn sealing_starts_a_new_step() {
+        let (mut catalog, plate) = catalog_with_plate();
+        let mut history = History::default();
+        set_price(&mut history, &plate, 11.0);
+        history.seal();
+        set_price(&mut history, &plate, 12.0);
+
+        history.undo(&mut catalog);
+        assert_eq!(plate.borrow().selling_price, 11.0);
+        history.undo(&mut catalog);
+        assert_eq!(plate.borrow().selling_price, 10.0);
+    }
+
+    #[test]
+    fn recording_clears_the_redo_stack() {
+        let (mut catalog, plate) = catalog_with_plate();
+        let mut history = History::default();
+        set_price(&mut history, &plate, 11.0);
+        history.undo(&mut catalog);
+        set_price(&mut history, &plate, 13.0);
+
+        assert!(history.redo_description().is_none());
+        history.undo(&mut catalog);
+        assert_eq!(plate.borrow().selling_price, 10.0);
+    }
+
+    fn catalog_with_milk() -> (Catalog, Rc<RefCell<Ingredient>>) {
+        let milk = Rc::new(RefCell::new(Ingredient {
+            name: "Lait".to_owned(),
+            cost_price: 1.2,
+            reference_quantity: 1.0,
+            unit: QuantityUnit::Liter,
+            ingredient_type: IngredientType::DairyEggsCheeseSauces,
+            supplier: Supplier {
+                name: "Laiterie".to_owned(),
+                contact: None,
+            },
+        }));
+        let catalog = Catalog {
+            ingredients: vec![milk.clone()],
+            ..Default::default()
+        };
+        (catalog, milk)
+    }
+
+    fn milk_lot(milk: &Rc<RefCell<Ingredient>>, day: u32, remaining_quantity: f64) -> StockLot {
+        StockLot {
+            ingredient: milk.clone(),
+            supplier: "Laiterie".to_owned(),
+            lot_number: format!("L{day}"),
+            received_on: NaiveDate::from_ymd_opt(2024, 3, day).expect("valid date"),
+            use_by: None,
+            unit_cost: 1.2,
+            received_quantity: 10.0,
+            remaining_quantity,
+        }
+    }
+
+    fn milk_loss(milk: &Rc<RefCell<Ingredient>>, quantity: f64) -> Loss {
+        Loss {
+            item: WastedItem::Ingredient(milk.clone()),
+            quantity,
+            unit: QuantityUnit::Liter,
+            reason: WasteReason::Spoiled,
+            date: NaiveDate::from_ymd_opt(2024, 3, 10).expect("valid date"),
+        }
+    }
+
+    fn remaining(catalog: &Catalog) -> Vec<f64> {
+        let lots = &catalog.inventory.lots;
+        lots.iter().map(|lot| lot.remaining_quantity).collect()
+    }
+
+    #[test]
+    fn removing_an_ingredient_takes_its_records_with_it() {
+        let (mut catalog, milk) = catalog_with_milk();
+        catalog.inventory.lots.push(milk_lot(&milk, 1, 8.0));
+        catalog.waste_log.entries.push(WasteEntry {
+            loss: milk_loss(&milk, 2.0),
+            value: 2.4,
+            value_by_type: Vec::new(),
+        });
+        catalog
+            .allergens
+            .insert("Lait".to_owned(), BTreeSet::from([Allergen::Milk]));
+        catalog
+            .ingredient_currencies
+            .insert("Lait".to_owned(), Currency::Chf);
+
+        let mut history = History::default();
+        let removal = Edit::remove_ingredient(&catalog, &milk);
+        history.apply(&mut catalog, removal);
+        assert!(catalog.inventory.lots.is_empty());
+        assert!(catalog.allergens.is_empty());
+        assert!(catalog.ingredient_currencies.is_empty());
+        let reloaded = Catalog::try_from(&CatalogFile::from(&catalog)).expect("valid catalog");
+        let [entry] = reloaded.waste_log.entries.as_slice() else {
+            panic!("the waste entry should outlive its ingredient");
+        };
+        assert!(
+            matches!(&entry.loss.item, WastedItem::Deleted { name, is_plate: false } if name == "Lait")
+        );
+
+        history.undo(&mut catalog);
+        assert_eq!(catalog.ingredients.len(), 1);
+        assert_eq!(catalog.inventory.lots.len(), 1);
+        assert!(
+            matches!(&catalog.waste_log.entries[0].loss.item, WastedItem::Ingredient(wasted) if Rc::ptr_eq(wasted, &milk))
+        );
+        assert_eq!(
+            catalog.ingredient_currencies.get("Lait"),
+            Some(&Currency::Chf)
+        );
+        assert!(Catalog::try_from(&CatalogFile::from(&catalog)).is_ok());
+    }
+
+    #[test]
+    fn recorded_waste_gives_its_stock_back_when_undone() {
+        let (mut catalog, milk) = catalog_with_milk();
+        catalog.inventory.lots.push(milk_lot(&milk, 2, 5.0));
+        catalog.inventory.lots.push(milk_lot(&milk, 1, 3.0));
+        let mut history = History::default();
+
+        let waste = Edit::record_waste(&catalog, milk_loss(&milk, 4.0)).expect("convertible unit");
+        history.apply(&mut catalog, waste);
+        assert_eq!(remaining(&catalog), [4.0, 0.0]);
+        assert_eq!(catalog.waste_log.entries.len(), 1);
+
+        history.undo(&mut catalog);
+        assert_eq!(remaining(&catalog), [5.0, 3.0]);
+        assert!(catalog.waste_log.entries.is_empty());
+
+        history.redo(&mut catalog);
+        assert_eq!(remaining(&catalog), [4.0, 0.0]);
+        assert_eq!(catalog.waste_log.entries.len(), 1);
+    }
+
+    #[test]
+    fn redone_delivery_keeps_the_lots_as_received() {
+        let (mut catalog, milk) = catalog_with_milk();
+        let mut history = History::default();
+        history.apply(
+            &mut catalog,
+            Edit::ReceiveLots {
+                index: 0,
+                lots: vec![milk_lot(&milk, 1, 10.0)],
+            },
+        );
+        let waste = Edit::record_waste(&catalog, milk_loss(&milk, 4.0)).expect("convertible unit");
+        history.apply(&mut catalog, waste);
+
+        history.undo(&mut catalog);
+        history.undo(&mut catalog);
+        history.redo(&mut catalog);
+        assert_eq!(remaining(&catalog), [10.0]);
+        history.redo(&mut catalog);
+        assert_eq!(remaining(&catalog), [6.0]);
+    }
+}
@@ -0,0 +1,84 @@
+//! Translation of the interface.
+//!
+//! French is the source language: texts are written in French in the code and looked up,
+//! as is, in the messages of the current language. A text missing from these messages is
+//! shown in French. Adding a language takes a variant of `Language` and a module holding its
+//! messages, like `en`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+mod en;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::French, Self::English];
+
+    /// Name of the language in itself, for the language selector.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::French => "Français",
+            Self::English => "English",
+        }
+    }
+
+    /// Translations of the French texts, `None` for French itself.
+    fn messages(self) -> Option<&'static HashMap<&'static str, &'static str>> {
+        static ENGLISH: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+        match self {
+            Self::French => None,
+            Self::English => Some(ENGLISH.get_or_init(|| en::MESSAGES.iter().copied().collect())),
+        }
+    }
+}
+
+/// Index in `Language::ALL` of the current language.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn language() -> Language {
+    Language::ALL
+        .get(usize::from(CURRENT.load(Ordering::Relaxed)))
+        .copied()
+        .unwrap_or_default()
+}
+
+pub fn set_language(language: Language) {
+    let idx = Language::ALL
+        .iter()
+        .position(|&candidate| candidate == language)
+        .unwrap_or_default();
+    CURRENT.store(u8::try_from(idx).unwrap_or_default(), Ordering::Relaxed);
+}
+
+/// `text` in the current language.
+pub fn tr(text: &'static str) -> &'static str {
+    language()
+        .messages()
+        .and_then(|messages| messages.get(text).copied())
+        .unwrap_or(text)
+}
+
+/// `template` in the current language, its `{}` replaced by `args` in order.
+pub fn trf(template: &'static str, args: &[&dyn Display]) -> String {
+    let mut text = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(template).split("{}");
+    if let Some(first) = parts.next() {
+        text.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
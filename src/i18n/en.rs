@@ -0,0 +1,577 @@
+//! English messages, by French source text.
+
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // Menus
+    ("Fichier", "File"),
+    ("Nouveau", "New"),
+    ("Ouvrir...", "Open..."),
+    ("Fichiers récents", "Recent Files"),
+    ("Effacer la liste", "Clear List"),
+    ("Enregistrer sous...", "Save As..."),
+    ("Quitter", "Quit"),
+    ("Édition", "Edit"),
+    ("Annuler : {}", "Undo: {}"),
+    ("Rien à annuler", "Nothing to undo"),
+    ("Rétablir : {}", "Redo: {}"),
+    ("Rien à rétablir", "Nothing to redo"),
+    ("Substituer un Ingrédient…", "Substitute an Ingredient…"),
+    ("Tableau des Ingrédients", "Ingredient Table"),
+    ("Coûts", "Costs"),
+    ("Devise", "Currency"),
+    ("Taux de Change", "Exchange Rates"),
+    ("Méthode de Valorisation", "Costing Method"),
+    ("Graphiques des Coûts", "Cost Charts"),
+    ("Production", "Production"),
+    ("Plan de Production", "Production Plan"),
+    ("Stock", "Stock"),
+    ("Réception de Marchandises", "Goods Receiving"),
+    ("Pertes et Gaspillage", "Losses and Waste"),
+    (
+        "Gestion des Coûts F&B propulsé par ",
+        "F&B Cost Management powered by ",
+    ),
+    // Ingredients panel
+    ("Ingrédients", "Ingredients"),
+    ("Ajouter un Nouvel Ingrédient", "Add a New Ingredient"),
+    ("Nom :", "Name:"),
+    ("Prix :", "Price:"),
+    ("Quantité Réf. :", "Ref. Quantity:"),
+    ("Unité :", "Unit:"),
+    ("Type :", "Type:"),
+    ("⚠ aucun taux {} → {}", "⚠ no {} → {} rate"),
+    ("Ajouter Ingrédient", "Add Ingredient"),
+    ("Par Défaut", "Default"),
+    ("Modifier", "Edit"),
+    (
+        "Substituer dans toutes les recettes",
+        "Substitute in every recipe",
+    ),
+    ("Prix de Revient :", "Cost Price:"),
+    ("Quantité de Référence :", "Reference Quantity:"),
+    ("Stock : {} {}", "Stock: {} {}"),
+    ("Fournisseur : {}", "Supplier: {}"),
+    ("Type : {}", "Type: {}"),
+    ("Allergènes ({})", "Allergens ({})"),
+    // Plates panel
+    ("Plats (Recettes)", "Plates (Recipes)"),
+    ("Échec de l'export : {}", "Export failed: {}"),
+    ("Photo illisible : {}", "Unreadable photo: {}"),
+    ("Erreur de fichier : {}", "File error: {}"),
+    (
+        "Catalogue enregistré illisible, il ne sera pas remplacé : {}",
+        "Unreadable saved catalog, it will not be replaced: {}",
+    ),
+    (
+        "Les modifications ne sont pas enregistrées automatiquement ; utilisez Fichier › Enregistrer sous pour les garder",
+        "Changes are not saved automatically; use File › Save As to keep them",
+    ),
+    ("➕ Ajouter une Nouvelle Recette", "➕ Add a New Recipe"),
+    ("Créer une Nouvelle Recette", "Create a New Recipe"),
+    ("Prix de Vente :", "Selling Price:"),
+    ("Temps de Préparation (h) :", "Preparation Time (h):"),
+    ("Quantité par Lot :", "Batch Quantity:"),
+    ("Unité du Lot :", "Batch Unit:"),
+    ("Créer la Recette", "Create Recipe"),
+    (
+        "Marge par rapport aux seuils d'alerte",
+        "Margin against the alert thresholds",
+    ),
+    (
+        "Exporter la fiche technique (PDF)",
+        "Export the recipe card (PDF)",
+    ),
+    ("Dupliquer la recette", "Duplicate the recipe"),
+    ("Coût Ingrédients :", "Ingredient Cost:"),
+    ("Coût Main-d'œuvre :", "Labor Cost:"),
+    ("Coût Total :", "Total Cost:"),
+    ("Marge Brute :", "Gross Margin:"),
+    ("Taux de Marge :", "Margin Rate:"),
+    ("Composants", "Components"),
+    ("Ajouter un ingrédient :", "Add an ingredient:"),
+    ("Sélectionner un ingrédient...", "Select an ingredient..."),
+    ("Ajouter une sous-recette :", "Add a sub-recipe:"),
+    ("Sélectionner une recette...", "Select a recipe..."),
+    (
+        "Unité incompatible avec l'unité d'achat ({}) : quantité comptée comme des {}",
+        "Unit incompatible with the purchase unit ({}): quantity counted as {}",
+    ),
+    ("Arbre des Coûts", "Cost Tree"),
+    ("Méthode", "Method"),
+    ("Coût du lot : {} ({} {})", "Batch cost: {} ({} {})"),
+    // Alerts
+    ("✔ Aucune alerte", "✔ No alerts"),
+    ("⚠ Alertes ({})", "⚠ Alerts ({})"),
+    ("Marge minimum :", "Minimum margin:"),
+    ("Food cost maximum :", "Maximum food cost:"),
+    (
+        "{} : marge de {} sous le minimum de {}",
+        "{}: {} margin below the {} minimum",
+    ),
+    (
+        "{} : food cost de {} au-dessus du maximum de {}",
+        "{}: {} food cost above the {} maximum",
+    ),
+    (
+        "{} : recette sans composant",
+        "{}: recipe has no components",
+    ),
+    (
+        "{} : ni prix de vente, ni utilisée comme sous-recette",
+        "{}: no selling price and not used as a sub-recipe",
+    ),
+    (
+        "{} : quantité de {} dans une unité incompatible avec son unité d'achat",
+        "{}: quantity of {} in a unit incompatible with its purchase unit",
+    ),
+    ("{} : prix nul", "{}: price is zero"),
+    (
+        "{} : recette contenue dans elle-même ({})",
+        "{}: recipe contains itself ({})",
+    ),
+    (
+        "{} : aucun taux de change {} → {} en vigueur",
+        "{}: no {} → {} exchange rate in effect",
+    ),
+    // Cost charts
+    ("Aucune donnée.", "No data."),
+    ("Recette :", "Recipe:"),
+    ("Sélectionner...", "Select..."),
+    ("Coût du lot par composant", "Batch cost by component"),
+    (
+        "Coût ingrédients par catégorie",
+        "Ingredient cost by category",
+    ),
+    ("Food cost par plat", "Food cost by plate"),
+    // Exchange rates
+    (
+        "Valeur d'une unité de la première devise dans la seconde, à partir de la date indiquée.",
+        "Value of one unit of the first currency in the second, from the given date.",
+    ),
+    ("À partir du", "Effective from"),
+    ("De", "From"),
+    ("Vers", "To"),
+    ("Taux", "Rate"),
+    ("➕ Ajouter le Taux", "➕ Add Rate"),
+    (
+        "Les deux devises doivent être différentes",
+        "The two currencies must be different",
+    ),
+    ("Le taux doit être positif", "The rate must be positive"),
+    // Deletion
+    ("Supprimer {} ?", "Delete {}?"),
+    ("Utilisé par {} recette(s) :", "Used by {} recipe(s):"),
+    ("Retirer des recettes", "Remove from the recipes"),
+    (
+        "Les recettes passant par une sous-recette changent avec elle",
+        "Recipes using it through a sub-recipe change with it",
+    ),
+    ("Remplacer par :", "Replace with:"),
+    ("🗑 Supprimer", "🗑 Delete"),
+    // Ingredient editor
+    ("Modifier {}", "Edit {}"),
+    ("Devise du fournisseur :", "Supplier currency:"),
+    ("Le nom est obligatoire", "The name is required"),
+    (
+        "Un autre ingrédient porte déjà ce nom",
+        "Another ingredient already has this name",
+    ),
+    (
+        "Un ingrédient porte déjà ce nom",
+        "An ingredient already has this name",
+    ),
+    (
+        "Une recette porte déjà ce nom",
+        "A recipe already has this name",
+    ),
+    (
+        "La quantité de référence doit être positive",
+        "The reference quantity must be positive",
+    ),
+    ("Le fournisseur est obligatoire", "The supplier is required"),
+    (
+        "Le facteur de conversion doit être positif",
+        "The conversion factor must be positive",
+    ),
+    ("Enregistrer", "Save"),
+    ("Annuler", "Cancel"),
+    ("Fournisseur :", "Supplier:"),
+    ("Contact Fournisseur :", "Supplier Contact:"),
+    ("Changement d'unité", "Unit change"),
+    (
+        "Quantité de référence équivalente : {} {}",
+        "Equivalent reference quantity: {} {}",
+    ),
+    ("Utiliser", "Use"),
+    (
+        "Le prix couvre alors la même quantité qu'avant",
+        "The price then covers the same quantity as before",
+    ),
+    (
+        "⚠ {} quantité(s) de recette ne peuvent pas être converties en {} :",
+        "⚠ {} recipe quantity(ies) cannot be converted to {}:",
+    ),
+    (
+        "Convertir ces quantités avec le facteur ci-dessus",
+        "Convert these quantities with the factor above",
+    ),
+    (
+        "Sinon, elles restent signalées dans les recettes",
+        "Otherwise, they stay flagged in the recipes",
+    ),
+    // Ingredient table
+    ("Nom", "Name"),
+    ("Type", "Type"),
+    ("Fournisseur", "Supplier"),
+    ("Prix", "Price"),
+    ("Qté Réf.", "Ref. Qty"),
+    ("Coût Unitaire", "Unit Cost"),
+    ("Tout sélectionner", "Select all"),
+    (
+        "{} ingrédient(s) sélectionné(s)",
+        "{} ingredient(s) selected",
+    ),
+    ("Appliquer", "Apply"),
+    (
+        "Changement de type de {} ingrédient(s)",
+        "Type change of {} ingredient(s)",
+    ),
+    (
+        "Changement de fournisseur de {} ingrédient(s)",
+        "Supplier change of {} ingredient(s)",
+    ),
+    ("Hausse de prix :", "Price increase:"),
+    (
+        "Hausse de prix de {} % sur {} ingrédient(s)",
+        "{}% price increase on {} ingredient(s)",
+    ),
+    // Filters
+    ("Tous", "All"),
+    ("Ordre décroissant", "Descending order"),
+    ("Ordre croissant", "Ascending order"),
+    ("Rechercher...", "Search..."),
+    ("Allergène :", "Allergen:"),
+    ("Trier par :", "Sort by:"),
+    ("Catalogue", "Catalog"),
+    ("Coût", "Cost"),
+    ("Marge inférieure à", "Margin below"),
+    ("Sans allergène :", "Free of allergen:"),
+    ("Taux de marge", "Margin rate"),
+    ("Food cost %", "Food cost %"),
+    // Production plan
+    ("Date de Production :", "Production Date:"),
+    ("AAAA-MM-JJ", "YYYY-MM-DD"),
+    (
+        "Prévisions (couverts par plat)",
+        "Forecast (covers per plate)",
+    ),
+    ("Liste de Préparation", "Prep List"),
+    ("📋 Copier pour Impression", "📋 Copy for Printing"),
+    ("Impossible de planifier : {}", "Cannot plan: {}"),
+    ("LISTE DE PRÉPARATION — {}", "PREP LIST — {}"),
+    ("Recette", "Recipe"),
+    ("Quantité", "Quantity"),
+    ("Unité", "Unit"),
+    ("Lots", "Batches"),
+    ("Heures", "Hours"),
+    ("Main-d'œuvre totale : {} h", "Total labor: {} h"),
+    (
+        "la recette {} se contient elle-même",
+        "recipe {} contains itself",
+    ),
+    // Goods receiving
+    ("N° de Commande :", "Order No.:"),
+    (
+        "Laisser vide pour une réception sans commande",
+        "Leave empty for a delivery without an order",
+    ),
+    ("Date de Réception :", "Delivery Date:"),
+    ("Mise à jour du prix :", "Price update:"),
+    ("Dernier prix", "Last price"),
+    ("Prix moyen pondéré", "Weighted average price"),
+    ("➕ Ajouter une Ligne", "➕ Add a Line"),
+    ("Valider la Réception", "Confirm Delivery"),
+    ("Ingrédient", "Ingredient"),
+    ("Prix Unitaire", "Unit Price"),
+    ("Prix Commandé", "Ordered Price"),
+    ("N° de Lot", "Lot No."),
+    ("DLC", "Use By"),
+    ("{} ligne(s) réceptionnée(s)", "{} line(s) received"),
+    (
+        "{} : unité incompatible ou quantité nulle, ligne ignorée",
+        "{}: incompatible unit or zero quantity, line skipped",
+    ),
+    (
+        "{} (lot {}) : commandé {}, facturé {} par {}, écart {}",
+        "{} (lot {}): ordered {}, invoiced {} per {}, difference {}",
+    ),
+    ("Lots en Stock", "Lots in Stock"),
+    (
+        "{} — lot {} — {} {} — DLC {}",
+        "{} — lot {} — {} {} — use by {}",
+    ),
+    (
+        "Date de réception invalide (AAAA-MM-JJ)",
+        "Invalid delivery date (YYYY-MM-DD)",
+    ),
+    (
+        "Chaque ligne doit avoir un ingrédient",
+        "Every line needs an ingredient",
+    ),
+    ("DLC invalide : {}", "Invalid use-by date: {}"),
+    (
+        "La réception ne contient aucune ligne",
+        "The delivery has no lines",
+    ),
+    // Recipe card
+    ("Fiche Technique — {}", "Recipe Card — {}"),
+    ("Rendement par lot", "Yield per batch"),
+    ("Temps de préparation par lot", "Preparation time per batch"),
+    ("Composant", "Component"),
+    ("Nature", "Kind"),
+    ("Sous-recette (voir sa fiche)", "Sub-recipe (see its card)"),
+    ("Aucun composant.", "No components."),
+    ("Coûts par lot", "Costs per batch"),
+    ("Coût ingrédients", "Ingredient cost"),
+    ("Coût main-d'œuvre", "Labor cost"),
+    ("Coût total", "Total cost"),
+    ("Prix de vente", "Selling price"),
+    ("Marge brute", "Gross margin"),
+    ("Décomposition du coût", "Cost breakdown"),
+    ("Méthode non renseignée.", "No method given."),
+    ("Notes du Chef", "Chef's Notes"),
+    ("Photo", "Photo"),
+    ("Fiche technique - {}.pdf", "Recipe card - {}.pdf"),
+    // Recipe editor and method
+    (
+        "Une autre recette porte déjà ce nom",
+        "Another recipe already has this name",
+    ),
+    (
+        "La quantité par lot doit être positive",
+        "The batch quantity must be positive",
+    ),
+    (
+        "Le temps de préparation ne peut pas être négatif",
+        "The preparation time cannot be negative",
+    ),
+    (
+        "Le prix de vente ne peut pas être négatif",
+        "The selling price cannot be negative",
+    ),
+    ("Description de l'étape...", "Step description..."),
+    ("Durée", "Duration"),
+    ("Température", "Temperature"),
+    ("➕ Ajouter une Étape", "➕ Add a Step"),
+    ("Notes du Chef :", "Chef's Notes:"),
+    (
+        "Astuces, dressage, points de vigilance...",
+        "Tips, plating, points to watch...",
+    ),
+    ("📷 Remplacer la Photo", "📷 Replace the Photo"),
+    ("Retirer la Photo", "Remove the Photo"),
+    ("📷 Ajouter une Photo", "📷 Add a Photo"),
+    ("Photo de {} (PNG ou JPEG)", "Photo of {} (PNG or JPEG)"),
+    ("Image", "Image"),
+    // Substitution
+    ("Substitution d'Ingrédient", "Ingredient Substitution"),
+    ("Ingrédient à remplacer :", "Ingredient to replace:"),
+    ("Facteur de quantité :", "Quantity factor:"),
+    (
+        "Nouvelle quantité = ancienne quantité × facteur, dans la même unité si le nouvel ingrédient s'y convertit, sinon dans la sienne",
+        "New quantity = former quantity × factor, in the same unit if the new ingredient converts to it, otherwise in its own",
+    ),
+    (
+        "Le facteur les convertit dans l'unité du nouvel ingrédient.",
+        "The factor converts them to the unit of the new ingredient.",
+    ),
+    (
+        "Supprimer l'ingrédient remplacé du catalogue",
+        "Delete the replaced ingredient from the catalog",
+    ),
+    (
+        "Choisissez deux ingrédients différents.",
+        "Choose two different ingredients.",
+    ),
+    (
+        "Aucune recette n'utilise cet ingrédient.",
+        "No recipe uses this ingredient.",
+    ),
+    (
+        "Le stock de l'ingrédient est valorisé au nouveau prix, comme s'il avait été acheté à ce prix.",
+        "The ingredient's stock is valued at the new price, as if it had been bought at that price.",
+    ),
+    ("Coût avant", "Cost before"),
+    ("Coût après", "Cost after"),
+    ("Écart", "Difference"),
+    ("Taux de marge après", "Margin rate after"),
+    ("Appliquer la Substitution", "Apply the Substitution"),
+    // Costing method
+    ("Valorisation des ingrédients :", "Ingredient costing:"),
+    (
+        "Coût ingrédients par lot selon chaque méthode (écart par rapport à la méthode choisie) :",
+        "Ingredient cost per batch with each method (difference from the chosen method):",
+    ),
+    ("Dernier Prix", "Last Price"),
+    ("Prix Moyen Pondéré", "Weighted Average Price"),
+    ("FIFO", "FIFO"),
+    ("Arrondi au centime :", "Rounding to the cent:"),
+    ("Par ligne", "Per line"),
+    ("Sur le total", "On the total"),
+    (
+        "Par ligne : chaque composant est arrondi avant d'être additionné, comme sur une facture",
+        "Per line: each component is rounded before being added up, as on an invoice",
+    ),
+    // Waste
+    ("Lot de Recette", "Recipe Batch"),
+    ("Article :", "Item:"),
+    ("Quantité :", "Quantity:"),
+    ("Motif :", "Reason:"),
+    ("Date :", "Date:"),
+    ("Enregistrer la Perte", "Record the Loss"),
+    ("Sélectionner un article", "Select an item"),
+    ("Date invalide (AAAA-MM-JJ)", "Invalid date (YYYY-MM-DD)"),
+    (
+        "Unité incompatible avec celle de l'article",
+        "Unit incompatible with the item's unit",
+    ),
+    ("Total des Pertes : {}", "Total Losses: {}"),
+    ("Par Motif", "By Reason"),
+    ("Par Type d'Ingrédient", "By Ingredient Type"),
+    ("Main-d'œuvre", "Labor"),
+    ("Par Semaine", "By Week"),
+    ("{} — semaine {}", "{} — week {}"),
+    ("Journal", "Log"),
+    ("DLC dépassée", "Past use-by date"),
+    ("Avarié", "Spoiled"),
+    ("Brûlé / Raté", "Burnt / Failed"),
+    ("Surproduction", "Overproduction"),
+    ("Endommagé", "Damaged"),
+    // History
+    ("Ajout de {}", "Addition of {}"),
+    ("Suppression de {}", "Deletion of {}"),
+    ("Modification de {}", "Edit of {}"),
+    ("Remplacement de {} par {}", "Replacement of {} with {}"),
+    ("Substitution de {} par {}", "Substitution of {} with {}"),
+    ("Duplication de {}", "Duplication of {}"),
+    ("{} (copie)", "{} (copy)"),
+    ("{} (copie {})", "{} (copy {})"),
+    (
+        "Modification de la méthode de {}",
+        "Edit of the method of {}",
+    ),
+    (
+        "Modification des allergènes de {}",
+        "Edit of the allergens of {}",
+    ),
+    ("Modification des paramètres", "Settings change"),
+    (
+        "Modification de la devise de {}",
+        "Edit of the currency of {}",
+    ),
+    ("Modification des taux de change", "Exchange rates change"),
+    ("Réception de {}", "Delivery from {}"),
+    ("Réception de {} lot(s)", "Receipt of {} lot(s)"),
+    ("Suppression de {} lot(s)", "Deletion of {} lot(s)"),
+    ("Archivage de {} perte(s)", "Archiving of {} loss(es)"),
+    ("Perte de {}", "Loss of {}"),
+    // Units
+    ("Gramme", "Gram"),
+    ("Kilogramme", "Kilogram"),
+    ("Milligramme", "Milligram"),
+    ("Litre", "Liter"),
+    ("Millilitre", "Milliliter"),
+    ("Centilitre", "Centiliter"),
+    ("Décilitre", "Deciliter"),
+    ("Pièce", "Piece"),
+    ("Tranche", "Slice"),
+    ("Cuillère à café", "Teaspoon"),
+    ("Cuillère à soupe", "Tablespoon"),
+    ("Tasse", "Cup"),
+    ("Autre", "Other"),
+    // Ingredient types
+    ("Épicerie", "Grocery"),
+    (
+        "Lait, Œufs, Fromages, Sauces",
+        "Dairy, Eggs, Cheese, Sauces",
+    ),
+    ("Légumes & Fruits", "Vegetables & Fruits"),
+    ("Packaging", "Packaging"),
+    ("Viandes / Protéines", "Meat / Proteins"),
+    // Allergens
+    ("Gluten", "Gluten"),
+    ("Crustacés", "Crustaceans"),
+    ("Œufs", "Eggs"),
+    ("Poissons", "Fish"),
+    ("Arachides", "Peanuts"),
+    ("Soja", "Soybeans"),
+    ("Lait", "Milk"),
+    ("Fruits à coque", "Tree nuts"),
+    ("Céleri", "Celery"),
+    ("Moutarde", "Mustard"),
+    ("Sésame", "Sesame"),
+    ("Sulfites", "Sulphites"),
+    ("Lupin", "Lupin"),
+    ("Mollusques", "Molluscs"),
+    // Command-line tool
+    (
+        "Utilisation : fnb [--lang fr|en] <catalogue.json> <commande>",
+        "Usage: fnb [--lang fr|en] <catalog.json> <command>",
+    ),
+    ("Commandes :", "Commands:"),
+    (
+        "ingrédients et recettes du catalogue",
+        "ingredients and recipes of the catalog",
+    ),
+    ("cost <recette>", "cost <recipe>"),
+    ("détail du coût d'une recette", "cost breakdown of a recipe"),
+    ("marges des plats vendus", "margins of the plates sold"),
+    (
+        "impact --ingredient <nom> --price <prix>",
+        "impact --ingredient <name> --price <price>",
+    ),
+    (
+        "effet d'un nouveau prix, dans la devise de l'ingrédient",
+        "effect of a new price, in the currency of the ingredient",
+    ),
+    ("coûts de toutes les recettes", "costs of every recipe"),
+    ("problèmes du catalogue", "issues of the catalog"),
+    (
+        "Code de sortie : 0 si tout va bien, 1 si le catalogue a des alertes rouges, 2 si la commande ou le fichier est invalide.",
+        "Exit code: 0 if all is well, 1 if the catalog has red alerts, 2 if the command or the file is invalid.",
+    ),
+    ("Option {} manquante", "Missing option {}"),
+    ("Valeur manquante après {}", "Missing value after {}"),
+    ("Nom de recette manquant", "Missing recipe name"),
+    ("Prix invalide : {}", "Invalid price: {}"),
+    ("Format inconnu : {}", "Unknown format: {}"),
+    ("Commande inconnue : {}", "Unknown command: {}"),
+    (
+        "Langue attendue après --lang : fr ou en",
+        "Language expected after --lang: fr or en",
+    ),
+    ("Recette inconnue : {}", "Unknown recipe: {}"),
+    ("Ingrédient inconnu : {}", "Unknown ingredient: {}"),
+    ("{} : ingrédient inconnu {}", "{}: unknown ingredient {}"),
+    ("{} : sous-recette inconnue {}", "{}: unknown sub-recipe {}"),
+    (
+        "Lot {} d'un ingrédient inconnu : {}",
+        "Lot {} of an unknown ingredient: {}",
+    ),
+    ("coût {}", "cost {}"),
+    ("vente {}", "price {}"),
+    ("Plat", "Plate"),
+    ("Statut", "Status"),
+    ("OK", "OK"),
+    ("À surveiller", "Watch"),
+    ("Alerte", "Alert"),
+    ("Aucun problème détecté.", "No issue found."),
+    // Catalog files
+    ("Sans titre", "Untitled"),
+    ("catalogue.json", "catalog.json"),
+    ("Modifications non enregistrées", "Unsaved Changes"),
+    (
+        "Enregistrer les modifications de {} ?",
+        "Save the changes to {}?",
+    ),
+    ("Ne pas enregistrer", "Don't Save"),
+];
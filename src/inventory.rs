@@ -0,0 +1,340 @@
+use chrono::NaiveDate;
+use poc_fnb::{Ingredient, QuantityUnit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::units;
+
+/// Differences below this amount (per unit) are treated as rounding, not as a dispute.
+const PRICE_TOLERANCE: f64 = 0.005;
+
+/// How receiving a delivery updates `Ingredient::cost_price`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum PriceUpdatePolicy {
+    /// The ingredient takes the price of the latest delivery.
+    #[default]
+    LastPrice,
+
+    /// The price is averaged with the stock on hand, weighted by quantity.
+    WeightedAverage,
+}
+
+/// A quantity of an ingredient received in one delivery line.
+#[derive(Clone, Debug)]
+pub struct StockLot {
+    pub ingredient: Rc<RefCell<Ingredient>>,
+    pub supplier: String,
+    pub lot_number: String,
+    pub received_on: NaiveDate,
+
+    /// Use-by date (DLC).
+    pub use_by: Option<NaiveDate>,
+
+    /// Price paid for one `Ingredient::unit`, in the currency of the ingredient's supplier.
+    pub unit_cost: f64,
+
+    /// Quantity received, in `Ingredient::unit`.
+    pub received_quantity: f64,
+
+    /// Quantity still in stock, in `Ingredient::unit`.
+    pub remaining_quantity: f64,
+}
+
+impl StockLot {
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.use_by.is_some_and(|use_by| use_by < today)
+    }
+}
+
+/// One line of a delivery note.
+#[derive(Debug)]
+pub struct DeliveryLine {
+    pub ingredient: Rc<RefCell<Ingredient>>,
+    pub quantity: f64,
+    pub unit: QuantityUnit,
+
+    /// Invoiced price for one `unit`.
+    pub unit_price: f64,
+
+    /// Price agreed on the purchase order for one `unit`, if the delivery has one.
+    pub ordered_unit_price: Option<f64>,
+
+    pub lot_number: String,
+    pub use_by: Option<NaiveDate>,
+}
+
+/// A delivery, either against a purchase order or ad-hoc.
+#[derive(Debug)]
+pub struct Delivery {
+    pub supplier: String,
+
+    /// Purchase order number, `None` for an ad-hoc delivery.
+    pub order_reference: Option<String>,
+
+    pub received_on: NaiveDate,
+    pub lines: Vec<DeliveryLine>,
+}
+
+/// An invoiced price that differs from the ordered one.
+#[derive(Clone, Debug)]
+pub struct PriceDiscrepancy {
+    pub ingredient_name: String,
+    pub order_reference: Option<String>,
+    pub lot_number: String,
+    pub quantity: f64,
+    pub unit: QuantityUnit,
+    pub ordered_unit_price: f64,
+    pub invoiced_unit_price: f64,
+}
+
+impl PriceDiscrepancy {
+    /// Difference per unit, positive when we were charged more than ordered.
+    pub fn unit_difference(&self) -> f64 {
+        self.invoiced_unit_price - self.ordered_unit_price
+    }
+
+    /// Difference over the whole delivered quantity.
+    pub fn total_difference(&self) -> f64 {
+        self.unit_difference() * self.quantity
+    }
+}
+
+/// Outcome of [`Inventory::receive`].
+#[derive(Clone, Debug, Default)]
+pub struct ReceivingReport {
+    pub received_lines: usize,
+    pub discrepancies: Vec<PriceDiscrepancy>,
+
+    /// Names of the ingredients whose line unit could not be converted to the ingredient unit.
+    pub rejected_lines: Vec<String>,
+}
+
+/// Stock on hand, kept as received lots.
+#[derive(Debug, Default)]
+pub struct Inventory {
+    pub lots: Vec<StockLot>,
+}
+
+impl Inventory {
+    pub fn lots_of<'a>(
+        &'a self,
+        ingredient: &'a Rc<RefCell<Ingredient>>,
+    ) -> impl Iterator<Item = &'a StockLot> + 'a {
+        self.lots
+            .iter()
+            .filter(move |lot| Rc::ptr_eq(&lot.ingredient, ingredient))
+    }
+
+    /// Quantity in stock, in `Ingredient::unit`.
+    pub fn stock_of(&self, ingredient: &Rc<RefCell<Ingredient>>) -> f64 {
+        self.lots_of(ingredient)
+            .map(|lot| lot.remaining_quantity)
+            .sum()
+    }
+
+    /// What taking `quantity` (in `Ingredient::unit`) out of stock takes from each lot, oldest
+    /// lots first: the position of the lot in `lots` and the quantity taken from it.
+    ///
+    /// Whatever is not in stock is left out.
+    pub fn consumption(
+        &self,
+        ingredient: &Rc<RefCell<Ingredient>>,
+        quantity: f64,
+    ) -> Vec<(usize, f64)> {
+        let mut lots: Vec<(usize, &StockLot)> = self
+            .lots
+            .iter()
+            .enumerate()
+            .filter(|(_, lot)| {
+                Rc::ptr_eq(&lot.ingredient, ingredient) && lot.remaining_quantity > 0.0
+            })
+            .collect();
+        lots.sort_by_key(|(_, lot)| lot.received_on);
+
+        let mut to_consume = quantity;
+        let mut consumption = Vec::new();
+        for (index, lot) in lots {
+            if to_consume <= 0.0 {
+                break;
+            }
+            let taken = to_consume.min(lot.remaining_quantity);
+            consumption.push((index, taken));
+            to_consume -= taken;
+        }
+        consumption
+    }
+
+    /// Adds the delivered quantities to stock and updates the ingredient prices.
+    pub fn receive(&mut self, delivery: Delivery, policy: PriceUpdatePolicy) -> ReceivingReport {
+        let mut report = ReceivingReport::default();
+
+        for line in delivery.lines {
+            let stock_before = self.stock_of(&line.ingredient);
+            let mut ingredient = line.ingredient.borrow_mut();
+
+            // Quantity and price expressed in the ingredient's own unit
+            let Some(quantity) = units::convert(line.quantity, &line.unit, &ingredient.unit) else {
+                report.rejected_lines.push(ingredient.name.clone());
+                continue;
+            };
+            if quantity <= 0.0 {
+                report.rejected_lines.push(ingredient.name.clone());
+                continue;
+            }
+            let unit_cost = line.unit_price * line.quantity / quantity;
+
+            if let Some(ordered_unit_price) = line.ordered_unit_price {
+                if (line.unit_price - ordered_unit_price).abs() > PRICE_TOLERANCE {
+                    report.discrepancies.push(PriceDiscrepancy {
+                        ingredient_name: ingredient.name.clone(),
+                        order_reference: delivery.order_reference.clone(),
+                        lot_number: line.lot_number.clone(),
+                        quantity: line.quantity,
+                        unit: line.unit.clone(),
+                        ordered_unit_price,
+                        invoiced_unit_price: line.unit_price,
+                    });
+                }
+            }
+
+            let averaged = policy == PriceUpdatePolicy::WeightedAverage
+                && stock_before > 0.0
+                && ingredient.reference_quantity > 0.0;
+            let new_unit_cost = if averaged {
+                let current_unit_cost = ingredient.cost_price / ingredient.reference_quantity;
+                (stock_before * current_unit_cost + quantity * unit_cost)
+                    / (stock_before + quantity)
+            } else {
+                unit_cost
+            };
+            ingredient.cost_price = new_unit_cost * ingredient.reference_quantity;
+            drop(ingredient);
+
+            self.lots.push(StockLot {
+                ingredient: line.ingredient,
+                supplier: delivery.supplier.clone(),
+                lot_number: line.lot_number,
+                received_on: delivery.received_on,
+                use_by: line.use_by,
+                unit_cost,
+                received_quantity: quantity,
+                remaining_quantity: quantity,
+            });
+            report.received_lines += 1;
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poc_fnb::{IngredientType, Supplier};
+
+    /// Flour at 1.00 per kilogram.
+    fn flour() -> Rc<RefCell<Ingredient>> {
+        Rc::new(RefCell::new(Ingredient {
+            name: "Farine".to_owned(),
+            cost_price: 10.0,
+            reference_quantity: 10.0,
+            unit: QuantityUnit::Kilogram,
+            ingredient_type: IngredientType::Grocery,
+            supplier: Supplier {
+                name: "Moulin".to_owned(),
+                contact: None,
+            },
+        }))
+    }
+
+    fn line(
+        ingredient: &Rc<RefCell<Ingredient>>,
+        quantity: f64,
+        unit: QuantityUnit,
+        unit_price: f64,
+        ordered_unit_price: Option<f64>,
+    ) -> DeliveryLine {
+        DeliveryLine {
+            ingredient: ingredient.clone(),
+            quantity,
+            unit,
+            unit_price,
+            ordered_unit_price,
+            lot_number: "L1".to_owned(),
+            use_by: None,
+        }
+    }
+
+    fn delivery(lines: Vec<DeliveryLine>) -> Delivery {
+        Delivery {
+            supplier: "Moulin".to_owned(),
+            order_reference: Some("PO-1".to_owned()),
+            received_on: NaiveDate::from_ymd_opt(2026, 1, 5).expect("valid date"),
+            lines,
+        }
+    }
+
+    /// Inventory holding 10 kg of `flour` bought at its current price.
+    fn stocked(flour: &Rc<RefCell<Ingredient>>) -> Inventory {
+        let mut inventory = Inventory::default();
+        let line = line(flour, 10.0, QuantityUnit::Kilogram, 1.0, None);
+        inventory.receive(delivery(vec![line]), PriceUpdatePolicy::LastPrice);
+        inventory
+    }
+
+    #[test]
+    fn weighted_average_blends_the_price_with_the_stock_on_hand() {
+        let flour = flour();
+        let mut inventory = stocked(&flour);
+        let line = line(&flour, 10.0, QuantityUnit::Kilogram, 2.0, None);
+        inventory.receive(delivery(vec![line]), PriceUpdatePolicy::WeightedAverage);
+        assert!((flour.borrow().cost_price - 15.0).abs() < 1e-9);
+        assert!((inventory.stock_of(&flour) - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn last_price_takes_the_price_of_the_delivery() {
+        let flour = flour();
+        let mut inventory = stocked(&flour);
+        let line = line(&flour, 10.0, QuantityUnit::Kilogram, 2.0, None);
+        inventory.receive(delivery(vec![line]), PriceUpdatePolicy::LastPrice);
+        assert!((flour.borrow().cost_price - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lines_are_converted_to_the_ingredient_unit() {
+        let flour = flour();
+        let mut inventory = Inventory::default();
+        let lines = vec![
+            line(&flour, 500.0, QuantityUnit::Gram, 0.002, None),
+            line(&flour, 1.0, QuantityUnit::Liter, 1.0, None),
+        ];
+        let report = inventory.receive(delivery(lines), PriceUpdatePolicy::LastPrice);
+
+        assert_eq!(report.received_lines, 1);
+        assert_eq!(report.rejected_lines, ["Farine"]);
+        let [lot] = inventory.lots.as_slice() else {
+            panic!("one lot should be received");
+        };
+        assert!((lot.received_quantity - 0.5).abs() < 1e-9);
+        assert!((lot.unit_cost - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn price_differences_within_the_tolerance_are_not_discrepancies() {
+        let flour = flour();
+        let mut inventory = Inventory::default();
+        let lines = vec![
+            line(&flour, 10.0, QuantityUnit::Kilogram, 1.004, Some(1.0)),
+            line(&flour, 10.0, QuantityUnit::Kilogram, 1.1, Some(1.0)),
+        ];
+        let report = inventory.receive(delivery(lines), PriceUpdatePolicy::LastPrice);
+
+        let [discrepancy] = report.discrepancies.as_slice() else {
+            panic!("only the second line should be disputed");
+        };
+        assert!((discrepancy.unit_difference() - 0.1).abs() < 1e-9);
+        assert!((discrepancy.total_difference() - 1.0).abs() < 1e-9);
+        assert_eq!(discrepancy.order_reference.as_deref(), Some("PO-1"));
+    }
+}
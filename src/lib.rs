@@ -1,4 +1,27 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod allergen;
 mod app;
+pub mod app_storage;
+pub mod catalog;
+pub mod cost_tree;
+pub mod costing;
+pub mod currency;
+pub mod dependencies;
+pub mod health;
+pub mod history;
+pub mod i18n;
+pub mod inventory;
+pub mod locale;
+pub mod money;
+pub mod pdf;
+pub mod persistence;
+pub mod production;
+pub mod recipe_method;
+pub mod report;
+pub mod substitution;
+pub mod unit_change;
+pub mod units;
+pub mod valuation;
+pub mod waste;
 pub use app::TemplateApp;
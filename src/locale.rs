@@ -0,0 +1,237 @@
+//! Numbers, amounts and percentages in the format of the current language, and parsing of
+//! the numbers typed by the user.
+
+use crate::currency::Currency;
+use crate::i18n::{self, Language};
+use crate::money::Money;
+
+/// Non-breaking space, so that "12,00 €" is never split across lines.
+const NBSP: char = '\u{A0}';
+
+/// Decimal and thousands separators of `language`.
+fn separators(language: Language) -> (char, char) {
+    match language {
+        Language::French => (',', NBSP),
+        Language::English => ('.', ','),
+    }
+}
+
+/// Replaces the decimal point of a number formatted by Rust with the separator of the
+/// current language.
+pub fn localize_decimal(text: &str) -> String {
+    let (decimal, _) = separators(i18n::language());
+    text.replace('.', &decimal.to_string())
+}
+
+/// `value` with `decimals` digits after the separator, grouped by thousands.
+pub fn number(value: f64, decimals: usize) -> String {
+    number_in(value, decimals, i18n::language())
+}
+
+fn number_in(value: f64, decimals: usize, language: Language) -> String {
+    localize_digits(
+        &format!("{:.*}", decimals, value.abs()),
+        value < 0.0,
+        language,
+    )
+}
+
+/// Digits such as "1234.50" with the separators of `language`, and a minus sign when
+/// `negative`.
+fn localize_digits(text: &str, negative: bool, language: Language) -> String {
+    let (decimal, thousands) = separators(language);
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+
+    let mut grouped = String::new();
+    for (idx, digit) in integer.chars().enumerate() {
+        if idx > 0 && (integer.len() - idx) % 3 == 0 {
+            grouped.push(thousands);
+        }
+        grouped.push(digit);
+    }
+    if !fraction.is_empty() {
+        grouped.push(decimal);
+        grouped.push_str(fraction);
+    }
+
+    // No "-0,00" for amounts that round to zero
+    let is_zero = text.chars().all(|c| c == '0' || c == '.');
+    if negative && !is_zero {
+        format!("-{grouped}")
+    } else {
+        grouped
+    }
+}
+
+/// `number` with an explicit sign, for differences.
+pub fn signed_number(value: f64, decimals: usize) -> String {
+    with_sign(number(value, decimals))
+}
+
+fn with_sign(text: String) -> String {
+    if text.starts_with('-') || text.chars().all(|c| !c.is_ascii_digit() || c == '0') {
+        text
+    } else {
+        format!("+{text}")
+    }
+}
+
+/// What goes before and after the number of an amount in `currency`: "€12.00" in English,
+/// "12,00 €" in French, and a space after letter symbols such as "CHF 12.00".
+pub fn money_affixes(currency: Currency) -> (String, String) {
+    let symbol = currency.symbol();
+    match i18n::language() {
+        Language::French => (String::new(), format!("{NBSP}{symbol}")),
+        Language::English if symbol.chars().all(char::is_alphabetic) => {
+            (format!("{symbol}{NBSP}"), String::new())
+        }
+        Language::English => (symbol.to_owned(), String::new()),
+    }
+}
+
+fn with_affixes(number: &str, currency: Currency) -> String {
+    let (prefix, suffix) = money_affixes(currency);
+    match number.strip_prefix(['-', '+']) {
+        Some(digits) => format!("{}{prefix}{digits}{suffix}", &number[..1]),
+        None => format!("{prefix}{number}{suffix}"),
+    }
+}
+
+/// `amount` rounded to `decimals` digits, without currency.
+fn money_number(amount: Money, decimals: u32) -> String {
+    localize_digits(
+        &amount.abs_digits(decimals),
+        amount.is_negative(),
+        i18n::language(),
+    )
+}
+
+/// An amount rounded to the cent.
+pub fn money(amount: impl Into<Money>, currency: Currency) -> String {
+    with_affixes(&money_number(amount.into(), 2), currency)
+}
+
+/// The cost of one unit, with the precision small unit costs need.
+pub fn unit_money(amount: impl Into<Money>, currency: Currency) -> String {
+    with_affixes(&money_number(amount.into(), 4), currency)
+}
+
+/// A difference of amounts, with its sign.
+pub fn signed_money(amount: impl Into<Money>, currency: Currency) -> String {
+    with_affixes(&with_sign(money_number(amount.into(), 2)), currency)
+}
+
+/// `percent` (already multiplied by 100) with `decimals` digits: "12,5 %" or "12.5%".
+pub fn percent(percent: f64, decimals: usize) -> String {
+    let number = number(percent, decimals);
+    match i18n::language() {
+        Language::French => format!("{number}{NBSP}%"),
+        Language::English => format!("{number}%"),
+    }
+}
+
+/// A number typed by the user, with the separators of the current language: "1 234,5" in
+/// French and "1,234.5" in English. Spaces and apostrophes may group thousands in both.
+///
+/// French also takes a dot as decimal separator, since it never groups thousands there.
+/// Input that could be read two ways, such as "1,5" in English, or "1,234" and "1.234,5" in
+/// French, is rejected rather than guessed.
+pub fn parse_number(text: &str) -> Option<f64> {
+    parse_number_in(text, i18n::language())
+}
+
+fn parse_number_in(text: &str, language: Language) -> Option<f64> {
+    let text: String = text
+        .trim()
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\'')
+        .collect();
+    let normalized = match language {
+        Language::French if text.contains(',') && text.contains('.') => return None,
+        // Read as a thousand and more where commas group thousands
+        Language::French if is_grouped_by_commas(&text) => return None,
+        Language::French => text.replace(',', "."),
+        Language::English => without_thousands_commas(&text)?,
+    };
+    normalized.parse().ok()
+}
+
+/// Whether `text` could be a number with thousands grouped by commas, such as "1,234" or
+/// "-12,345". "0,125" could not.
+fn is_grouped_by_commas(text: &str) -> bool {
+    let digits = text.trim_start_matches(['-', '+']);
+    text.contains(',') && !digits.starts_with('0') && without_thousands_commas(text).is_some()
+}
+
+/// `text` without its thousands commas, if the integer part is grouped by three digits.
+fn without_thousands_commas(text: &str) -> Option<String> {
+    let (integer, fraction) = text.split_at(text.find('.').unwrap_or(text.len()));
+    let mut groups = integer.split(',');
+    let first = groups.next().unwrap_or_default();
+    let grouped = groups.all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit()));
+    let leading_digits = first.trim_start_matches(['-', '+']).len();
+    let valid = grouped
+        && !fraction.contains(',')
+        && (!integer.contains(',') || (1..=3).contains(&leading_digits));
+    valid.then(|| format!("{}{fraction}", integer.replace(',', "")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn french_numbers() {
+        let parse = |text| parse_number_in(text, Language::French);
+        assert_eq!(parse("1,5"), Some(1.5));
+        assert_eq!(parse("1.5"), Some(1.5));
+        assert_eq!(parse("1 234,5"), Some(1234.5));
+        assert_eq!(parse("1\u{A0}234,5"), Some(1234.5));
+        assert_eq!(parse("1'234,5"), Some(1234.5));
+        assert_eq!(parse("-0,125"), Some(-0.125));
+        assert_eq!(parse("1234,567"), Some(1234.567));
+        assert_eq!(parse("1,2345"), Some(1.2345));
+    }
+
+    #[test]
+    fn french_rejects_what_could_be_thousands_grouped_by_commas() {
+        let parse = |text| parse_number_in(text, Language::French);
+        assert_eq!(parse("1,234"), None);
+        assert_eq!(parse("-12,345"), None);
+        assert_eq!(parse("1.234,5"), None);
+        assert_eq!(parse("1,234.5"), None);
+    }
+
+    #[test]
+    fn english_numbers() {
+        let parse = |text| parse_number_in(text, Language::English);
+        assert_eq!(parse("1.5"), Some(1.5));
+        assert_eq!(parse("1,234.5"), Some(1234.5));
+        assert_eq!(parse("-12,345,678"), Some(-12_345_678.0));
+        assert_eq!(parse("1 234.5"), Some(1234.5));
+        assert_eq!(parse("1'234.5"), Some(1234.5));
+        assert_eq!(parse("1234"), Some(1234.0));
+    }
+
+    #[test]
+    fn english_rejects_commas_that_do_not_group_thousands() {
+        let parse = |text| parse_number_in(text, Language::English);
+        assert_eq!(parse("1,5"), None);
+        assert_eq!(parse("1234,567"), None);
+        assert_eq!(parse("1,23,456"), None);
+        assert_eq!(parse("1.5,0"), None);
+        assert_eq!(parse("abc"), None);
+    }
+
+    #[test]
+    fn formatted_numbers_parse_back() {
+        for language in Language::ALL {
+            for value in [0.0, 0.5, -3.25, 1234.5, -1_234_567.89, 1_000_000.0] {
+                let text = number_in(value, 2, language);
+                assert_eq!(parse_number_in(&text, language), Some(value), "{text}");
+            }
+        }
+        assert_eq!(number_in(-1234.5, 2, Language::French), "-1\u{A0}234,50");
+        assert_eq!(number_in(-1234.5, 2, Language::English), "-1,234.50");
+    }
+}
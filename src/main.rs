@@ -6,6 +6,12 @@
 fn main() -> eframe::Result {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    // Tells other programs, such as `fnb-server`, to leave the saved catalog alone
+    let _lock = eframe_todo_list::app_storage::AppStorage::locate()
+        .and_then(|storage| storage.lock())
+        .map_err(|err| log::warn!("{err}"))
+        .ok();
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 800.0])
@@ -18,7 +24,7 @@ fn main() -> eframe::Result {
         ..Default::default()
     };
     eframe::run_native(
-        "Gestion des Coûts F&B",
+        eframe_todo_list::app_storage::APP_ID,
         native_options,
         Box::new(|cc| Ok(Box::new(eframe_todo_list::TemplateApp::new(cc)))),
     )
@@ -0,0 +1,188 @@
+//! Exact amounts of money.
+//!
+//! `poc_fnb` stores prices as `f64`; costs computed from them are turned into [`Money`] per
+//! component, so that adding up many components does not drift by a cent, and rounded to
+//! the cent according to a [`Rounding`].
+
+use rust_decimal::prelude::{FromPrimitive as _, ToPrimitive as _};
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub};
+
+/// Digits kept by [`Money::round`].
+const CENT_DIGITS: u32 = 2;
+
+/// Where amounts are rounded to the cent when they are added up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Rounding {
+    /// Each line is rounded, and the total is the sum of the rounded lines, as on an invoice.
+    #[default]
+    PerLine,
+
+    /// Lines are added exactly and only the total is rounded.
+    OnTotal,
+}
+
+impl Rounding {
+    pub const ALL: [Self; 2] = [Self::PerLine, Self::OnTotal];
+
+    /// `line` as it is added to a total.
+    pub fn line(self, line: Money) -> Money {
+        match self {
+            Self::PerLine => line.round(),
+            Self::OnTotal => line,
+        }
+    }
+
+    /// Sum of `lines`, rounded to the cent.
+    pub fn total(self, lines: impl IntoIterator<Item = Money>) -> Money {
+        lines
+            .into_iter()
+            .map(|line| self.line(line))
+            .sum::<Money>()
+            .round()
+    }
+}
+
+/// An amount of money, exact in decimal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(Decimal);
+
+impl Money {
+    pub const ZERO: Self = Self(Decimal::ZERO);
+
+    /// The decimal closest to `amount`; `NaN` and infinite amounts count as zero.
+    pub fn from_f64(amount: f64) -> Self {
+        Self(Decimal::from_f64(amount).unwrap_or_default())
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    /// Rounded to the cent, halves away from zero.
+    pub fn round(self) -> Self {
+        self.round_to(CENT_DIGITS)
+    }
+
+    /// Rounded to `digits` decimals, halves away from zero.
+    pub fn round_to(self, digits: u32) -> Self {
+        Self(
+            self.0
+                .round_dp_with_strategy(digits, RoundingStrategy::MidpointAwayFromZero),
+        )
+    }
+
+    /// This amount for `quantity` units, not rounded.
+    pub fn times(self, quantity: f64) -> Self {
+        Self(self.0 * Decimal::from_f64(quantity).unwrap_or_default())
+    }
+
+    /// This amount shared over `quantity` units, not rounded; zero when `quantity` is not
+    /// positive.
+    pub fn per(self, quantity: f64) -> Self {
+        match Decimal::from_f64(quantity) {
+            Some(quantity) if quantity > Decimal::ZERO => Self(self.0 / quantity),
+            _ => Self::ZERO,
+        }
+    }
+
+    /// This amount as a fraction of `whole`, zero when `whole` is not positive.
+    pub fn ratio_to(self, whole: Self) -> f64 {
+        if whole.0 > Decimal::ZERO {
+            (self.0 / whole.0).to_f64().unwrap_or_default()
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Absolute value with `digits` decimals, such as "1234.50", for formatting.
+    pub fn abs_digits(self, digits: u32) -> String {
+        format!("{:.*}", digits as usize, self.round_to(digits).0.abs())
+    }
+}
+
+impl From<f64> for Money {
+    fn from(amount: f64) -> Self {
+        Self::from_f64(amount)
+    }
+}
+
+impl Add for Money {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Money {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(units: i64, scale: u32) -> Money {
+        Money(Decimal::new(units, scale))
+    }
+
+    #[test]
+    fn per_line_rounds_each_line_before_adding() {
+        let lines = [money(4, 3), money(4, 3), money(4, 3)];
+        assert_eq!(Rounding::PerLine.total(lines), Money::ZERO);
+    }
+
+    #[test]
+    fn on_total_rounds_the_exact_sum() {
+        let lines = [money(4, 3), money(4, 3), money(4, 3)];
+        assert_eq!(Rounding::OnTotal.total(lines), money(1, 2));
+    }
+
+    #[test]
+    fn modes_differ_when_lines_round_up() {
+        let lines = [money(125, 4), money(125, 4)];
+        assert_eq!(Rounding::PerLine.total(lines), money(2, 2));
+        assert_eq!(Rounding::OnTotal.total(lines), money(3, 2));
+    }
+
+    #[test]
+    fn halves_round_away_from_zero() {
+        assert_eq!(money(5, 3).round(), money(1, 2));
+        assert_eq!(money(-5, 3).round(), money(-1, 2));
+        assert_eq!(money(1_015, 3).round(), money(102, 2));
+    }
+}
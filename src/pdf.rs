@@ -0,0 +1,245 @@
+//! Minimal PDF writer: text in the standard Helvetica fonts, lines, filled rectangles
+//! and JPEG images.
+//!
+//! Enough for printable sheets without pulling a PDF library into the web build.
+
+use std::fmt::Write as _;
+
+/// A4 portrait, in points.
+pub const PAGE_WIDTH: f32 = 595.0;
+pub const PAGE_HEIGHT: f32 = 842.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource_name(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+        }
+    }
+}
+
+/// Approximate width of `text` in points; Helvetica averages about half an em per glyph.
+pub fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().count() as f32 * size * 0.5
+}
+
+/// Splits `text` into lines no wider than `max_width`, breaking on spaces.
+pub fn wrap_text(text: &str, size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && text_width(&format!("{line} {word}"), size) > max_width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Handle of an image added with [`PdfDocument::add_jpeg`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageId(usize);
+
+struct JpegImage {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+/// One page; coordinates are in points from the bottom-left corner.
+#[derive(Default)]
+pub struct Page {
+    content: Vec<u8>,
+}
+
+impl Page {
+    pub fn text(&mut self, x: f32, y: f32, size: f32, font: Font, text: &str) {
+        let mut ops = String::new();
+        write!(
+            ops,
+            "BT /{} {size:.1} Tf {x:.2} {y:.2} Td (",
+            font.resource_name()
+        )
+        .ok();
+        self.content.extend_from_slice(ops.as_bytes());
+        self.content.extend(encode_text(text));
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, width: f32) {
+        let ops = format!("{width:.2} w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S\n");
+        self.content.extend_from_slice(ops.as_bytes());
+    }
+
+    /// Filled rectangle; `gray` goes from 0 (black) to 1 (white).
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, gray: f32) {
+        let ops = format!("q {gray:.2} g {x:.2} {y:.2} {width:.2} {height:.2} re f Q\n");
+        self.content.extend_from_slice(ops.as_bytes());
+    }
+
+    /// Draws `image` stretched over the rectangle whose bottom-left corner is (`x`, `y`).
+    pub fn image(&mut self, image: ImageId, x: f32, y: f32, width: f32, height: f32) {
+        let ops = format!(
+            "q {width:.2} 0 0 {height:.2} {x:.2} {y:.2} cm /Im{} Do Q\n",
+            image.0
+        );
+        self.content.extend_from_slice(ops.as_bytes());
+    }
+}
+
+#[derive(Default)]
+pub struct PdfDocument {
+    pages: Vec<Page>,
+    images: Vec<JpegImage>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page::default());
+        let last = self.pages.len() - 1;
+        &mut self.pages[last]
+    }
+
+    pub fn last_page_mut(&mut self) -> Option<&mut Page> {
+        self.pages.last_mut()
+    }
+
+    /// Embeds an RGB JPEG of `width` × `height` pixels, drawable on any page.
+    pub fn add_jpeg(&mut self, width: u32, height: u32, data: Vec<u8>) -> ImageId {
+        self.images.push(JpegImage {
+            width,
+            height,
+            data,
+        });
+        ImageId(self.images.len() - 1)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Objects 1-4 are fixed, then each page takes a page object and a content stream,
+        // then come the images
+        let mut objects: Vec<Vec<u8>> = Vec::new();
+        let page_ids: Vec<usize> = (0..self.pages.len()).map(|idx| 5 + idx * 2).collect();
+        let kids: Vec<String> = page_ids.iter().map(|id| format!("{id} 0 R")).collect();
+        let first_image_id = 5 + self.pages.len() * 2;
+        let mut x_objects = String::new();
+        for idx in 0..self.images.len() {
+            write!(x_objects, "/Im{idx} {} 0 R ", first_image_id + idx).ok();
+        }
+
+        objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+        objects.push(
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                page_ids.len()
+            )
+            .into_bytes(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        );
+        objects.push(
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        );
+
+        for (page, page_id) in self.pages.iter().zip(&page_ids) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> /XObject << {x_objects}>> >> \
+                     /Contents {} 0 R >>",
+                    page_id + 1
+                )
+                .into_bytes(),
+            );
+            let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+            stream.extend_from_slice(&page.content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        for image in &self.images {
+            let mut stream = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                 /BitsPerComponent 8 /Filter /DCTDecode /Length {} >>\nstream\n",
+                image.width,
+                image.height,
+                image.data.len()
+            )
+            .into_bytes();
+            stream.extend_from_slice(&image.data);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut bytes = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(bytes.len());
+            bytes.extend_from_slice(format!("{} 0 obj\n", idx + 1).as_bytes());
+            bytes.extend_from_slice(object);
+            bytes.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = bytes.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            writeln!(xref, "{offset:010} 00000 n ").ok();
+        }
+        write!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .ok();
+        bytes.extend_from_slice(xref.as_bytes());
+        bytes
+    }
+}
+
+/// Encodes `text` as the body of a PDF string in `WinAnsiEncoding`, escaping the delimiters.
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        let byte = match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                c as u8
+            }
+            // WinAnsi matches Latin-1 for ASCII and the accented letters
+            ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+            '€' => 0x80,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            'Œ' => 0x8C,
+            'œ' => 0x9C,
+            _ => b'?',
+        };
+        bytes.push(byte);
+    }
+    bytes
+}
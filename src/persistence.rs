@@ -0,0 +1,670 @@
+//! Serializable form of a [`Catalog`].
+//!
+//! `poc_fnb` types are not serializable and recipes share ingredients through `Rc`,
+//! so the file format refers to ingredients and sub-recipes by name.
+
+use chrono::NaiveDate;
+use poc_fnb::{Ingredient, IngredientType, Plate, PlateComponent, QuantityUnit, Supplier};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+
+use crate::allergen::Allergen;
+use crate::catalog::{Catalog, CatalogSettings};
+use crate::currency::{Currency, ExchangeRates};
+use crate::health::{self, Issue};
+use crate::i18n::trf;
+use crate::inventory::StockLot;
+use crate::recipe_method::RecipeMethod;
+use crate::waste::{Loss, WasteEntry, WasteReason, WastedItem};
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct CatalogFile {
+    pub settings: CatalogSettings,
+    pub ingredients: Vec<IngredientRecord>,
+    pub plates: Vec<PlateRecord>,
+    pub stock_lots: Vec<StockLotRecord>,
+    pub waste: Vec<WasteRecord>,
+    pub exchange_rates: ExchangeRates,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct IngredientRecord {
+    pub name: String,
+    pub cost_price: f64,
+    pub reference_quantity: f64,
+    pub unit: String,
+    pub ingredient_type: String,
+    pub supplier: String,
+
+    #[serde(default)]
+    pub supplier_contact: Option<String>,
+
+    #[serde(default)]
+    pub allergens: BTreeSet<Allergen>,
+
+    /// Currency of the cost price, `None` for the catalog currency.
+    #[serde(default)]
+    pub currency: Option<Currency>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PlateRecord {
+    pub name: String,
+    pub components: Vec<ComponentRecord>,
+    pub selling_price: f64,
+    pub batch_preparation_time_hours: f64,
+    pub batch_quantity: f64,
+    pub batch_unit: String,
+
+    #[serde(default)]
+    pub method: RecipeMethod,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "kind")]
+pub enum ComponentRecord {
+    Ingredient {
+        ingredient: String,
+        quantity: f64,
+        unit: String,
+    },
+    Plate {
+        plate: String,
+        quantity: f64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct StockLotRecord {
+    pub ingredient: String,
+    pub supplier: String,
+    pub lot_number: String,
+    pub received_on: NaiveDate,
+    pub use_by: Option<NaiveDate>,
+    pub unit_cost: f64,
+    pub received_quantity: f64,
+    pub remaining_quantity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct WasteRecord {
+    /// Ingredient name, or plate name when `is_plate` is set.
+    pub item: String,
+    pub is_plate: bool,
+    pub quantity: f64,
+    pub unit: String,
+    pub reason: WasteReason,
+    pub date: NaiveDate,
+    pub value: f64,
+    pub value_by_type: Vec<(String, f64)>,
+}
+
+impl CatalogFile {
+    /// Reads a catalog file written by [`Self::to_json`].
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| err.to_string())
+    }
+
+    /// The catalog as an indented JSON document, the format of catalog files.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Stable identifier of a unit in catalog files.
+pub fn unit_code(unit: &QuantityUnit) -> &'static str {
+    match unit {
+        QuantityUnit::Gram => "g",
+        QuantityUnit::Kilogram => "kg",
+        QuantityUnit::Milligram => "mg",
+        QuantityUnit::Liter => "l",
+        QuantityUnit::Milliliter => "ml",
+        QuantityUnit::Centiliter => "cl",
+        QuantityUnit::Deciliter => "dl",
+        QuantityUnit::Piece => "piece",
+        QuantityUnit::Slice => "slice",
+        QuantityUnit::Teaspoon => "tsp",
+        QuantityUnit::Tablespoon => "tbsp",
+        QuantityUnit::Cup => "cup",
+        _ => "unit",
+    }
+}
+
+/// Unknown codes fall back to `QuantityUnit::Unit`.
+pub fn unit_from_code(code: &str) -> QuantityUnit {
+    match code {
+        "g" => QuantityUnit::Gram,
+        "kg" => QuantityUnit::Kilogram,
+        "mg" => QuantityUnit::Milligram,
+        "l" => QuantityUnit::Liter,
+        "ml" => QuantityUnit::Milliliter,
+        "cl" => QuantityUnit::Centiliter,
+        "dl" => QuantityUnit::Deciliter,
+        "piece" => QuantityUnit::Piece,
+        "slice" => QuantityUnit::Slice,
+        "tsp" => QuantityUnit::Teaspoon,
+        "tbsp" => QuantityUnit::Tablespoon,
+        "cup" => QuantityUnit::Cup,
+        _ => QuantityUnit::Unit,
+    }
+}
+
+/// Stable identifier of an ingredient type in catalog files.
+pub fn ingredient_type_code(ingredient_type: &IngredientType) -> &'static str {
+    match ingredient_type {
+        IngredientType::Grocery => "grocery",
+        IngredientType::DairyEggsCheeseSauces => "dairy",
+        IngredientType::VegetablesFruits => "vegetables_fruits",
+        IngredientType::Packaging => "packaging",
+        IngredientType::MeatProteins => "meat_proteins",
+    }
+}
+
+/// Unknown codes fall back to `IngredientType::Grocery`.
+pub fn ingredient_type_from_code(code: &str) -> IngredientType {
+    match code {
+        "dairy" => IngredientType::DairyEggsCheeseSauces,
+        "vegetables_fruits" => IngredientType::VegetablesFruits,
+        "packaging" => IngredientType::Packaging,
+        "meat_proteins" => IngredientType::MeatProteins,
+        _ => IngredientType::Grocery,
+    }
+}
+
+impl From<&Catalog> for CatalogFile {
+    fn from(catalog: &Catalog) -> Self {
+        let ingredients = catalog
+            .ingredients
+            .iter()
+            .map(|ingredient| {
+                let ingredient = ingredient.borrow();
+                IngredientRecord {
+                    name: ingredient.name.clone(),
+                    cost_price: ingredient.cost_price,
+                    reference_quantity: ingredient.reference_quantity,
+                    unit: unit_code(&ingredient.unit).to_owned(),
+                    ingredient_type: ingredient_type_code(&ingredient.ingredient_type).to_owned(),
+                    supplier: ingredient.supplier.name.clone(),
+                    supplier_contact: ingredient.supplier.contact.clone(),
+                    allergens: catalog
+                        .allergens
+                        .get(&ingredient.name)
+                        .cloned()
+                        .unwrap_or_default(),
+                    currency: catalog.ingredient_currencies.get(&ingredient.name).copied(),
+                }
+            })
+            .collect();
+
+        let plates = catalog
+            .plates
+            .iter()
+            .map(|plate| {
+                let plate = plate.borrow();
+                PlateRecord {
+                    name: plate.name.clone(),
+                    components: plate.components.iter().map(component_record).collect(),
+                    selling_price: plate.selling_price,
+                    batch_preparation_time_hours: plate.batch_preparation_time_hours,
+                    batch_quantity: plate.batch_quantity,
+                    batch_unit: unit_code(&plate.batch_unit).to_owned(),
+                    method: catalog
+                        .recipe_methods
+                        .get(&plate.name)
+                        .cloned()
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let stock_lots = catalog
+            .inventory
+            .lots
+            .iter()
+            .map(|lot| StockLotRecord {
+                ingredient: lot.ingredient.borrow().name.clone(),
+                supplier: lot.supplier.clone(),
+                lot_number: lot.lot_number.clone(),
+                received_on: lot.received_on,
+                use_by: lot.use_by,
+                unit_cost: lot.unit_cost,
+                received_quantity: lot.received_quantity,
+                remaining_quantity: lot.remaining_quantity,
+            })
+            .collect();
+
+        let waste = catalog
+            .waste_log
+            .entries
+            .iter()
+            .map(|entry| WasteRecord {
+                item: entry.loss.item.name(),
+                is_plate: entry.loss.item.is_plate(),
+                quantity: entry.loss.quantity,
+                unit: unit_code(&entry.loss.unit).to_owned(),
+                reason: entry.loss.reason,
+                date: entry.loss.date,
+                value: entry.value,
+                value_by_type: entry
+                    .value_by_type
+                    .iter()
+                    .map(|(ingredient_type, value)| {
+                        (ingredient_type_code(ingredient_type).to_owned(), *value)
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            settings: catalog.settings,
+            ingredients,
+            plates,
+            stock_lots,
+            waste,
+            exchange_rates: catalog.exchange_rates.clone(),
+        }
+    }
+}
+
+fn component_record(component: &PlateComponent) -> ComponentRecord {
+    match component {
+        PlateComponent::Ingredient {
+            ingredient,
+            quantity,
+            unit,
+        } => ComponentRecord::Ingredient {
+            ingredient: ingredient.borrow().name.clone(),
+            quantity: *quantity,
+            unit: unit_code(unit).to_owned(),
+        },
+        PlateComponent::Plate { plate, quantity } => ComponentRecord::Plate {
+            plate: plate.borrow().name.clone(),
+            quantity: *quantity,
+        },
+    }
+}
+
+impl TryFrom<&CatalogFile> for Catalog {
+    type Error = String;
+
+    /// Fails on recipes or stock lots referring to unknown ingredients or plates, and on
+    /// recipes containing themselves, which could not be costed, listing every problem on its
+    /// own line. Waste of unknown items is kept as waste of deleted items.
+    fn try_from(file: &CatalogFile) -> Result<Self, String> {
+        let mut catalog = Self {
+            settings: file.settings,
+            exchange_rates: file.exchange_rates.clone(),
+            ..Default::default()
+        };
+
+        for record in &file.ingredients {
+            if !record.allergens.is_empty() {
+                catalog
+                    .allergens
+                    .insert(record.name.clone(), record.allergens.clone());
+            }
+            if let Some(currency) = record.currency {
+                catalog
+                    .ingredient_currencies
+                    .insert(record.name.clone(), currency);
+            }
+        }
+
+        catalog.ingredients = file
+            .ingredients
+            .iter()
+            .map(|record| {
+                Rc::new(RefCell::new(Ingredient {
+                    name: record.name.clone(),
+                    cost_price: record.cost_price,
+                    reference_quantity: record.reference_quantity,
+                    unit: unit_from_code(&record.unit),
+                    ingredient_type: ingredient_type_from_code(&record.ingredient_type),
+                    supplier: Supplier {
+                        name: record.supplier.clone(),
+                        contact: record.supplier_contact.clone(),
+                    },
+                }))
+            })
+            .collect();
+
+        // Plates first, components second: sub-recipes may be listed after the plates using them
+        catalog.plates = file
+            .plates
+            .iter()
+            .map(|record| {
+                Rc::new(RefCell::new(Plate {
+                    name: record.name.clone(),
+                    components: Vec::new(),
+                    selling_price: record.selling_price,
+                    batch_preparation_time_hours: record.batch_preparation_time_hours,
+                    batch_quantity: record.batch_quantity,
+                    batch_unit: unit_from_code(&record.batch_unit),
+                }))
+            })
+            .collect();
+
+        let mut problems = Vec::new();
+        for (record, plate) in file.plates.iter().zip(&catalog.plates) {
+            let components = record
+                .components
+                .iter()
+                .filter_map(|component| {
+                    catalog
+                        .component_from_record(&record.name, component)
+                        .map_err(|problem| problems.push(problem))
+                        .ok()
+                })
+                .collect();
+            plate.borrow_mut().components = components;
+
+            if !record.method.is_empty() {
+                catalog
+                    .recipe_methods
+                    .insert(record.name.clone(), record.method.clone());
+            }
+        }
+
+        let lots: Vec<StockLot> = file
+            .stock_lots
+            .iter()
+            .filter_map(|record| {
+                catalog
+                    .stock_lot_from_record(record)
+                    .map_err(|problem| problems.push(problem))
+                    .ok()
+            })
+            .collect();
+        catalog.inventory.lots.extend(lots);
+
+        let waste: Vec<WasteEntry> = file
+            .waste
+            .iter()
+            .map(|record| catalog.waste_entry_from_record(record))
+            .collect();
+        catalog.waste_log.entries.extend(waste);
+
+        problems.extend(health::cycles(&catalog).iter().map(Issue::message));
+        if problems.is_empty() {
+            return Ok(catalog);
+        }
+        // Plates in a cycle refer to each other and would never be freed
+        for plate in &catalog.plates {
+            plate.borrow_mut().components.clear();
+        }
+        Err(problems.join("\n"))
+    }
+}
+
+impl Catalog {
+    fn component_from_record(
+        &self,
+        plate_name: &str,
+        record: &ComponentRecord,
+    ) -> Result<PlateComponent, String> {
+        match record {
+            ComponentRecord::Ingredient {
+                ingredient,
+                quantity,
+                unit,
+            } => {
+                let found = self
+                    .find_ingredient(ingredient)
+                    .ok_or_else(|| trf("{} : ingrédient inconnu {}", &[&plate_name, ingredient]))?;
+                Ok(PlateComponent::Ingredient {
+                    ingredient: found.clone(),
+                    quantity: *quantity,
+                    unit: unit_from_code(unit),
+                })
+            }
+            ComponentRecord::Plate { plate, quantity } => {
+                let found = self
+                    .find_plate(plate)
+                    .ok_or_else(|| trf("{} : sous-recette inconnue {}", &[&plate_name, plate]))?;
+                Ok(PlateComponent::Plate {
+                    plate: found.clone(),
+                    quantity: *quantity,
+                })
+            }
+        }
+    }
+
+    fn stock_lot_from_record(&self, record: &StockLotRecord) -> Result<StockLot, String> {
+        let ingredient = self.find_ingredient(&record.ingredient).ok_or_else(|| {
+            trf(
+                "Lot {} d'un ingrédient inconnu : {}",
+                &[&record.lot_number, &record.ingredient],
+            )
+        })?;
+        Ok(StockLot {
+            ingredient: ingredient.clone(),
+            supplier: record.supplier.clone(),
+            lot_number: record.lot_number.clone(),
+            received_on: record.received_on,
+            use_by: record.use_by,
+            unit_cost: record.unit_cost,
+            received_quantity: record.received_quantity,
+            remaining_quantity: record.remaining_quantity,
+        })
+    }
+
+    /// Entry of `record`, on an item deleted since if the catalog has none of that name.
+    fn waste_entry_from_record(&self, record: &WasteRecord) -> WasteEntry {
+        let item = if record.is_plate {
+            self.find_plate(&record.item)
+                .cloned()
+                .map(WastedItem::Plate)
+        } else {
+            self.find_ingredient(&record.item)
+                .cloned()
+                .map(WastedItem::Ingredient)
+        };
+        let item = item.unwrap_or_else(|| WastedItem::Deleted {
+            name: record.item.clone(),
+            is_plate: record.is_plate,
+        });
+        WasteEntry {
+            loss: Loss {
+                item,
+                quantity: record.quantity,
+                unit: unit_from_code(&record.unit),
+                reason: record.reason,
+                date: record.date,
+            },
+            value: record.value,
+            value_by_type: record
+                .value_by_type
+                .iter()
+                .map(|(code, value)| (ingredient_type_from_code(code), *value))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CATALOG: &str = r#"{
+    "settings": {
+        "currency": "Chf",
+        "rounding": "OnTotal"
+    },
+    "ingredients": [
+        {
+            "name": "Farine",
+            "cost_price": 1.2,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "grocery",
+            "supplier": "Moulin",
+            "supplier_contact": "a@b",
+            "allergens": [
+                "Gluten"
+            ]
+        },
+        {
+            "name": "Mozzarella",
+            "cost_price": 9.5,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "dairy",
+            "supplier": "Latte",
+            "currency": "Eur",
+            "allergens": [
+                "Milk"
+            ]
+        },
+        {
+            "name": "Sel",
+            "cost_price": 0.5,
+            "reference_quantity": 1,
+            "unit": "kg",
+            "ingredient_type": "grocery",
+            "supplier": "Moulin"
+        }
+    ],
+    "plates": [
+        {
+            "name": "Pizza",
+            "components": [
+                {
+                    "kind": "Plate",
+                    "plate": "Pâte",
+                    "quantity": 1
+                },
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Mozzarella",
+                    "quantity": 125,
+                    "unit": "g"
+                }
+            ],
+            "selling_price": 12,
+            "batch_preparation_time_hours": 0.1,
+            "batch_quantity": 1,
+            "batch_unit": "piece",
+            "method": {
+                "steps": [],
+                "chef_notes": "chaud",
+                "photo": null
+            }
+        },
+        {
+            "name": "Pâte",
+            "components": [
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Farine",
+                    "quantity": 500,
+                    "unit": "g"
+                },
+                {
+                    "kind": "Ingredient",
+                    "ingredient": "Sel",
+                    "quantity": 10,
+                    "unit": "g"
+                }
+            ],
+            "selling_price": 0,
+            "batch_preparation_time_hours": 0.5,
+            "batch_quantity": 4,
+            "batch_unit": "piece"
+        }
+    ],
+    "stock_lots": [
+        {
+            "ingredient": "Farine",
+            "supplier": "Moulin",
+            "lot_number": "L1",
+            "received_on": "2026-01-02",
+            "use_by": null,
+            "unit_cost": 1.1,
+            "received_quantity": 10,
+            "remaining_quantity": 4
+        }
+    ],
+    "waste": [
+        {
+            "item": "Pizza",
+            "is_plate": true,
+            "quantity": 1,
+            "unit": "piece",
+            "reason": "Burnt",
+            "date": "2026-02-03",
+            "value": 4.5,
+            "value_by_type": [
+                [
+                    "grocery",
+                    1.5
+                ]
+            ]
+        },
+        {
+            "item": "Ancienne Farine",
+            "is_plate": false,
+            "quantity": 2,
+            "unit": "kg",
+            "reason": "Expired",
+            "date": "2026-02-04",
+            "value": 2.2,
+            "value_by_type": [
+                [
+                    "grocery",
+                    2.2
+                ]
+            ]
+        }
+    ],
+    "exchange_rates": {
+        "rates": [
+            {
+                "from": "Eur",
+                "to": "Chf",
+                "effective_from": "2026-01-01",
+                "rate": 0.95
+            }
+        ]
+    }
+}"#;
+
+    #[test]
+    fn catalog_survives_a_round_trip() {
+        let file = CatalogFile::from_json(CATALOG).expect("valid JSON");
+        let catalog = Catalog::try_from(&file).expect("valid catalog");
+        assert_eq!(CatalogFile::from(&catalog), file);
+    }
+
+    #[test]
+    fn waste_of_deleted_items_is_kept_by_name() {
+        let file = CatalogFile::from_json(CATALOG).expect("valid JSON");
+        let catalog = Catalog::try_from(&file).expect("valid catalog");
+        let items: Vec<String> = catalog
+            .waste_log
+            .entries
+            .iter()
+            .map(|entry| entry.loss.item.name())
+            .collect();
+        assert_eq!(items, ["Pizza", "Ancienne Farine"]);
+        assert!(matches!(
+            catalog.waste_log.entries[1].loss.item,
+            WastedItem::Deleted {
+                is_plate: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn every_unknown_reference_is_reported() {
+        let mut file = CatalogFile::from_json(CATALOG).expect("valid JSON");
+        file.ingredients
+            .retain(|ingredient| ingredient.name != "Farine");
+        let problems = Catalog::try_from(&file).expect_err("Farine is used");
+        assert_eq!(problems.lines().count(), 2, "{problems}");
+        assert!(problems.contains("Pâte"));
+        assert!(problems.contains("L1"));
+    }
+}
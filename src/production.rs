@@ -0,0 +1,222 @@
+use poc_fnb::{Plate, PlateComponent};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::i18n::trf;
+
+/// Covers expected for one plate.
+#[derive(Clone, Debug)]
+pub struct Forecast {
+    pub plate: Rc<RefCell<Plate>>,
+
+    /// Number of portions, in `Plate::batch_unit`.
+    pub covers: f64,
+}
+
+/// One recipe to prepare.
+#[derive(Clone, Debug)]
+pub struct PrepItem {
+    pub plate: Rc<RefCell<Plate>>,
+
+    /// Quantity needed by the forecast and the recipes using it, in `Plate::batch_unit`.
+    pub required_quantity: f64,
+
+    /// Whole batches to make.
+    pub batches: u32,
+
+    pub labor_hours: f64,
+}
+
+/// Recipes to prepare, sub-recipes before the plates that use them.
+#[derive(Clone, Debug, Default)]
+pub struct PrepList {
+    pub items: Vec<PrepItem>,
+    pub total_labor_hours: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanningError {
+    /// The recipe contains itself, directly or through its sub-recipes.
+    Cycle(String),
+}
+
+impl std::fmt::Display for PlanningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cycle(name) => f.write_str(&trf("la recette {} se contient elle-même", &[name])),
+        }
+    }
+}
+
+impl std::error::Error for PlanningError {}
+
+/// Computes the batches needed to serve `forecast`, sub-recipes included.
+pub fn plan_production(forecast: &[Forecast]) -> Result<PrepList, PlanningError> {
+    let mut order = Vec::new();
+    let mut stack = Vec::new();
+    for entry in forecast.iter().filter(|entry| entry.covers > 0.0) {
+        visit(&entry.plate, &mut order, &mut stack)?;
+    }
+
+    let mut required = vec![0.0; order.len()];
+    for entry in forecast.iter().filter(|entry| entry.covers > 0.0) {
+        if let Some(idx) = position(&order, &entry.plate) {
+            required[idx] += entry.covers;
+        }
+    }
+
+    // Parents come after their sub-recipes in `order`, so walking it backwards
+    // settles each plate's batches before adding its needs to the sub-recipes.
+    let mut batches = vec![0; order.len()];
+    for idx in (0..order.len()).rev() {
+        let plate = order[idx].borrow();
+        batches[idx] = batch_count(required[idx], plate.batch_quantity);
+        for component in &plate.components {
+            if let PlateComponent::Plate {
+                plate: sub_plate,
+                quantity,
+            } = component
+            {
+                if let Some(sub_idx) = position(&order, sub_plate) {
+                    required[sub_idx] += quantity * f64::from(batches[idx]);
+                }
+            }
+        }
+    }
+
+    let mut prep_list = PrepList::default();
+    for (idx, plate_rc) in order.into_iter().enumerate() {
+        if batches[idx] == 0 {
+            continue;
+        }
+        let labor_hours = f64::from(batches[idx]) * plate_rc.borrow().batch_preparation_time_hours;
+        prep_list.total_labor_hours += labor_hours;
+        prep_list.items.push(PrepItem {
+            plate: plate_rc,
+            required_quantity: required[idx],
+            batches: batches[idx],
+            labor_hours,
+        });
+    }
+    Ok(prep_list)
+}
+
+fn position(plates: &[Rc<RefCell<Plate>>], plate: &Rc<RefCell<Plate>>) -> Option<usize> {
+    plates.iter().position(|other| Rc::ptr_eq(other, plate))
+}
+
+/// Post-order walk: pushes the sub-recipes of `plate` to `order`, then `plate` itself.
+fn visit(
+    plate: &Rc<RefCell<Plate>>,
+    order: &mut Vec<Rc<RefCell<Plate>>>,
+    stack: &mut Vec<Rc<RefCell<Plate>>>,
+) -> Result<(), PlanningError> {
+    if position(order, plate).is_some() {
+        return Ok(());
+    }
+    if position(stack, plate).is_some() {
+        return Err(PlanningError::Cycle(plate.borrow().name.clone()));
+    }
+
+    stack.push(plate.clone());
+    for component in &plate.borrow().components {
+        if let PlateComponent::Plate {
+            plate: sub_plate, ..
+        } = component
+        {
+            visit(sub_plate, order, stack)?;
+        }
+    }
+    stack.pop();
+    order.push(plate.clone());
+    Ok(())
+}
+
+fn batch_count(required_quantity: f64, batch_quantity: f64) -> u32 {
+    if required_quantity <= 0.0 || batch_quantity <= 0.0 {
+        return 0;
+    }
+    // The epsilon keeps 4500 g / 1500 g from rounding up to 4 batches after float noise.
+    // Saturating float-to-int cast; a prep list never gets anywhere near `u32::MAX`.
+    (required_quantity / batch_quantity - 1e-9).ceil() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poc_fnb::QuantityUnit;
+
+    fn plate(
+        name: &str,
+        batch_quantity: f64,
+        sub_recipes: &[(&Rc<RefCell<Plate>>, f64)],
+    ) -> Rc<RefCell<Plate>> {
+        Rc::new(RefCell::new(Plate {
+            name: name.to_owned(),
+            components: sub_recipes
+                .iter()
+                .map(|(sub_plate, quantity)| PlateComponent::Plate {
+                    plate: Rc::clone(sub_plate),
+                    quantity: *quantity,
+                })
+                .collect(),
+            selling_price: 0.0,
+            batch_preparation_time_hours: 1.0,
+            batch_quantity,
+            batch_unit: QuantityUnit::Piece,
+        }))
+    }
+
+    fn names(prep_list: &PrepList) -> Vec<String> {
+        prep_list
+            .items
+            .iter()
+            .map(|item| item.plate.borrow().name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn sub_recipes_come_before_the_plates_using_them() {
+        let dough = plate("Pâte", 4.0, &[]);
+        let sauce = plate("Sauce", 10.0, &[]);
+        let pizza = plate("Pizza", 1.0, &[(&dough, 1.0), (&sauce, 1.0)]);
+        let calzone = plate("Calzone", 1.0, &[(&dough, 1.0)]);
+        let forecast = [
+            Forecast {
+                plate: pizza,
+                covers: 10.0,
+            },
+            Forecast {
+                plate: calzone,
+                covers: 2.0,
+            },
+        ];
+
+        let prep_list = plan_production(&forecast).expect("no cycle");
+        assert_eq!(names(&prep_list), ["Pâte", "Sauce", "Pizza", "Calzone"]);
+        // 12 portions of dough in batches of 4
+        assert_eq!(prep_list.items[0].batches, 3);
+        assert_eq!(prep_list.items[1].batches, 1);
+    }
+
+    #[test]
+    fn recipes_containing_themselves_are_refused() {
+        let dough = plate("Pâte", 1.0, &[]);
+        let pizza = plate("Pizza", 1.0, &[(&dough, 1.0)]);
+        dough.borrow_mut().components.push(PlateComponent::Plate {
+            plate: pizza.clone(),
+            quantity: 1.0,
+        });
+        let forecast = [Forecast {
+            plate: pizza.clone(),
+            covers: 1.0,
+        }];
+
+        assert_eq!(
+            plan_production(&forecast).map(|_| ()),
+            Err(PlanningError::Cycle("Pizza".to_owned()))
+        );
+        // Break the cycle so that the plates are freed
+        dough.borrow_mut().components.clear();
+    }
+}
@@ -0,0 +1,96 @@
+use std::io::Cursor;
+
+/// Photos are downscaled to fit this size (in pixels) when attached.
+const PHOTO_MAX_SIZE: u32 = 1024;
+
+/// One step of a preparation method.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct PrepStep {
+    pub text: String,
+    pub duration_minutes: Option<f64>,
+    pub temperature_celsius: Option<f64>,
+}
+
+/// A dish photo, stored as JPEG.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Photo {
+    pub file_name: String,
+    pub width: u32,
+    pub height: u32,
+
+    #[serde(with = "base64_bytes")]
+    pub jpeg: Vec<u8>,
+}
+
+impl Photo {
+    /// Decodes a PNG or JPEG file and re-encodes it as a JPEG no larger than 1024 px.
+    pub fn from_file(file_name: &str, bytes: &[u8]) -> Result<Self, String> {
+        let image = image::load_from_memory(bytes).map_err(|err| err.to_string())?;
+        let image = if image.width() > PHOTO_MAX_SIZE || image.height() > PHOTO_MAX_SIZE {
+            image.thumbnail(PHOTO_MAX_SIZE, PHOTO_MAX_SIZE)
+        } else {
+            image
+        };
+        let rgb = image.to_rgb8();
+
+        let mut jpeg = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, 85)
+            .encode_image(&rgb)
+            .map_err(|err| err.to_string())?;
+
+        Ok(Self {
+            file_name: file_name.to_owned(),
+            width: rgb.width(),
+            height: rgb.height(),
+            jpeg,
+        })
+    }
+
+    /// Size and decoded RGBA pixels, for display.
+    pub fn to_rgba(&self) -> Option<([usize; 2], Vec<u8>)> {
+        let image = image::load(Cursor::new(&self.jpeg), image::ImageFormat::Jpeg).ok()?;
+        let size = [image.width() as usize, image.height() as usize];
+        Some((size, image.to_rgba8().into_raw()))
+    }
+}
+
+/// How to prepare a plate: ordered steps, chef notes and a photo.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct RecipeMethod {
+    pub steps: Vec<PrepStep>,
+    pub chef_notes: String,
+    pub photo: Option<Photo>,
+}
+
+impl RecipeMethod {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty() && self.chef_notes.trim().is_empty() && self.photo.is_none()
+    }
+
+    /// Moves the step at `from` to position `to`, shifting the steps in between.
+    pub fn move_step(&mut self, from: usize, to: usize) {
+        if from < self.steps.len() && to < self.steps.len() {
+            let step = self.steps.remove(from);
+            self.steps.insert(to, step);
+        }
+    }
+}
+
+/// Stores bytes as a base64 string rather than a list of numbers.
+mod base64_bytes {
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD;
+
+    pub fn serialize<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let text: String = serde::Deserialize::deserialize(deserializer)?;
+        STANDARD.decode(text).map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,120 @@
+//! Costs of the plates in plain numbers, for exports and the HTTP API.
+//!
+//! Amounts are in the catalog currency, rounded to the cent like in the app; rates are
+//! fractions of the selling price.
+
+use poc_fnb::Plate;
+
+use crate::catalog::Catalog;
+use crate::cost_tree::{CostNode, CostNodeKind};
+use crate::costing::PlateCost;
+use crate::health::Status;
+use crate::persistence::unit_code;
+use crate::valuation::Valuation;
+
+/// Stable name of a status, such as "red".
+pub fn status_code(status: Status) -> &'static str {
+    match status {
+        Status::Green => "green",
+        Status::Amber => "amber",
+        Status::Red => "red",
+    }
+}
+
+/// Costs of one batch of a plate.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct PlateCostRecord {
+    pub plate: String,
+    pub batch_quantity: f64,
+    pub batch_unit: &'static str,
+    pub currency: &'static str,
+    pub ingredient_cost: f64,
+    pub labor_cost: f64,
+    pub total_cost: f64,
+    pub selling_price: f64,
+    pub gross_margin: f64,
+    pub margin_rate: f64,
+    pub food_cost_rate: f64,
+
+    /// `None` for plates that are not sold.
+    pub status: Option<&'static str>,
+}
+
+impl PlateCostRecord {
+    pub fn of(plate: &Plate, catalog: &Catalog, valuation: &Valuation<'_>) -> Self {
+        let cost = PlateCost::of(plate, valuation);
+        Self {
+            plate: plate.name.clone(),
+            batch_quantity: plate.batch_quantity,
+            batch_unit: unit_code(&plate.batch_unit),
+            currency: catalog.settings.currency.code(),
+            ingredient_cost: cost.ingredient_cost.to_f64(),
+            labor_cost: cost.labor_cost.to_f64(),
+            total_cost: cost.total_cost_price().to_f64(),
+            selling_price: cost.selling_price.to_f64(),
+            gross_margin: cost.gross_margin().to_f64(),
+            margin_rate: cost.margin_rate(),
+            food_cost_rate: cost.food_cost_rate(),
+            status: catalog
+                .settings
+                .margin_thresholds
+                .status(&cost)
+                .map(status_code),
+        }
+    }
+}
+
+/// Costs of every plate, in catalog order.
+pub fn plate_cost_records(catalog: &Catalog) -> Vec<PlateCostRecord> {
+    let valuation = catalog.valuation();
+    catalog
+        .plates
+        .iter()
+        .map(|plate| PlateCostRecord::of(&plate.borrow(), catalog, &valuation))
+        .collect()
+}
+
+/// A [`CostNode`] and its children.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct CostNodeRecord {
+    /// "plate", "ingredient" or "labor".
+    pub kind: &'static str,
+    pub name: String,
+    pub quantity: f64,
+
+    /// `None` for labor, counted in hours.
+    pub unit: Option<&'static str>,
+    pub unit_cost: f64,
+    pub cost: f64,
+
+    /// Share of the cost of the whole plate, in percent.
+    pub share: f64,
+    pub children: Vec<CostNodeRecord>,
+}
+
+impl CostNodeRecord {
+    /// The children of `tree`, the cost tree of a whole plate.
+    pub fn components(tree: &CostNode) -> Vec<Self> {
+        Self::children(tree, tree)
+    }
+
+    fn children(node: &CostNode, tree: &CostNode) -> Vec<Self> {
+        node.children
+            .iter()
+            .map(|child| Self {
+                kind: match child.kind {
+                    CostNodeKind::Plate => "plate",
+                    CostNodeKind::Ingredient => "ingredient",
+                    CostNodeKind::Labor => "labor",
+                },
+                name: child.name.clone(),
+                quantity: child.quantity,
+                unit: child.unit.as_ref().map(unit_code),
+                unit_cost: child.unit_cost.to_f64(),
+                cost: child.cost.to_f64(),
+                share: child.percent_of(tree.cost),
+                children: Self::children(child, tree),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,224 @@
+//! Swapping one ingredient for another in every recipe, e.g. when a product is discontinued.
+
+use poc_fnb::{Ingredient, Plate, PlateComponent};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::catalog::Catalog;
+use crate::costing::{self, PlateCost};
+use crate::dependencies;
+use crate::money::Money;
+use crate::unit_change::IncompatibleUse;
+use crate::units;
+use crate::valuation::Valuation;
+
+/// Replaces `ingredient` by `replacement`, scaling the quantities by `factor`.
+#[derive(Clone, Debug)]
+pub struct Substitution {
+    pub ingredient: Rc<RefCell<Ingredient>>,
+    pub replacement: Rc<RefCell<Ingredient>>,
+
+    /// Quantity of `replacement` used for one unit of `ingredient`. The unit is kept unless
+    /// it cannot be converted to the unit of `replacement`, which is then used instead.
+    pub factor: f64,
+}
+
+/// How a substitution changes the cost of a plate.
+#[derive(Clone, Debug)]
+pub struct SubstitutionImpact {
+    pub plate: Rc<RefCell<Plate>>,
+
+    /// Sub-recipes through which the plate uses the ingredient; empty for a direct use.
+    pub path: Vec<String>,
+    pub before: PlateCost,
+    pub after: PlateCost,
+}
+
+impl SubstitutionImpact {
+    /// Change of the batch cost; positive when the plate gets more expensive.
+    pub fn cost_delta(&self) -> Money {
+        self.after.total_cost_price() - self.before.total_cost_price()
+    }
+}
+
+impl Substitution {
+    /// The component using `replacement` instead, if `component` uses `ingredient`.
+    pub fn substitute(&self, component: &PlateComponent) -> Option<PlateComponent> {
+        match component {
+            PlateComponent::Ingredient {
+                ingredient,
+                quantity,
+                unit,
+            } if Rc::ptr_eq(ingredient, &self.ingredient) => {
+                // A quantity the replacement's unit cannot be converted from would be costed
+                // as if it were in that unit
+                let replacement_unit = self.replacement.borrow().unit.clone();
+                let unit = if units::convert(1.0, unit, &replacement_unit).is_some() {
+                    unit.clone()
+                } else {
+                    replacement_unit
+                };
+                Some(PlateComponent::Ingredient {
+                    ingredient: self.replacement.clone(),
+                    quantity: quantity * self.factor,
+                    unit,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Recipe quantities in a unit that the unit of `replacement` cannot be converted from,
+    /// which the substitution expresses in the unit of `replacement`.
+    pub fn incompatible_uses(&self, plates: &[Rc<RefCell<Plate>>]) -> Vec<IncompatibleUse> {
+        let mut uses = Vec::new();
+        for plate in plates {
+            for component in &plate.borrow().components {
+                let (
+                    PlateComponent::Ingredient { quantity, unit, .. },
+                    Some(PlateComponent::Ingredient {
+                        quantity: converted_quantity,
+                        unit: converted_unit,
+                        ..
+                    }),
+                ) = (component, self.substitute(component))
+                else {
+                    continue;
+                };
+                if converted_unit != *unit {
+                    uses.push(IncompatibleUse {
+                        plate: plate.clone(),
+                        quantity: *quantity,
+                        unit: unit.clone(),
+                        converted_quantity,
+                    });
+                }
+            }
+        }
+        uses
+    }
+
+    /// Cost change of every plate using the ingredient, directly or through sub-recipes,
+    /// without modifying them.
+    pub fn preview(&self, catalog: &Catalog, valuation: &Valuation<'_>) -> Vec<SubstitutionImpact> {
+        dependencies::ingredient_dependents(catalog, &self.ingredient)
+            .into_iter()
+            .map(|dependent| {
+                let plate = dependent.plate.borrow();
+                let before = PlateCost::of(&plate, valuation);
+                let after = PlateCost {
+                    ingredient_cost: self.ingredient_cost_after(&plate, valuation),
+                    ..before
+                };
+                drop(plate);
+                SubstitutionImpact {
+                    plate: dependent.plate,
+                    path: dependent.path,
+                    before,
+                    after,
+                }
+            })
+            .collect()
+    }
+
+    /// Mirrors `costing::ingredient_cost` as if the substitution were applied.
+    fn ingredient_cost_after(&self, plate: &Plate, valuation: &Valuation<'_>) -> Money {
+        valuation
+            .rounding()
+            .total(plate.components.iter().map(|component| {
+                match (self.substitute(component), component) {
+                    (Some(substituted), _) => costing::component_cost(&substituted, valuation),
+                    (
+                        None,
+                        PlateComponent::Plate {
+                            plate: sub_plate,
+                            quantity,
+                        },
+                    ) => self
+                        .batch_cost_per_unit_after(&sub_plate.borrow(), valuation)
+                        .times(*quantity),
+                    (None, PlateComponent::Ingredient { .. }) => {
+                        costing::component_cost(component, valuation)
+                    }
+                }
+            }))
+    }
+
+    fn batch_cost_per_unit_after(&self, plate: &Plate, valuation: &Valuation<'_>) -> Money {
+        let labor_cost = Money::from_f64(plate.labor_cost()).round();
+        (self.ingredient_cost_after(plate, valuation) + labor_cost).per(plate.batch_quantity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poc_fnb::{IngredientType, QuantityUnit, Supplier};
+
+    fn ingredient(name: &str, unit: QuantityUnit) -> Rc<RefCell<Ingredient>> {
+        Rc::new(RefCell::new(Ingredient {
+            name: name.to_owned(),
+            cost_price: 1.0,
+            reference_quantity: 1.0,
+            unit,
+            ingredient_type: IngredientType::DairyEggsCheeseSauces,
+            supplier: Supplier {
+                name: "Crèmerie".to_owned(),
+                contact: None,
+            },
+        }))
+    }
+
+    fn component_unit(component: &PlateComponent) -> (f64, QuantityUnit) {
+        match component {
+            PlateComponent::Ingredient { quantity, unit, .. } => (*quantity, unit.clone()),
+            PlateComponent::Plate { .. } => panic!("expected an ingredient"),
+        }
+    }
+
+    #[test]
+    fn quantities_move_to_the_replacement_unit_only_when_they_must() {
+        let eggs = ingredient("Œufs", QuantityUnit::Piece);
+        let substitution = Substitution {
+            ingredient: eggs.clone(),
+            replacement: ingredient("Œufs liquides", QuantityUnit::Liter),
+            factor: 0.05,
+        };
+        let in_pieces = PlateComponent::Ingredient {
+            ingredient: eggs.clone(),
+            quantity: 4.0,
+            unit: QuantityUnit::Piece,
+        };
+        let (quantity, unit) = component_unit(
+            &substitution
+                .substitute(&in_pieces)
+                .expect("uses the ingredient"),
+        );
+        assert!((quantity - 0.2).abs() < 1e-9);
+        assert_eq!(unit, QuantityUnit::Liter);
+
+        let in_milliliters = PlateComponent::Ingredient {
+            ingredient: eggs.clone(),
+            quantity: 100.0,
+            unit: QuantityUnit::Milliliter,
+        };
+        let (_, unit) = component_unit(
+            &substitution
+                .substitute(&in_milliliters)
+                .expect("uses the ingredient"),
+        );
+        assert_eq!(unit, QuantityUnit::Milliliter);
+
+        let plate = Rc::new(RefCell::new(Plate {
+            name: "Flan".to_owned(),
+            components: vec![in_pieces, in_milliliters],
+            selling_price: 5.0,
+            batch_preparation_time_hours: 0.0,
+            batch_quantity: 1.0,
+            batch_unit: QuantityUnit::Piece,
+        }));
+        let uses = substitution.incompatible_uses(&[plate]);
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].unit, QuantityUnit::Piece);
+    }
+}
@@ -0,0 +1,189 @@
+//! Changing the purchase unit of an ingredient that recipes already use.
+
+use poc_fnb::{Ingredient, Plate, PlateComponent, QuantityUnit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::units;
+
+/// Switches the purchase unit of `ingredient` from `from` to `to`.
+#[derive(Clone, Debug)]
+pub struct UnitChange {
+    pub ingredient: Rc<RefCell<Ingredient>>,
+    pub from: QuantityUnit,
+    pub to: QuantityUnit,
+
+    /// Quantity in `to` of one `from`, e.g. 1000 from kilograms to grams, or the weight of
+    /// one piece when going from pieces to grams.
+    pub factor: f64,
+}
+
+/// A recipe quantity that the new unit cannot be converted from.
+#[derive(Debug)]
+pub struct IncompatibleUse {
+    pub plate: Rc<RefCell<Plate>>,
+    pub quantity: f64,
+    pub unit: QuantityUnit,
+
+    /// The same quantity in the new unit, using the factor of the change.
+    pub converted_quantity: f64,
+}
+
+impl UnitChange {
+    /// Change from the current unit of `ingredient`, with the factor of the two units when
+    /// they convert to each other.
+    pub fn new(ingredient: &Rc<RefCell<Ingredient>>, to: QuantityUnit) -> Self {
+        let from = ingredient.borrow().unit.clone();
+        let factor = units::convert(1.0, &from, &to).unwrap_or(1.0);
+        Self {
+            ingredient: ingredient.clone(),
+            from,
+            to,
+            factor,
+        }
+    }
+
+    /// Whether the two units convert to each other on their own, e.g. kilograms and grams.
+    pub fn is_exact(&self) -> bool {
+        units::convert(1.0, &self.from, &self.to).is_some()
+    }
+
+    /// Reference quantity in the new unit for the same amount of product, so that the price
+    /// keeps its meaning.
+    pub fn reference_quantity(&self, reference_quantity: f64) -> f64 {
+        reference_quantity * self.factor
+    }
+
+    /// The component expressed in the new unit, if `component` uses the ingredient in a unit
+    /// that cannot be converted to it.
+    ///
+    /// Components in a compatible unit need no change: costing converts them.
+    pub fn convert(&self, component: &PlateComponent) -> Option<PlateComponent> {
+        match component {
+            PlateComponent::Ingredient {
+                ingredient,
+                quantity,
+                unit,
+            } if Rc::ptr_eq(ingredient, &self.ingredient)
+                && units::convert(1.0, unit, &self.to).is_none() =>
+            {
+                // Quantities that did not convert to the former unit were costed as if they did
+                let former = units::convert(*quantity, unit, &self.from).unwrap_or(*quantity);
+                Some(PlateComponent::Ingredient {
+                    ingredient: ingredient.clone(),
+                    quantity: former * self.factor,
+                    unit: self.to.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Recipe quantities that would be costed wrongly after the change unless converted.
+    pub fn incompatible_uses(&self, plates: &[Rc<RefCell<Plate>>]) -> Vec<IncompatibleUse> {
+        let mut uses = Vec::new();
+        for plate in plates {
+            for component in &plate.borrow().components {
+                let (
+                    PlateComponent::Ingredient { quantity, unit, .. },
+                    Some(PlateComponent::Ingredient {
+                        quantity: converted_quantity,
+                        ..
+                    }),
+                ) = (component, self.convert(component))
+                else {
+                    continue;
+                };
+                uses.push(IncompatibleUse {
+                    plate: plate.clone(),
+                    quantity: *quantity,
+                    unit: unit.clone(),
+                    converted_quantity,
+                });
+            }
+        }
+        uses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poc_fnb::{IngredientType, Supplier};
+
+    fn ingredient(unit: QuantityUnit) -> Rc<RefCell<Ingredient>> {
+        Rc::new(RefCell::new(Ingredient {
+            name: "Œufs".to_owned(),
+            cost_price: 3.0,
+            reference_quantity: 12.0,
+            unit,
+            ingredient_type: IngredientType::DairyEggsCheeseSauces,
+            supplier: Supplier {
+                name: "Ferme".to_owned(),
+                contact: None,
+            },
+        }))
+    }
+
+    fn uses(
+        ingredient: &Rc<RefCell<Ingredient>>,
+        quantity: f64,
+        unit: QuantityUnit,
+    ) -> PlateComponent {
+        PlateComponent::Ingredient {
+            ingredient: ingredient.clone(),
+            quantity,
+            unit,
+        }
+    }
+
+    #[test]
+    fn convertible_units_need_no_recipe_change() {
+        let change = UnitChange::new(&ingredient(QuantityUnit::Kilogram), QuantityUnit::Gram);
+        assert!(change.is_exact());
+        assert!((change.factor - 1000.0).abs() < 1e-9);
+        assert!((change.reference_quantity(2.0) - 2000.0).abs() < 1e-9);
+        let component = uses(&change.ingredient, 1.0, QuantityUnit::Kilogram);
+        assert!(change.convert(&component).is_none());
+    }
+
+    #[test]
+    fn quantities_in_other_units_are_converted_with_the_factor() {
+        let eggs = ingredient(QuantityUnit::Piece);
+        let mut change = UnitChange::new(&eggs, QuantityUnit::Gram);
+        assert!(!change.is_exact());
+        // Weight of one egg
+        change.factor = 60.0;
+
+        let Some(PlateComponent::Ingredient { quantity, unit, .. }) =
+            change.convert(&uses(&eggs, 4.0, QuantityUnit::Piece))
+        else {
+            panic!("pieces do not convert to grams");
+        };
+        assert!((quantity - 240.0).abs() < 1e-9);
+        assert_eq!(unit, QuantityUnit::Gram);
+        assert!(
+            change
+                .convert(&uses(&eggs, 100.0, QuantityUnit::Gram))
+                .is_none()
+        );
+
+        let plate = Rc::new(RefCell::new(Plate {
+            name: "Omelette".to_owned(),
+            components: vec![
+                uses(&eggs, 3.0, QuantityUnit::Piece),
+                uses(&eggs, 50.0, QuantityUnit::Gram),
+            ],
+            selling_price: 9.0,
+            batch_preparation_time_hours: 0.1,
+            batch_quantity: 1.0,
+            batch_unit: QuantityUnit::Piece,
+        }));
+        let incompatible = change.incompatible_uses(&[plate]);
+        let [only] = incompatible.as_slice() else {
+            panic!("only the quantity in pieces should be listed");
+        };
+        assert!((only.quantity - 3.0).abs() < 1e-9);
+        assert!((only.converted_quantity - 180.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,21 @@
+use poc_fnb::QuantityUnit;
+
+/// Converts `quantity` expressed in `from` into `to`.
+///
+/// Returns `None` when the two units cannot be converted (e.g. grams to pieces).
+pub fn convert(quantity: f64, from: &QuantityUnit, to: &QuantityUnit) -> Option<f64> {
+    if from == to {
+        return Some(quantity);
+    }
+    from.conversion_factor_to(to)
+        .map(|factor| quantity * factor)
+}
+
+/// Unit in which quantities of `unit` are compared: grams for masses, milliliters for
+/// volumes, and `unit` itself for counts.
+pub fn base_unit(unit: &QuantityUnit) -> QuantityUnit {
+    [QuantityUnit::Gram, QuantityUnit::Milliliter]
+        .into_iter()
+        .find(|base| convert(1.0, unit, base).is_some())
+        .unwrap_or_else(|| unit.clone())
+}
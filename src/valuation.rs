@@ -0,0 +1,203 @@
+use chrono::NaiveDate;
+use poc_fnb::Ingredient;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::currency::PriceConversion;
+use crate::inventory::{Inventory, StockLot};
+use crate::money::Rounding;
+
+/// How ingredient quantities are valued when costing recipes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum CostMethod {
+    /// `Ingredient::cost_price`, which each delivery updates according to the
+    /// [`PriceUpdatePolicy`](crate::inventory::PriceUpdatePolicy).
+    #[default]
+    LastPrice,
+
+    /// Average price of the lots in stock, weighted by their remaining quantity.
+    WeightedAverage,
+
+    /// Quantities are taken from the oldest lots first, at the price each lot was bought.
+    Fifo,
+}
+
+impl CostMethod {
+    pub const ALL: [Self; 3] = [Self::LastPrice, Self::WeightedAverage, Self::Fifo];
+}
+
+/// Values ingredient quantities according to a [`CostMethod`].
+///
+/// [`CostMethod::LastPrice`] uses `Ingredient::cost_price`, so a price typed by hand counts
+/// right away; the other methods value the received lots, and fall back to `cost_price` for
+/// ingredients without any. Costs are converted to the catalog currency, lots at the rate
+/// of the day they were received.
+pub struct Valuation<'a> {
+    inventory: &'a Inventory,
+    method: CostMethod,
+    conversion: PriceConversion<'a>,
+
+    /// How the costs of recipe components are rounded when added up.
+    rounding: Rounding,
+}
+
+/// A lot with its unit cost in the catalog currency.
+struct CostedLot {
+    received_on: NaiveDate,
+    unit_cost: f64,
+    received_quantity: f64,
+    remaining_quantity: f64,
+}
+
+impl<'a> Valuation<'a> {
+    pub fn new(
+        inventory: &'a Inventory,
+        method: CostMethod,
+        conversion: PriceConversion<'a>,
+        rounding: Rounding,
+    ) -> Self {
+        Self {
+            inventory,
+            method,
+            conversion,
+            rounding,
+        }
+    }
+
+    pub fn method(&self) -> CostMethod {
+        self.method
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Cost of `quantity` of `ingredient`, expressed in `Ingredient::unit`.
+    pub fn cost_of(&self, ingredient: &Rc<RefCell<Ingredient>>, quantity: f64) -> f64 {
+        let ingredient_ref = ingredient.borrow();
+        let name = &ingredient_ref.name;
+        let price_cost = || {
+            quantity
+                * self
+                    .conversion
+                    .convert(name, catalog_unit_cost(&ingredient_ref))
+        };
+        if self.method == CostMethod::LastPrice {
+            return price_cost();
+        }
+
+        let mut lots: Vec<CostedLot> = self
+            .inventory
+            .lots_of(ingredient)
+            .map(|lot| self.costed_lot(name, lot))
+            .collect();
+        if lots.is_empty() {
+            return price_cost();
+        }
+        // Oldest first; `sort_by_key` is stable so same-day lots keep their receiving order
+        lots.sort_by_key(|lot| lot.received_on);
+
+        if self.method == CostMethod::Fifo {
+            fifo_cost(&lots, quantity)
+        } else {
+            quantity * weighted_average_unit_cost(&lots)
+        }
+    }
+
+    /// Cost of one `Ingredient::unit` of `ingredient`.
+    pub fn unit_cost_of(&self, ingredient: &Rc<RefCell<Ingredient>>) -> f64 {
+        self.cost_of(ingredient, 1.0)
+    }
+
+    fn costed_lot(&self, ingredient_name: &str, lot: &StockLot) -> CostedLot {
+        CostedLot {
+            received_on: lot.received_on,
+            unit_cost: self
+                .conversion
+                .convert_on(ingredient_name, lot.unit_cost, lot.received_on),
+            received_quantity: lot.received_quantity,
+            remaining_quantity: lot.remaining_quantity,
+        }
+    }
+}
+
+fn catalog_unit_cost(ingredient: &Ingredient) -> f64 {
+    if ingredient.reference_quantity > 0.0 {
+        ingredient.cost_price / ingredient.reference_quantity
+    } else {
+        0.0
+    }
+}
+
+/// `lots` must be sorted oldest first and not be empty.
+fn last_unit_cost(lots: &[CostedLot]) -> f64 {
+    lots.last().map(|lot| lot.unit_cost).unwrap_or_default()
+}
+
+fn weighted_average_unit_cost(lots: &[CostedLot]) -> f64 {
+    let average = |quantity: fn(&CostedLot) -> f64| {
+        let total_quantity: f64 = lots.iter().map(quantity).sum();
+        (total_quantity > 0.0).then(|| {
+            lots.iter()
+                .map(|lot| quantity(lot) * lot.unit_cost)
+                .sum::<f64>()
+                / total_quantity
+        })
+    };
+
+    // Lots in stock first; once everything is consumed, the whole purchase history
+    average(|lot| lot.remaining_quantity)
+        .or_else(|| average(|lot| lot.received_quantity))
+        .unwrap_or_else(|| last_unit_cost(lots))
+}
+
+/// Consumes `quantity` from the oldest lots; anything beyond the stock is valued at the last price.
+fn fifo_cost(lots: &[CostedLot], quantity: f64) -> f64 {
+    let mut to_consume = quantity;
+    let mut cost = 0.0;
+    for lot in lots.iter().filter(|lot| lot.remaining_quantity > 0.0) {
+        if to_consume <= 0.0 {
+            break;
+        }
+        let taken = to_consume.min(lot.remaining_quantity);
+        cost += taken * lot.unit_cost;
+        to_consume -= taken;
+    }
+    if to_consume > 0.0 {
+        cost += to_consume * last_unit_cost(lots);
+    }
+    cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lot(day: u32, unit_cost: f64, remaining_quantity: f64) -> CostedLot {
+        CostedLot {
+            received_on: NaiveDate::from_ymd_opt(2026, 1, day).expect("valid date"),
+            unit_cost,
+            received_quantity: 5.0,
+            remaining_quantity,
+        }
+    }
+
+    #[test]
+    fn fifo_takes_the_oldest_lots_first() {
+        let lots = [lot(1, 1.0, 2.0), lot(2, 2.0, 3.0)];
+        assert!((fifo_cost(&lots, 4.0) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fifo_values_quantity_beyond_stock_at_last_price() {
+        let lots = [lot(1, 1.0, 2.0), lot(2, 2.0, 3.0)];
+        // 2 at 1.0 and 3 at 2.0 from the stock, then 5 more at 2.0
+        assert!((fifo_cost(&lots, 10.0) - 18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fifo_without_stock_left_uses_last_price() {
+        let lots = [lot(1, 1.0, 0.0), lot(2, 2.5, 0.0)];
+        assert!((fifo_cost(&lots, 4.0) - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,315 @@
+use chrono::{Datelike as _, NaiveDate};
+use poc_fnb::{Ingredient, IngredientType, Plate, QuantityUnit};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::costing::{self, add_to};
+use crate::money::Money;
+use crate::units;
+use crate::valuation::Valuation;
+
+/// Why something was thrown away.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum WasteReason {
+    /// Past its use-by date.
+    Expired,
+    Spoiled,
+    Burnt,
+    Overproduction,
+    Damaged,
+    Other,
+}
+
+impl WasteReason {
+    pub const ALL: [Self; 6] = [
+        Self::Expired,
+        Self::Spoiled,
+        Self::Burnt,
+        Self::Overproduction,
+        Self::Damaged,
+        Self::Other,
+    ];
+}
+
+/// What was thrown away: raw stock or a prepared batch.
+#[derive(Clone, Debug)]
+pub enum WastedItem {
+    Ingredient(Rc<RefCell<Ingredient>>),
+    Plate(Rc<RefCell<Plate>>),
+
+    /// An ingredient or plate deleted since, known only by its name so that the waste
+    /// history outlives it.
+    Deleted {
+        name: String,
+        is_plate: bool,
+    },
+}
+
+impl WastedItem {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Ingredient(ingredient) => ingredient.borrow().name.clone(),
+            Self::Plate(plate) => plate.borrow().name.clone(),
+            Self::Deleted { name, .. } => name.clone(),
+        }
+    }
+
+    pub const fn is_plate(&self) -> bool {
+        match self {
+            Self::Ingredient(_) => false,
+            Self::Plate(_) => true,
+            Self::Deleted { is_plate, .. } => *is_plate,
+        }
+    }
+
+    /// The same item once deleted from the catalog.
+    pub fn deleted(&self) -> Self {
+        Self::Deleted {
+            name: self.name(),
+            is_plate: self.is_plate(),
+        }
+    }
+}
+
+/// A loss as declared by the kitchen.
+#[derive(Clone, Debug)]
+pub struct Loss {
+    pub item: WastedItem,
+    pub quantity: f64,
+    pub unit: QuantityUnit,
+    pub reason: WasteReason,
+    pub date: NaiveDate,
+}
+
+impl Loss {
+    /// Ingredient this loss takes out of stock, with the quantity in its unit. Prepared
+    /// batches are not stocked.
+    pub fn stock_used(&self) -> Option<(&Rc<RefCell<Ingredient>>, f64)> {
+        match &self.item {
+            WastedItem::Ingredient(ingredient) => {
+                let quantity =
+                    units::convert(self.quantity, &self.unit, &ingredient.borrow().unit)?;
+                Some((ingredient, quantity))
+            }
+            WastedItem::Plate(_) | WastedItem::Deleted { .. } => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WasteEntry {
+    pub loss: Loss,
+
+    /// Cost of the loss when it was recorded.
+    pub value: f64,
+
+    /// Ingredient part of `value` split by type; the remainder is labor.
+    pub value_by_type: Vec<(IngredientType, f64)>,
+}
+
+impl WasteEntry {
+    /// Entry of `loss` valued at the current cost.
+    ///
+    /// The quantity is converted to the ingredient unit, or to the plate batch unit.
+    /// Returns `None` if the loss unit cannot be converted or the item was deleted.
+    pub fn of(loss: Loss, valuation: &Valuation<'_>) -> Option<Self> {
+        let Loss { quantity, unit, .. } = &loss;
+        let (value, value_by_type) = match &loss.item {
+            WastedItem::Ingredient(ingredient) => {
+                let (ingredient_unit, ingredient_type) = {
+                    let ingredient = ingredient.borrow();
+                    (ingredient.unit.clone(), ingredient.ingredient_type.clone())
+                };
+                let stock_quantity = units::convert(*quantity, unit, &ingredient_unit)?;
+                let value = valuation.cost_of(ingredient, stock_quantity);
+                let value = Money::from_f64(value).round().to_f64();
+                (value, vec![(ingredient_type, value)])
+            }
+            WastedItem::Plate(plate) => {
+                let plate = plate.borrow();
+                let batch_quantity = units::convert(*quantity, unit, &plate.batch_unit)?;
+                let value = costing::batch_cost_per_unit(&plate, valuation)
+                    .times(batch_quantity)
+                    .round()
+                    .to_f64();
+                let share = if plate.batch_quantity > 0.0 {
+                    batch_quantity / plate.batch_quantity
+                } else {
+                    0.0
+                };
+                let value_by_type = costing::ingredient_cost_by_type(&plate, valuation)
+                    .into_iter()
+                    .map(|(ingredient_type, cost)| (ingredient_type, cost * share))
+                    .collect();
+                (value, value_by_type)
+            }
+            WastedItem::Deleted { .. } => return None,
+        };
+
+        Some(Self {
+            loss,
+            value,
+            value_by_type,
+        })
+    }
+
+    /// Part of `value` that is prep labor of wasted batches.
+    pub fn labor_value(&self) -> f64 {
+        self.value
+            - self
+                .value_by_type
+                .iter()
+                .map(|(_, value)| value)
+                .sum::<f64>()
+    }
+}
+
+/// Waste totals, each list sorted by decreasing value.
+#[derive(Clone, Debug, Default)]
+pub struct WasteReport {
+    pub total: f64,
+    pub by_reason: Vec<(WasteReason, f64)>,
+    pub by_type: Vec<(IngredientType, f64)>,
+    pub labor: f64,
+
+    /// Keyed by ISO `(year, week)`, in chronological order.
+    pub by_week: BTreeMap<(i32, u32), f64>,
+}
+
+#[derive(Debug, Default)]
+pub struct WasteLog {
+    pub entries: Vec<WasteEntry>,
+}
+
+impl WasteLog {
+    pub fn report(&self) -> WasteReport {
+        let mut report = WasteReport::default();
+        for entry in &self.entries {
+            report.total += entry.value;
+            add_to(&mut report.by_reason, entry.loss.reason, entry.value);
+            for (ingredient_type, value) in &entry.value_by_type {
+                add_to(&mut report.by_type, ingredient_type.clone(), *value);
+            }
+            report.labor += entry.labor_value();
+
+            let week = entry.loss.date.iso_week();
+            *report
+                .by_week
+                .entry((week.year(), week.week()))
+                .or_default() += entry.value;
+        }
+
+        report.by_reason.sort_by(|a, b| b.1.total_cmp(&a.1));
+        report.by_type.sort_by(|a, b| b.1.total_cmp(&a.1));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::Catalog;
+    use poc_fnb::Supplier;
+
+    fn entry(
+        reason: WasteReason,
+        day: u32,
+        value: f64,
+        value_by_type: Vec<(IngredientType, f64)>,
+    ) -> WasteEntry {
+        WasteEntry {
+            loss: Loss {
+                item: WastedItem::Deleted {
+                    name: "Pâte".to_owned(),
+                    is_plate: true,
+                },
+                quantity: 1.0,
+                unit: QuantityUnit::Piece,
+                reason,
+                date: NaiveDate::from_ymd_opt(2026, 1, day).expect("valid date"),
+            },
+            value,
+            value_by_type,
+        }
+    }
+
+    #[test]
+    fn report_totals_by_reason_type_and_week() {
+        let log = WasteLog {
+            entries: vec![
+                entry(
+                    WasteReason::Burnt,
+                    5,
+                    10.0,
+                    vec![(IngredientType::Grocery, 4.0)],
+                ),
+                entry(
+                    WasteReason::Expired,
+                    6,
+                    3.0,
+                    vec![(IngredientType::DairyEggsCheeseSauces, 3.0)],
+                ),
+                entry(
+                    WasteReason::Burnt,
+                    12,
+                    2.0,
+                    vec![(IngredientType::Grocery, 2.0)],
+                ),
+            ],
+        };
+        let report = log.report();
+
+        assert!((report.total - 15.0).abs() < 1e-9);
+        assert_eq!(
+            report.by_reason,
+            [(WasteReason::Burnt, 12.0), (WasteReason::Expired, 3.0)]
+        );
+        assert_eq!(
+            report.by_type,
+            [
+                (IngredientType::Grocery, 6.0),
+                (IngredientType::DairyEggsCheeseSauces, 3.0)
+            ]
+        );
+        // Prep labor of the burnt batch
+        assert!((report.labor - 6.0).abs() < 1e-9);
+        // January 5th and 6th 2026 are in ISO week 2, the 12th in week 3
+        assert_eq!(
+            report.by_week.into_iter().collect::<Vec<_>>(),
+            [((2026, 2), 13.0), ((2026, 3), 2.0)]
+        );
+    }
+
+    #[test]
+    fn losses_are_valued_in_the_unit_of_the_ingredient() {
+        let butter = Rc::new(RefCell::new(Ingredient {
+            name: "Beurre".to_owned(),
+            cost_price: 8.0,
+            reference_quantity: 1.0,
+            unit: QuantityUnit::Kilogram,
+            ingredient_type: IngredientType::DairyEggsCheeseSauces,
+            supplier: Supplier {
+                name: "Crèmerie".to_owned(),
+                contact: None,
+            },
+        }));
+        let loss = |unit| Loss {
+            item: WastedItem::Ingredient(butter.clone()),
+            quantity: 250.0,
+            unit,
+            reason: WasteReason::Spoiled,
+            date: NaiveDate::from_ymd_opt(2026, 1, 5).expect("valid date"),
+        };
+        let catalog = Catalog::default();
+
+        let entry = WasteEntry::of(loss(QuantityUnit::Gram), &catalog.valuation())
+            .expect("grams convert to kilograms");
+        assert!((entry.value - 2.0).abs() < 1e-9);
+        assert_eq!(
+            entry.value_by_type,
+            [(IngredientType::DairyEggsCheeseSauces, 2.0)]
+        );
+        assert!(WasteEntry::of(loss(QuantityUnit::Liter), &catalog.valuation()).is_none());
+    }
+}